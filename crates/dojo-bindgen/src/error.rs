@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use cainome::parser::Error as CainomeError;
 use dojo_world::manifest::AbstractManifestError;
 use thiserror::Error;
@@ -14,6 +16,18 @@ pub enum Error {
     Format(String),
     #[error(transparent)]
     Manifest(#[from] AbstractManifestError),
+    #[error("Conflicting definitions for type `{0}`")]
+    TypeConflict(String),
+    #[error("Name collisions: {}", .0.join("; "))]
+    NameCollision(Vec<String>),
+    #[error("Unsupported types: {}", .0.join(", "))]
+    UnsupportedTypes(Vec<String>),
+    #[error("Post-generation hook failed on `{}`: {reason}", .path.display())]
+    Hook { path: PathBuf, reason: String },
+    #[error("Unknown plugin `{0}`")]
+    UnknownPlugin(String),
+    #[error("Generated file `{}` escapes the output directory", .0.display())]
+    OutputPathEscapes(PathBuf),
 }
 
 pub type BindgenResult<T, E = Error> = Result<T, E>;
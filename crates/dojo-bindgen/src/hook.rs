@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use crate::error::{BindgenResult, Error};
+
+/// Signature of a [`PostGenerationHook::Callback`]. It is given the path of the file, relative
+/// to the plugin output directory, and its content.
+pub type HookFn = dyn Fn(&Path, Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync;
+
+/// Transformation applied to every generated file before it is written, e.g. to run a formatter
+/// such as `dotnet csharpier` on the generated C#.
+#[derive(Clone)]
+pub enum PostGenerationHook {
+    /// Runs `program` with `args`, with the content of the file on its stdin. Its stdout replaces
+    /// the content of the file.
+    Command { program: String, args: Vec<String> },
+    /// Calls the function, its output replaces the content of the file.
+    Callback(Arc<HookFn>),
+}
+
+impl PostGenerationHook {
+    /// Creates a hook calling the given function.
+    pub fn callback<F>(f: F) -> Self
+    where
+        F: Fn(&Path, Vec<u8>) -> Result<Vec<u8>, String> + Send + Sync + 'static,
+    {
+        Self::Callback(Arc::new(f))
+    }
+
+    /// Applies the hook to the content of the file at `path`.
+    pub fn apply(&self, path: &Path, content: Vec<u8>) -> BindgenResult<Vec<u8>> {
+        let result = match self {
+            Self::Command { program, args } => run_command(program, args, &content),
+            Self::Callback(f) => f(path, content),
+        };
+
+        result.map_err(|reason| Error::Hook { path: path.to_path_buf(), reason })
+    }
+
+    /// Applies the hook to all the generated files.
+    pub fn apply_all(
+        &self,
+        files: HashMap<PathBuf, Vec<u8>>,
+    ) -> BindgenResult<HashMap<PathBuf, Vec<u8>>> {
+        files
+            .into_iter()
+            .map(|(path, content)| {
+                let content = self.apply(&path, content)?;
+                Ok((path, content))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for PostGenerationHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Command { program, args } => {
+                f.debug_struct("Command").field("program", program).field("args", args).finish()
+            }
+            Self::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
+
+fn run_command(program: &str, args: &[String], content: &[u8]) -> Result<Vec<u8>, String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run `{program}`: {e}"))?;
+
+    // Written from another thread, as the command may not read its whole input before writing
+    // to its output.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = content.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child.wait_with_output().map_err(|e| format!("`{program}` failed: {e}"))?;
+    writer
+        .join()
+        .expect("stdin writer panicked")
+        .map_err(|e| format!("failed to write to `{program}`: {e}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("`{program}` exited with {}: {}", output.status, stderr.trim()));
+    }
+
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::write_files;
+
+    fn uppercase() -> PostGenerationHook {
+        PostGenerationHook::callback(|_, content| Ok(content.to_ascii_uppercase()))
+    }
+
+    #[test]
+    fn hook_transforms_written_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = HashMap::from([(
+            PathBuf::from("Models/Position.gen.cs"),
+            b"public class Position {}".to_vec(),
+        )]);
+
+        let files = uppercase().apply_all(files).unwrap();
+        write_files(dir.path(), &files).unwrap();
+
+        let written = fs::read_to_string(dir.path().join("Models/Position.gen.cs")).unwrap();
+        assert_eq!(written, "PUBLIC CLASS POSITION {}");
+    }
+
+    #[test]
+    fn hook_errors_name_the_file() {
+        let hook = PostGenerationHook::callback(|_, _| Err("invalid syntax".to_string()));
+        let err = hook.apply(Path::new("Contracts/Actions.gen.cs"), vec![]).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Post-generation hook failed on `Contracts/Actions.gen.cs`: invalid syntax"
+        );
+    }
+}
@@ -1,22 +1,29 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use cainome::parser::tokens::Token;
 use cainome::parser::{AbiParser, TokenizedAbi};
 use camino::Utf8PathBuf;
 use dojo_world::manifest::BaseManifest;
 pub mod error;
-use error::BindgenResult;
+use error::{BindgenResult, Error};
+
+pub mod hook;
+use hook::PostGenerationHook;
+
+pub mod lint;
+pub mod manifest;
+pub mod merge;
+use manifest::{BindgenManifest, BINDGEN_MANIFEST_FILE_NAME};
+
+pub mod schema;
 
 mod plugins;
-use plugins::recs::TypescriptRecsPlugin;
-use plugins::typescript::TypescriptPlugin;
-use plugins::typescript_v2::TypeScriptV2Plugin;
 use plugins::unity::UnityPlugin;
-use plugins::BuiltinPlugin;
-pub use plugins::BuiltinPlugins;
+pub use plugins::unity::{UnityMember, UnityPluginConfig, UnknownTypePolicy};
+pub use plugins::{BuiltinPlugin, BuiltinPlugins, ReservedWords};
 
 #[derive(Debug, PartialEq)]
 pub struct DojoModel {
@@ -68,6 +75,10 @@ pub struct PluginManager {
     pub builtin_plugins: Vec<BuiltinPlugins>,
     /// A list of custom plugins to invoke.
     pub plugins: Vec<String>,
+    /// Options of the builtin Unity plugin.
+    pub unity_config: UnityPluginConfig,
+    /// Hook applied to every generated file before it is written, if any.
+    pub post_generation_hook: Option<PostGenerationHook>,
 }
 
 impl PluginManager {
@@ -84,28 +95,71 @@ impl PluginManager {
             skip_migration,
         )?;
 
+        self.generate_from_data(&data).await
+    }
+
+    /// Generates the bindings of `data` for all the builtin plugins.
+    ///
+    /// Nothing is written if the types or files generated by any of the plugins collide, see
+    /// [`lint`].
+    pub async fn generate_from_data(&self, data: &DojoData) -> BindgenResult<()> {
+        let mut outputs = Vec::new();
+
         for plugin in &self.builtin_plugins {
             // Get the plugin builder from the plugin enum.
-            let builder: Box<dyn BuiltinPlugin> = match plugin {
-                BuiltinPlugins::Typescript => Box::new(TypescriptPlugin::new()),
-                BuiltinPlugins::Unity => Box::new(UnityPlugin::new()),
-                BuiltinPlugins::TypeScriptV2 => Box::new(TypeScriptV2Plugin::new()),
-                BuiltinPlugins::Recs => Box::new(TypescriptRecsPlugin::new()),
-            };
-
-            let files = builder.generate_code(&data).await?;
-            for (path, content) in files {
-                // Prepends the output directory and plugin name to the path.
-                let path = self.output_path.join(plugin.to_string()).join(path);
-                fs::create_dir_all(path.parent().unwrap()).unwrap();
-
-                fs::write(path, content)?;
+            let builder = plugin.instantiate_with(&self.unity_config);
+            lint::check_name_collisions(data, builder.as_ref())?;
+
+            let mut files = builder.generate_code(data).await?;
+            if let Some(hook) = &self.post_generation_hook {
+                files = hook.apply_all(files)?;
             }
+
+            lint::check_file_collisions(&files)?;
+            outputs.push((plugin, builder, files));
         }
+
+        for (plugin, builder, files) in outputs {
+            // Hashes the files as they are written, after the hook.
+            let manifest = BindgenManifest::new(data, &files);
+
+            // Prepends the output directory and plugin name to the path.
+            let plugin_path = self.output_path.join(plugin.to_string());
+            builder.write_to(&plugin_path, &files)?;
+
+            fs::create_dir_all(&plugin_path)?;
+            fs::write(
+                plugin_path.join(BINDGEN_MANIFEST_FILE_NAME),
+                serde_json::to_vec_pretty(&manifest)?,
+            )?;
+        }
+
         Ok(())
     }
 }
 
+/// Writes the generated `files` under the `dir` directory.
+///
+/// The paths must be relative and stay within `dir`, otherwise nothing is written and
+/// [`Error::OutputPathEscapes`] is returned. Existing files are overwritten.
+fn write_files(dir: &Path, files: &HashMap<PathBuf, Vec<u8>>) -> BindgenResult<()> {
+    if let Some(path) = files
+        .keys()
+        .find(|path| path.components().any(|component| !matches!(component, Component::Normal(_))))
+    {
+        return Err(Error::OutputPathEscapes(path.clone()));
+    }
+
+    for (path, content) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        fs::write(path, content)?;
+    }
+
+    Ok(())
+}
+
 /// Gathers dojo data from the manifests files.
 ///
 /// # Arguments
@@ -140,17 +194,7 @@ fn gather_dojo_data(
         let tokens = AbiParser::tokens_from_abi_string(&abi, &HashMap::new())?;
         let tag = contract_manifest.inner.tag.clone();
 
-        // Identify the systems -> for now only take the functions from the
-        // interfaces.
-        let mut systems = vec![];
-        let interface_blacklist =
-            ["dojo::world::IWorldProvider", "dojo::contract::upgradeable::IUpgradeable"];
-
-        for (interface, funcs) in &tokens.interfaces {
-            if !interface_blacklist.contains(&interface.as_str()) {
-                systems.extend(funcs.clone());
-            }
-        }
+        let systems = systems_from_abi(&tokens);
 
         contracts.insert(tag.clone(), DojoContract { tag, tokens, systems });
     }
@@ -177,6 +221,34 @@ fn gather_dojo_data(
     Ok(DojoData { world, models, contracts })
 }
 
+/// Identifies the systems of a contract.
+///
+/// For now only takes the functions from the interfaces.
+fn systems_from_abi(tokens: &TokenizedAbi) -> Vec<Token> {
+    let mut systems = vec![];
+    let interface_blacklist =
+        ["dojo::world::IWorldProvider", "dojo::contract::upgradeable::IUpgradeable"];
+
+    for (interface, funcs) in &tokens.interfaces {
+        if !interface_blacklist.contains(&interface.as_str()) {
+            systems.extend(funcs.clone());
+        }
+    }
+
+    systems
+}
+
+/// Generates a single Unity SDK for several contracts whose ABIs are given separately.
+///
+/// The types shared by the contracts are only emitted once, see [`merge::merge_abis`].
+pub fn generate_unity_sdk(
+    inputs: Vec<(String, TokenizedAbi)>,
+    config: UnityPluginConfig,
+) -> BindgenResult<HashMap<PathBuf, Vec<u8>>> {
+    let merged = merge::merge_abis(inputs)?;
+    UnityPlugin::with_config(config).generate_merged_code(&merged)
+}
+
 /// Filters the model ABI to keep relevant types
 /// to be generated for bindings.
 fn filter_model_tokens(tokens: &TokenizedAbi) -> TokenizedAbi {
@@ -0,0 +1,160 @@
+//! Checks run on the [`DojoData`] and the generated files before any binding is written.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use crate::error::{BindgenResult, Error};
+use crate::plugins::BuiltinPlugin;
+use crate::DojoData;
+
+/// Checks that the types emitted by `plugin` don't collide.
+///
+/// Two types collide if they share the same name and `plugin` emits them into the same
+/// namespace, see [`BuiltinPlugin::type_namespace`], as one definition would overwrite the other.
+/// All the collisions are reported at once in [`Error::NameCollision`].
+pub fn check_name_collisions(data: &DojoData, plugin: &dyn BuiltinPlugin) -> BindgenResult<()> {
+    // (Namespace, type name) -> type paths defining it.
+    let mut types = BTreeMap::<(String, String), BTreeSet<String>>::new();
+    for model in data.models.values() {
+        for token in model.tokens.structs.iter().chain(model.tokens.enums.iter()) {
+            let Ok(composite) = token.to_composite() else { continue };
+            types
+                .entry((
+                    plugin.type_namespace(&composite.type_path),
+                    composite.type_name_or_alias(),
+                ))
+                .or_default()
+                .insert(composite.type_path.clone());
+        }
+    }
+
+    let collisions = types
+        .iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((_, name), paths)| format!("{name} ({})", join(paths)))
+        .collect::<Vec<_>>();
+
+    if collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::NameCollision(collisions))
+    }
+}
+
+/// Checks that the paths of the generated `files` don't collide.
+///
+/// Files are named after the models and contracts tags. Two paths only differing by their case
+/// would overwrite each other on case-insensitive filesystems. All the collisions are reported at
+/// once in [`Error::NameCollision`].
+pub fn check_file_collisions(files: &HashMap<PathBuf, Vec<u8>>) -> BindgenResult<()> {
+    let paths =
+        files.keys().map(|path| path.to_string_lossy().replace('\\', "/")).collect::<Vec<_>>();
+    let collisions = case_collisions(paths.iter().map(String::as_str));
+
+    if collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::NameCollision(collisions))
+    }
+}
+
+/// Returns the names which are only distinct when compared case-sensitively.
+fn case_collisions<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut groups = BTreeMap::<String, BTreeSet<&str>>::new();
+    for name in names {
+        groups.entry(name.to_lowercase()).or_default().insert(name);
+    }
+
+    groups.into_values().filter(|names| names.len() > 1).map(|names| join(&names)).collect()
+}
+
+fn join<T: AsRef<str>>(names: &BTreeSet<T>) -> String {
+    names.iter().map(|n| n.as_ref()).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use cainome::parser::tokens::{Composite, CompositeType, Token};
+    use cainome::parser::TokenizedAbi;
+
+    use super::*;
+    use crate::plugins::typescript::TypescriptPlugin;
+    use crate::plugins::unity::UnityPlugin;
+    use crate::{DojoModel, DojoWorld};
+
+    fn model(tag: &str, type_path: &str) -> (String, DojoModel) {
+        let composite = Composite {
+            type_path: type_path.to_string(),
+            r#type: CompositeType::Struct,
+            generic_args: vec![],
+            inners: vec![],
+            is_event: false,
+            alias: None,
+        };
+
+        let tokens =
+            TokenizedAbi { structs: vec![Token::Composite(composite)], ..Default::default() };
+        (tag.to_string(), DojoModel { tag: tag.to_string(), tokens })
+    }
+
+    fn dojo_data(models: Vec<(String, DojoModel)>) -> DojoData {
+        DojoData {
+            world: DojoWorld { name: "dojo_examples".to_string() },
+            models: models.into_iter().collect(),
+            contracts: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn case_insensitive_file_collisions_are_reported() {
+        let files = HashMap::from([
+            (PathBuf::from("Models/dojo_examples-Position.gen.cs"), vec![]),
+            (PathBuf::from("Models/dojo_examples-position.gen.cs"), vec![]),
+            (PathBuf::from("Models/dojo_examples-Moves.gen.cs"), vec![]),
+        ]);
+
+        let Err(Error::NameCollision(collisions)) = check_file_collisions(&files) else {
+            panic!("collision expected");
+        };
+
+        assert_eq!(
+            collisions,
+            vec!["Models/dojo_examples-Position.gen.cs, Models/dojo_examples-position.gen.cs"
+                .to_string()]
+        );
+
+        // Types only differing by their case don't overwrite each other.
+        let data = dojo_data(vec![
+            model("dojo_examples-Position", "dojo_examples::models::Position"),
+            model("dojo_examples-position", "dojo_examples::others::position"),
+        ]);
+        assert!(check_name_collisions(&data, &TypescriptPlugin::new()).is_ok());
+    }
+
+    #[test]
+    fn shared_types_are_not_collisions() {
+        let plugin = TypescriptPlugin::new();
+        let data = dojo_data(vec![
+            model("dojo_examples-Position", "dojo_examples::models::Vec2"),
+            model("dojo_examples-Moves", "dojo_examples::models::Vec2"),
+        ]);
+
+        assert!(check_name_collisions(&data, &plugin).is_ok());
+
+        let data = dojo_data(vec![
+            model("dojo_examples-Position", "dojo_examples::models::Vec2"),
+            model("dojo_examples-Moves", "dojo_examples::others::Vec2"),
+        ]);
+
+        let Err(Error::NameCollision(collisions)) = check_name_collisions(&data, &plugin) else {
+            panic!("collision expected");
+        };
+        assert_eq!(
+            collisions,
+            vec!["Vec2 (dojo_examples::models::Vec2, dojo_examples::others::Vec2)".to_string()]
+        );
+
+        // They're emitted in the namespace of their module by the Unity plugin.
+        assert!(check_name_collisions(&data, &UnityPlugin::new()).is_ok());
+    }
+}
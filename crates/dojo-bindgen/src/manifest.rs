@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use starknet::core::utils::starknet_keccak;
+
+use crate::DojoData;
+
+/// Name of the manifest file written next to the generated bindings of each plugin.
+pub const BINDGEN_MANIFEST_FILE_NAME: &str = "bindgen.manifest.json";
+
+/// Describes a bindings generation run.
+///
+/// The manifest is written alongside the generated files so that a verification step (e.g. in
+/// CI) can check that the committed bindings match what the bindgen would produce for the same
+/// input. Only `version`, `schema_hash` and `files` are meant to be compared, `generated_at` and
+/// `systems` are informative.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BindgenManifest {
+    /// Version of the `dojo-bindgen` crate that generated the bindings.
+    pub version: String,
+    /// When the bindings were generated.
+    pub generated_at: DateTime<Utc>,
+    /// Hash of the input schema (models and contracts) the bindings were generated from.
+    pub schema_hash: String,
+    /// Content hash of each generated file, keyed by its path relative to the plugin output
+    /// directory.
+    pub files: BTreeMap<String, String>,
+    /// Index of the systems for which bindings were generated, grouped by contract tag.
+    #[serde(default)]
+    pub systems: BTreeMap<String, Vec<SystemEntry>>,
+}
+
+/// A system of the [`BindgenManifest`] index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemEntry {
+    /// Name of the system.
+    pub name: String,
+    /// Parameters of the system, in declaration order.
+    pub inputs: Vec<ParameterEntry>,
+    /// Types returned by the system, by their full type path.
+    pub outputs: Vec<String>,
+}
+
+/// A parameter of a [`SystemEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterEntry {
+    /// Name of the parameter.
+    pub name: String,
+    /// Full type path of the parameter.
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+impl BindgenManifest {
+    /// Creates a new manifest for the `files` generated from `data`.
+    pub fn new(data: &DojoData, files: &HashMap<PathBuf, Vec<u8>>) -> Self {
+        let files = files
+            .iter()
+            .map(|(path, content)| {
+                (path.to_string_lossy().replace('\\', "/"), content_hash(content))
+            })
+            .collect();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            generated_at: Utc::now(),
+            schema_hash: schema_hash(data),
+            files,
+            systems: systems_index(data),
+        }
+    }
+}
+
+/// Lists the systems of every contract, sorted by name.
+pub fn systems_index(data: &DojoData) -> BTreeMap<String, Vec<SystemEntry>> {
+    data.contracts
+        .values()
+        .map(|contract| {
+            let mut systems = contract
+                .systems
+                .iter()
+                .filter_map(|token| token.to_function().ok())
+                .map(|function| SystemEntry {
+                    name: function.name.clone(),
+                    inputs: function
+                        .inputs
+                        .iter()
+                        .map(|(name, token)| ParameterEntry {
+                            name: name.clone(),
+                            ty: token.type_path(),
+                        })
+                        .collect(),
+                    outputs: function.outputs.iter().map(|token| token.type_path()).collect(),
+                })
+                .collect::<Vec<_>>();
+
+            systems.sort_by(|a, b| a.name.cmp(&b.name));
+            (contract.tag.clone(), systems)
+        })
+        .collect()
+}
+
+/// Computes the hash of the given content, hex encoded.
+pub fn content_hash(content: &[u8]) -> String {
+    format!("{:#x}", starknet_keccak(content))
+}
+
+/// Computes a deterministic hash of the input schema.
+///
+/// Models and contracts are sorted by tag, and only the ordered token lists are hashed, to make
+/// the result independent of the iteration order of the underlying maps.
+pub fn schema_hash(data: &DojoData) -> String {
+    let mut schema = format!("world:{}\n", data.world.name);
+
+    let mut models = data.models.values().collect::<Vec<_>>();
+    models.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    for model in models {
+        schema +=
+            &format!("model:{}:{:?}:{:?}\n", model.tag, model.tokens.structs, model.tokens.enums);
+    }
+
+    let mut contracts = data.contracts.values().collect::<Vec<_>>();
+    contracts.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    for contract in contracts {
+        schema += &format!(
+            "contract:{}:{:?}:{:?}:{:?}\n",
+            contract.tag, contract.tokens.structs, contract.tokens.enums, contract.systems
+        );
+    }
+
+    content_hash(schema.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use cainome::parser::tokens::{
+        Composite, CompositeInner, CompositeInnerKind, CompositeType, CoreBasic, Function,
+        StateMutability, Token,
+    };
+    use cainome::parser::TokenizedAbi;
+
+    use super::*;
+    use crate::plugins::{BuiltinPlugin, BuiltinPlugins};
+    use crate::{DojoContract, DojoModel, DojoWorld};
+
+    fn mock_data() -> DojoData {
+        DojoData {
+            world: DojoWorld { name: "dojo_examples".to_string() },
+            models: HashMap::new(),
+            contracts: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn manifest_lists_every_file_with_stable_hash() {
+        let data = mock_data();
+        let files = HashMap::from([
+            (PathBuf::from("Models/Position.gen.cs"), b"public class Position {}".to_vec()),
+            (PathBuf::from("Contracts/Actions.gen.cs"), b"public class Actions {}".to_vec()),
+        ]);
+
+        let first = BindgenManifest::new(&data, &files);
+        let second = BindgenManifest::new(&data, &files);
+
+        assert_eq!(first.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(first.files.len(), 2);
+        assert!(first.files.contains_key("Models/Position.gen.cs"));
+        assert!(first.files.contains_key("Contracts/Actions.gen.cs"));
+
+        assert_eq!(first.schema_hash, second.schema_hash);
+        assert_eq!(first.files, second.files);
+    }
+
+    #[test]
+    fn manifest_indexes_systems_by_contract() {
+        fn contract(tag: &str, system: &str, input: (&str, &str)) -> (String, DojoContract) {
+            let function = Token::Function(Function {
+                name: system.to_string(),
+                state_mutability: StateMutability::External,
+                inputs: vec![(
+                    input.0.to_string(),
+                    Token::CoreBasic(CoreBasic { type_path: input.1.to_string() }),
+                )],
+                outputs: vec![],
+                named_outputs: vec![],
+            });
+
+            let contract = DojoContract {
+                tag: tag.to_string(),
+                tokens: Default::default(),
+                systems: vec![function],
+            };
+            (tag.to_string(), contract)
+        }
+
+        let mut data = mock_data();
+        data.contracts = HashMap::from([
+            contract("dojo_examples-actions", "spawn", ("color", "core::integer::u8")),
+            contract("dojo_examples-others", "teleport", ("x", "core::integer::u32")),
+        ]);
+
+        let manifest = BindgenManifest::new(&data, &HashMap::new());
+
+        assert_eq!(manifest.systems.len(), 2);
+        assert_eq!(
+            manifest.systems["dojo_examples-actions"],
+            vec![SystemEntry {
+                name: "spawn".to_string(),
+                inputs: vec![ParameterEntry {
+                    name: "color".to_string(),
+                    ty: "core::integer::u8".to_string()
+                }],
+                outputs: vec![],
+            }]
+        );
+        assert_eq!(
+            manifest.systems["dojo_examples-others"],
+            vec![SystemEntry {
+                name: "teleport".to_string(),
+                inputs: vec![ParameterEntry {
+                    name: "x".to_string(),
+                    ty: "core::integer::u32".to_string()
+                }],
+                outputs: vec![],
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn manifest_is_stable_across_generations() {
+        let position = Composite {
+            type_path: "dojo_examples::models::Position".to_string(),
+            r#type: CompositeType::Struct,
+            generic_args: vec![],
+            inners: vec![CompositeInner {
+                index: 0,
+                name: "player".to_string(),
+                kind: CompositeInnerKind::Key,
+                token: Token::CoreBasic(CoreBasic { type_path: "core::felt252".to_string() }),
+            }],
+            is_event: false,
+            alias: None,
+        };
+
+        let mut data = mock_data();
+        data.models = HashMap::from([(
+            "dojo_examples-Position".to_string(),
+            DojoModel {
+                tag: "dojo_examples-Position".to_string(),
+                tokens: TokenizedAbi {
+                    structs: vec![Token::Composite(position)],
+                    ..Default::default()
+                },
+            },
+        )]);
+
+        let plugins = [
+            BuiltinPlugins::Typescript,
+            BuiltinPlugins::Unity,
+            BuiltinPlugins::TypeScriptV2,
+            BuiltinPlugins::Recs,
+            BuiltinPlugins::Godot,
+        ];
+
+        let mut manifests = vec![];
+        for plugin in &plugins {
+            let files = plugin.instantiate().generate_code(&data).await.unwrap();
+            manifests.push(BindgenManifest::new(&data, &files));
+        }
+
+        // Lets the clock move on, in case the generated files embed the time.
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+
+        for (plugin, first) in plugins.iter().zip(manifests) {
+            let files = plugin.instantiate().generate_code(&data).await.unwrap();
+            let second = BindgenManifest::new(&data, &files);
+
+            assert_eq!(first.schema_hash, second.schema_hash, "{plugin}");
+            assert_eq!(first.files, second.files, "{plugin}");
+        }
+    }
+
+    #[test]
+    fn manifest_hash_changes_with_content() {
+        let data = mock_data();
+        let files = HashMap::from([(PathBuf::from("a.cs"), b"a".to_vec())]);
+        let other = HashMap::from([(PathBuf::from("a.cs"), b"b".to_vec())]);
+
+        let first = BindgenManifest::new(&data, &files);
+        let second = BindgenManifest::new(&data, &other);
+
+        assert_ne!(first.files["a.cs"], second.files["a.cs"]);
+    }
+}
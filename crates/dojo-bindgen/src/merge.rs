@@ -0,0 +1,78 @@
+//! Merging of the ABIs of several contracts into a single set of bindings.
+
+use std::collections::BTreeMap;
+
+use cainome::parser::tokens::Token;
+use cainome::parser::TokenizedAbi;
+
+use crate::error::{BindgenResult, Error};
+use crate::{systems_from_abi, DojoContract};
+
+/// The ABIs of several contracts, with the types they define unified.
+#[derive(Debug, Default)]
+pub struct MergedAbi {
+    /// The structs of all the contracts, defined once each and sorted by name.
+    pub structs: Vec<Token>,
+    /// The enums of all the contracts, defined once each and sorted by name.
+    pub enums: Vec<Token>,
+    /// The contracts, in the order they were given.
+    pub contracts: Vec<DojoContract>,
+}
+
+/// Merges the ABIs of the given `(contract_name, abi)` pairs.
+///
+/// Types are unified by their name, as it's the one used in the generated code. A type defined
+/// by several contracts is only kept once, but it's an error for the definitions to differ.
+pub fn merge_abis(inputs: Vec<(String, TokenizedAbi)>) -> BindgenResult<MergedAbi> {
+    let mut structs = BTreeMap::new();
+    let mut enums = BTreeMap::new();
+    let mut contracts = Vec::with_capacity(inputs.len());
+
+    for (name, tokens) in inputs {
+        for token in &tokens.structs {
+            insert_type(&mut structs, token)?;
+        }
+
+        for token in &tokens.enums {
+            insert_type(&mut enums, token)?;
+        }
+
+        let systems = systems_from_abi(&tokens);
+        contracts.push(DojoContract { tag: name, tokens, systems });
+    }
+
+    Ok(MergedAbi {
+        structs: structs.into_values().collect(),
+        enums: enums.into_values().collect(),
+        contracts,
+    })
+}
+
+fn insert_type(types: &mut BTreeMap<String, Token>, token: &Token) -> BindgenResult<()> {
+    let name = token.type_name();
+
+    match types.get(&name) {
+        Some(existing) if !same_shape(existing, token) => Err(Error::TypeConflict(name)),
+        Some(_) => Ok(()),
+        None => {
+            types.insert(name, token.clone());
+            Ok(())
+        }
+    }
+}
+
+// Two definitions are the same if they only differ by the module they are defined in.
+fn same_shape(a: &Token, b: &Token) -> bool {
+    match (a, b) {
+        (Token::Composite(a), Token::Composite(b)) => {
+            a.r#type == b.r#type
+                && a.generic_args == b.generic_args
+                && a.inners.len() == b.inners.len()
+                && a.inners
+                    .iter()
+                    .zip(&b.inners)
+                    .all(|(a, b)| a.name == b.name && a.token.type_path() == b.token.type_path())
+        }
+        _ => a == b,
+    }
+}
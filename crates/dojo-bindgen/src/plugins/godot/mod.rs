@@ -0,0 +1,378 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use cainome::parser::tokens::{Composite, CompositeInnerKind, Token};
+use dojo_world::contracts::naming;
+
+use crate::error::BindgenResult;
+use crate::plugins::{BuiltinPlugin, ReservedWords};
+use crate::{compare_tokens_by_type_name, DojoData, DojoModel};
+
+#[cfg(test)]
+mod tests;
+
+/// The GDScript keywords, which can't be used as property names unless prefixed with `_`.
+pub const GDSCRIPT_KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "assert",
+    "await",
+    "break",
+    "breakpoint",
+    "class",
+    "class_name",
+    "const",
+    "continue",
+    "elif",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "for",
+    "func",
+    "if",
+    "in",
+    "is",
+    "match",
+    "not",
+    "null",
+    "or",
+    "pass",
+    "preload",
+    "return",
+    "self",
+    "signal",
+    "static",
+    "super",
+    "true",
+    "var",
+    "void",
+    "while",
+    "yield",
+];
+
+/// Generates GDScript bindings of the models, to be used in Godot 4 projects.
+///
+/// Each model, and each struct and enum it uses, is emitted as a global class with typed
+/// properties and a static `from_dict` constructor, deserializing the model from its dictionary
+/// representation as returned by Torii.
+#[derive(Debug)]
+pub struct GodotPlugin {
+    reserved_words: ReservedWords,
+}
+
+impl GodotPlugin {
+    pub fn new() -> Self {
+        Self { reserved_words: ReservedWords::new(GDSCRIPT_KEYWORDS.iter().copied(), "_") }
+    }
+
+    fn ident(&self, name: &str) -> String {
+        self.reserved_words.escape(name)
+    }
+
+    // Maps cairo types to GDScript types
+    // The types without a GDScript equivalent are left untyped, ie. `Variant`
+    fn map_type(&self, token: &Token) -> String {
+        match token.type_name().as_str() {
+            "bool" => "bool".to_string(),
+            "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "usize" => "int".to_string(),
+            // Don't fit in the 64-bit signed `int`, kept as strings.
+            "u64" | "i128" | "u128" | "u256" => "String".to_string(),
+            "felt252" | "ClassHash" | "ContractAddress" => "FieldElement".to_string(),
+            "bytes31" | "ByteArray" => "String".to_string(),
+            "array" => {
+                if let Token::Array(array) = token {
+                    let inner = self.map_type(&array.inner);
+                    // Typed arrays can't be nested.
+                    if inner == "Variant" || inner.starts_with("Array") {
+                        "Array".to_string()
+                    } else {
+                        format!("Array[{inner}]")
+                    }
+                } else {
+                    panic!("Invalid array token: {:?}", token);
+                }
+            }
+            _ => match token {
+                Token::Composite(composite) if composite.generic_args.is_empty() => {
+                    composite.type_name()
+                }
+                _ => "Variant".to_string(),
+            },
+        }
+    }
+
+    // Formats an expression deserializing the given type from the dictionary `value`
+    // Nested arrays are mapped with lambdas whose parameter is named after the `depth`
+    fn deserialize(&self, token: &Token, value: &str, depth: usize) -> String {
+        match self.map_type(token).as_str() {
+            "bool" => format!("bool({value})"),
+            "int" => format!("int({value})"),
+            "String" => format!("str({value})"),
+            "FieldElement" => format!("FieldElement.from_hex({value})"),
+            "Variant" => value.to_string(),
+            ty if ty.starts_with("Array") => {
+                let Token::Array(array) = token else {
+                    panic!("Invalid array token: {:?}", token);
+                };
+                let item = format!("item{depth}");
+                format!(
+                    "{value}.map(func({item}): return {})",
+                    self.deserialize(&array.inner, &item, depth + 1)
+                )
+            }
+            ty => format!("{ty}.from_dict({value})"),
+        }
+    }
+
+    fn generated_header() -> String {
+        "# Generated by dojo-bindgen. Do not modify this file manually.\n".to_string()
+    }
+
+    // The resource the felts are deserialized into
+    fn format_field_element() -> String {
+        "class_name FieldElement
+extends Resource
+
+## The felt, as a `0x` prefixed hexadecimal string.
+@export var hex: String
+
+
+static func from_hex(value: String) -> FieldElement:
+\tvar felt := FieldElement.new()
+\tfelt.hex = value
+\treturn felt
+
+
+func _to_string() -> String:
+\treturn hex
+"
+        .to_string()
+    }
+
+    // Formats the typed properties of a struct, and the `from_dict` constructor setting them
+    fn format_class_body(&self, token: &Composite) -> String {
+        let name = token.type_name();
+
+        let properties = token
+            .inners
+            .iter()
+            .map(|field| {
+                format!("var {}: {}", self.ident(&field.name), self.map_type(&field.token))
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let assignments = token
+            .inners
+            .iter()
+            .map(|field| {
+                let property = self.ident(&field.name);
+                let value = self.deserialize(&field.token, &format!("data[\"{}\"]", field.name), 0);
+                // Typed arrays can only be assigned arrays of the same type, which `map` doesn't
+                // return.
+                if self.map_type(&field.token).starts_with("Array[") {
+                    format!("\tinstance.{property}.assign({value})")
+                } else {
+                    format!("\tinstance.{property} = {value}")
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            "{properties}
+
+
+static func from_dict(data: Dictionary) -> {name}:
+\tvar instance := {name}.new()
+{assignments}
+\treturn instance
+"
+        )
+    }
+
+    // Token should be a struct
+    // This will be formatted into a GDScript class
+    fn format_struct(&self, token: &Composite) -> String {
+        format!(
+            "# Type definition for `{}` struct
+class_name {}
+extends RefCounted
+
+{}",
+            token.type_path,
+            token.type_name(),
+            self.format_class_body(token)
+        )
+    }
+
+    // Token should be an enum
+    // This will be formatted into a GDScript class holding the variant name and its data
+    // Unit variants can be deserialized from their name alone
+    fn format_enum(&self, token: &Composite) -> String {
+        let name = token.type_name();
+
+        let variants = token
+            .inners
+            .iter()
+            .map(|field| format!("\"{}\"", field.name))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        let cases = token
+            .inners
+            .iter()
+            .filter(|field| !GodotPlugin::is_unit(&field.token))
+            .map(|field| {
+                format!(
+                    "\t\t\"{}\":\n\t\t\tinstance.value = {}",
+                    field.name,
+                    self.deserialize(&field.token, "data[\"value\"]", 0)
+                )
+            })
+            .collect::<Vec<String>>();
+        let cases = if cases.is_empty() {
+            String::new()
+        } else {
+            format!("\tmatch instance.option:\n{}\n", cases.join("\n"))
+        };
+
+        format!(
+            "# Type definition for `{}` enum
+class_name {name}
+extends RefCounted
+
+const VARIANTS: Array[String] = [{variants}]
+
+## The name of the variant.
+var option: String
+## The data of the variant, `null` for the variants without data.
+var value: Variant
+
+
+static func from_dict(data: Variant) -> {name}:
+\tvar instance := {name}.new()
+\tif data is String:
+\t\tinstance.option = data
+\t\treturn instance
+\tinstance.option = data[\"option\"]
+{cases}\treturn instance
+",
+            token.type_path,
+        )
+    }
+
+    // Whether the enum variant doesn't carry any data
+    fn is_unit(token: &Token) -> bool {
+        match token {
+            Token::CoreBasic(basic) => basic.type_path == "()",
+            Token::Tuple(tuple) => tuple.inners.is_empty(),
+            _ => false,
+        }
+    }
+
+    // Token should be the model struct
+    // This will be formatted into a GDScript class, along with the tag and keys of the model
+    fn format_model(&self, tag: &str, model: &Composite) -> String {
+        let keys = model
+            .inners
+            .iter()
+            .filter(|field| matches!(field.kind, CompositeInnerKind::Key))
+            .map(|field| format!("\"{}\"", field.name))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        format!(
+            "# Model definition for `{}` model
+class_name {}
+extends RefCounted
+
+const TAG := \"{tag}\"
+const KEYS: Array[String] = [{keys}]
+
+{}",
+            model.type_path,
+            model.type_name(),
+            self.format_class_body(model)
+        )
+    }
+
+    // Whether the composite is emitted as its own class, ie. isn't mapped to a GDScript type
+    fn is_class(&self, token: &Token) -> bool {
+        self.map_type(token) == token.type_name()
+    }
+
+    // Formats the model, and adds the structs and enums it uses which haven't been handled yet
+    // to `out`
+    fn handle_model(
+        &self,
+        model: &DojoModel,
+        handled_tokens: &mut HashSet<String>,
+        out: &mut HashMap<PathBuf, Vec<u8>>,
+    ) -> String {
+        let mut model_struct: Option<&Composite> = None;
+
+        let mut sorted_structs = model.tokens.structs.clone();
+        sorted_structs.sort_by(compare_tokens_by_type_name);
+
+        let mut sorted_enums = model.tokens.enums.clone();
+        sorted_enums.sort_by(compare_tokens_by_type_name);
+
+        for token in &sorted_structs {
+            let composite = token.to_composite().unwrap();
+
+            // first index is our model struct
+            if token.type_name() == naming::get_name_from_tag(&model.tag) {
+                model_struct = Some(composite);
+                continue;
+            }
+
+            if !self.is_class(token) || !handled_tokens.insert(token.type_path()) {
+                continue;
+            }
+
+            let code = GodotPlugin::generated_header() + &self.format_struct(composite);
+            out.insert(PathBuf::from(format!("Types/{}.gd", token.type_name())), code.into_bytes());
+        }
+
+        for token in &sorted_enums {
+            if !self.is_class(token) || !handled_tokens.insert(token.type_path()) {
+                continue;
+            }
+
+            let code =
+                GodotPlugin::generated_header() + &self.format_enum(token.to_composite().unwrap());
+            out.insert(PathBuf::from(format!("Types/{}.gd", token.type_name())), code.into_bytes());
+        }
+
+        GodotPlugin::generated_header()
+            + &self.format_model(&model.tag, model_struct.expect("model struct not found"))
+    }
+}
+
+#[async_trait]
+impl BuiltinPlugin for GodotPlugin {
+    async fn generate_code(&self, data: &DojoData) -> BindgenResult<HashMap<PathBuf, Vec<u8>>> {
+        let mut out: HashMap<PathBuf, Vec<u8>> = HashMap::new();
+        let mut handled_tokens = HashSet::<String>::new();
+
+        let mut models = data.models.iter().collect::<Vec<_>>();
+        // Sort models based on their tag to ensure deterministic output.
+        models.sort_by(|(_, a), (_, b)| a.tag.cmp(&b.tag));
+
+        for (name, model) in &models {
+            log::debug!("Generating model: {}", name);
+            let code = self.handle_model(model, &mut handled_tokens, &mut out);
+            out.insert(PathBuf::from(format!("Models/{}.gd", name)), code.into_bytes());
+        }
+
+        out.insert(
+            PathBuf::from("FieldElement.gd"),
+            (GodotPlugin::generated_header() + &GodotPlugin::format_field_element()).into_bytes(),
+        );
+
+        Ok(out)
+    }
+}
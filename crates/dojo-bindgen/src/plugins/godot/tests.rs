@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use cainome::parser::tokens::{Array, CompositeInnerKind, CompositeType, Token};
+use cainome::parser::TokenizedAbi;
+
+use crate::plugins::godot::GodotPlugin;
+use crate::plugins::test_utils::{basic, composite, field};
+use crate::{BuiltinPlugin, DojoData, DojoModel, DojoWorld};
+
+#[test]
+fn test_format_model() {
+    let model = composite(
+        "dojo_examples::models::Score",
+        CompositeType::Struct,
+        vec![
+            field(
+                0,
+                "player",
+                CompositeInnerKind::Key,
+                basic("core::starknet::contract_address::ContractAddress"),
+            ),
+            field(1, "points", CompositeInnerKind::Data, basic("core::integer::u32")),
+        ],
+    );
+
+    assert_eq!(
+        GodotPlugin::new().format_model("dojo_examples-Score", &model),
+        "# Model definition for `dojo_examples::models::Score` model
+class_name Score
+extends RefCounted
+
+const TAG := \"dojo_examples-Score\"
+const KEYS: Array[String] = [\"player\"]
+
+var player: FieldElement
+var points: int
+
+
+static func from_dict(data: Dictionary) -> Score:
+\tvar instance := Score.new()
+\tinstance.player = FieldElement.from_hex(data[\"player\"])
+\tinstance.points = int(data[\"points\"])
+\treturn instance
+"
+    );
+}
+
+#[tokio::test]
+async fn test_generate_code() {
+    let vec2 = composite(
+        "dojo_examples::models::Vec2",
+        CompositeType::Struct,
+        vec![
+            field(0, "x", CompositeInnerKind::Data, basic("core::integer::u32")),
+            field(1, "y", CompositeInnerKind::Data, basic("core::integer::u32")),
+        ],
+    );
+    let direction = composite(
+        "dojo_examples::models::Direction",
+        CompositeType::Enum,
+        vec![
+            field(0, "None", CompositeInnerKind::Data, basic("()")),
+            field(1, "Towards", CompositeInnerKind::Data, Token::Composite(vec2.clone())),
+        ],
+    );
+    let path = Token::Array(Array {
+        type_path: "core::array::Array::<core::array::Array::<core::integer::u32>>".to_string(),
+        inner: Box::new(Token::Array(Array {
+            type_path: "core::array::Array::<core::integer::u32>".to_string(),
+            inner: Box::new(basic("core::integer::u32")),
+            is_legacy: false,
+        })),
+        is_legacy: false,
+    });
+    let moves = composite(
+        "dojo_examples::models::Moves",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "last", CompositeInnerKind::Data, Token::Composite(direction.clone())),
+            field(2, "path", CompositeInnerKind::Data, path),
+        ],
+    );
+    let model = DojoModel {
+        tag: "dojo_examples-Moves".to_string(),
+        tokens: TokenizedAbi {
+            structs: vec![Token::Composite(moves), Token::Composite(vec2)],
+            enums: vec![Token::Composite(direction)],
+            ..Default::default()
+        },
+    };
+    let data = DojoData {
+        world: DojoWorld { name: "dojo_examples".to_string() },
+        models: HashMap::from([(model.tag.clone(), model)]),
+        contracts: HashMap::new(),
+    };
+
+    let out = GodotPlugin::new().generate_code(&data).await.unwrap();
+
+    let mut paths = out.keys().cloned().collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "FieldElement.gd",
+            "Models/dojo_examples-Moves.gd",
+            "Types/Direction.gd",
+            "Types/Vec2.gd",
+        ]
+        .map(PathBuf::from)
+    );
+
+    let file = |path: &str| String::from_utf8(out[&PathBuf::from(path)].clone()).unwrap();
+
+    let moves = file("Models/dojo_examples-Moves.gd");
+    assert!(moves.contains("var last: Direction\nvar path: Array\n"));
+    assert!(moves.contains("\tinstance.last = Direction.from_dict(data[\"last\"])"));
+    assert!(moves.contains(
+        "\tinstance.path = data[\"path\"].map(func(item0): return item0.map(func(item1): return \
+         int(item1)))"
+    ));
+
+    let direction = file("Types/Direction.gd");
+    assert!(direction.contains("const VARIANTS: Array[String] = [\"None\", \"Towards\"]"));
+    assert!(direction.contains(
+        "\tmatch instance.option:\n\t\t\"Towards\":\n\t\t\tinstance.value = \
+         Vec2.from_dict(data[\"value\"])\n"
+    ));
+}
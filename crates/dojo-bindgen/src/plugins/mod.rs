@@ -1,15 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use async_trait::async_trait;
 use cainome::parser::tokens::{Composite, Function};
 
-use crate::error::BindgenResult;
+use self::godot::GodotPlugin;
+use self::recs::TypescriptRecsPlugin;
+use self::typescript::TypescriptPlugin;
+use self::typescript_v2::TypeScriptV2Plugin;
+use self::unity::{UnityPlugin, UnityPluginConfig};
+use crate::error::{BindgenResult, Error};
 use crate::{DojoContract, DojoData};
 
+pub mod godot;
 pub mod recs;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub mod typescript;
 pub mod typescript_v2;
 pub mod unity;
@@ -20,6 +29,7 @@ pub enum BuiltinPlugins {
     Unity,
     TypeScriptV2,
     Recs,
+    Godot,
 }
 
 impl fmt::Display for BuiltinPlugins {
@@ -29,6 +39,44 @@ impl fmt::Display for BuiltinPlugins {
             BuiltinPlugins::Unity => write!(f, "unity"),
             BuiltinPlugins::TypeScriptV2 => write!(f, "typescript_v2"),
             BuiltinPlugins::Recs => write!(f, "recs"),
+            BuiltinPlugins::Godot => write!(f, "godot"),
+        }
+    }
+}
+
+impl FromStr for BuiltinPlugins {
+    type Err = Error;
+
+    /// Parses the name of a plugin, as displayed by [`BuiltinPlugins`].
+    fn from_str(s: &str) -> BindgenResult<Self> {
+        match s {
+            "typescript" => Ok(BuiltinPlugins::Typescript),
+            "unity" => Ok(BuiltinPlugins::Unity),
+            "typescript_v2" => Ok(BuiltinPlugins::TypeScriptV2),
+            "recs" => Ok(BuiltinPlugins::Recs),
+            "godot" => Ok(BuiltinPlugins::Godot),
+            _ => Err(Error::UnknownPlugin(s.to_string())),
+        }
+    }
+}
+
+impl BuiltinPlugins {
+    /// Instantiates the plugin, with its default configuration.
+    pub fn instantiate(&self) -> Box<dyn BuiltinPlugin> {
+        self.instantiate_with(&UnityPluginConfig::default())
+    }
+
+    /// Instantiates the plugin, the Unity plugin being configured with `unity_config`.
+    pub(crate) fn instantiate_with(
+        &self,
+        unity_config: &UnityPluginConfig,
+    ) -> Box<dyn BuiltinPlugin> {
+        match self {
+            BuiltinPlugins::Typescript => Box::new(TypescriptPlugin::new()),
+            BuiltinPlugins::Unity => Box::new(UnityPlugin::with_config(unity_config.clone())),
+            BuiltinPlugins::TypeScriptV2 => Box::new(TypeScriptV2Plugin::new()),
+            BuiltinPlugins::Recs => Box::new(TypescriptRecsPlugin::new()),
+            BuiltinPlugins::Godot => Box::new(GodotPlugin::new()),
         }
     }
 }
@@ -72,6 +120,50 @@ impl DerefMut for Buffer {
     }
 }
 
+/// Words of a target language which can't be used as identifiers as is, and how to escape them.
+///
+/// Each plugin holds the reserved words of the language it generates, and escapes the field,
+/// argument and method names it emits with [`ReservedWords::escape`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReservedWords {
+    words: HashSet<String>,
+    prefix: String,
+}
+
+impl ReservedWords {
+    /// Creates a set of reserved `words`, escaped by prepending them with `prefix`.
+    pub fn new<I, S>(words: I, prefix: &str) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self { words: words.into_iter().map(Into::into).collect(), prefix: prefix.to_string() }
+    }
+
+    /// Adds a reserved word.
+    pub fn insert(&mut self, word: impl Into<String>) {
+        self.words.insert(word.into());
+    }
+
+    /// Removes a reserved word, returning `true` if it was reserved.
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.words.remove(word)
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word)
+    }
+
+    /// Returns the identifier to emit for `name`, escaped if it's a reserved word.
+    pub fn escape(&self, name: &str) -> String {
+        if self.contains(name) {
+            format!("{}{name}", self.prefix)
+        } else {
+            name.to_string()
+        }
+    }
+}
+
 #[async_trait]
 pub trait BuiltinPlugin: Sync {
     /// Generates code by executing the plugin.
@@ -80,6 +172,23 @@ pub trait BuiltinPlugin: Sync {
     ///
     /// * `data` - Dojo data gathered from the compiled project.
     async fn generate_code(&self, data: &DojoData) -> BindgenResult<HashMap<PathBuf, Vec<u8>>>;
+
+    /// Returns the namespace the type of `type_path` is emitted into. Types of the same name only
+    /// collide within a namespace, see [`crate::lint::check_name_collisions`].
+    ///
+    /// Defaults to a single namespace for all the types.
+    fn type_namespace(&self, _type_path: &str) -> String {
+        String::new()
+    }
+
+    /// Writes the generated files under `out_dir`, creating the missing directories and
+    /// overwriting the existing files.
+    ///
+    /// Fails with [`Error::OutputPathEscapes`], without writing anything, if a file path isn't
+    /// relative to `out_dir`, eg. contains `..`.
+    fn write_to(&self, out_dir: &Path, files: &HashMap<PathBuf, Vec<u8>>) -> BindgenResult<()> {
+        crate::write_files(out_dir, files)
+    }
 }
 
 pub trait BindgenWriter: Sync {
@@ -111,3 +220,57 @@ pub trait BindgenContractGenerator: Sync {
         buffer: &mut Buffer,
     ) -> BindgenResult<String>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_builtin_plugins() {
+        assert!(matches!("unity".parse::<BuiltinPlugins>(), Ok(BuiltinPlugins::Unity)));
+
+        for plugin in [
+            BuiltinPlugins::Typescript,
+            BuiltinPlugins::Unity,
+            BuiltinPlugins::TypeScriptV2,
+            BuiltinPlugins::Recs,
+            BuiltinPlugins::Godot,
+        ] {
+            let parsed = plugin.to_string().parse::<BuiltinPlugins>().unwrap();
+            assert_eq!(parsed.to_string(), plugin.to_string());
+        }
+
+        let err = "unreal".parse::<BuiltinPlugins>().unwrap_err();
+        assert!(matches!(err, Error::UnknownPlugin(name) if name == "unreal"));
+    }
+
+    #[test]
+    fn write_generated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = BuiltinPlugins::Unity.instantiate();
+
+        std::fs::create_dir_all(dir.path().join("Models")).unwrap();
+        std::fs::write(dir.path().join("Models/Position.gen.cs"), "stale").unwrap();
+
+        let files = HashMap::from([
+            (PathBuf::from("Models/Position.gen.cs"), b"class Position {}".to_vec()),
+            (PathBuf::from("Contracts/actions.gen.cs"), b"class Actions {}".to_vec()),
+        ]);
+        plugin.write_to(dir.path(), &files).unwrap();
+
+        for (path, content) in &files {
+            assert_eq!(&std::fs::read(dir.path().join(path)).unwrap(), content);
+        }
+
+        let escaping = HashMap::from([
+            (PathBuf::from("Models/Moves.gen.cs"), b"class Moves {}".to_vec()),
+            (PathBuf::from("../Evil.gen.cs"), b"class Evil {}".to_vec()),
+        ]);
+        let err = plugin.write_to(&dir.path().join("Models"), &escaping).unwrap_err();
+        assert!(
+            matches!(err, Error::OutputPathEscapes(path) if path == Path::new("../Evil.gen.cs"))
+        );
+        assert!(!dir.path().join("Evil.gen.cs").exists());
+        assert!(!dir.path().join("Models/Models/Moves.gen.cs").exists());
+    }
+}
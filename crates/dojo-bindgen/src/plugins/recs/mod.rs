@@ -84,14 +84,12 @@ impl TypescriptRecsPlugin {
     }
 
     fn generated_header() -> String {
-        format!(
-            "
-// Generated by dojo-bindgen on {}. Do not modify this file manually.
+        "
+// Generated by dojo-bindgen. Do not modify this file manually.
 // Import the necessary types from the recs SDK
 // generate again with `sozo build --typescript` 
-",
-            chrono::Utc::now().to_rfc2822()
-        )
+"
+        .to_string()
     }
 
     // Token should be a struct
@@ -0,0 +1,33 @@
+//! Builders of the cainome tokens shared by the tests of the plugins.
+
+use cainome::parser::tokens::{
+    Composite, CompositeInner, CompositeInnerKind, CompositeType, CoreBasic, Token,
+};
+
+pub(crate) fn basic(type_path: &str) -> Token {
+    Token::CoreBasic(CoreBasic { type_path: type_path.to_string() })
+}
+
+pub(crate) fn field(
+    index: usize,
+    name: &str,
+    kind: CompositeInnerKind,
+    token: Token,
+) -> CompositeInner {
+    CompositeInner { index, name: name.to_string(), kind, token }
+}
+
+pub(crate) fn composite(
+    type_path: &str,
+    r#type: CompositeType,
+    inners: Vec<CompositeInner>,
+) -> Composite {
+    Composite {
+        type_path: type_path.to_string(),
+        r#type,
+        generic_args: vec![],
+        inners,
+        is_event: false,
+        alias: None,
+    }
+}
@@ -82,8 +82,58 @@ mod tests {
         assert_eq!(
             result,
             "// Type definition for `core::test::TestStruct` struct\nexport interface TestStruct \
-             {\n\tfieldOrder: string[];\n\tfield1: number;\n\tfield2: number;\n\tfield3: \
-             number;\n}\n"
+             {\n\tfieldOrder: string[];\n\tfield1: bigint;\n\tfield2: bigint;\n\tfield3: \
+             bigint;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_interface_type_mapping() {
+        let mut buff = Buffer::new();
+        let writer = TsInterfaceGenerator;
+
+        let fields = [
+            ("small", "core::integer::u8"),
+            ("timestamp", "core::integer::u64"),
+            ("amount", "core::integer::u128"),
+            ("id", "core::felt252"),
+            ("player", "core::starknet::contract_address::ContractAddress"),
+            ("class", "core::starknet::class_hash::ClassHash"),
+            ("name", "core::byte_array::ByteArray"),
+        ];
+        let token = Composite {
+            type_path: "dojo_examples::models::Balance".to_owned(),
+            inners: fields
+                .iter()
+                .enumerate()
+                .map(|(index, (name, type_path))| CompositeInner {
+                    index,
+                    name: name.to_string(),
+                    kind: CompositeInnerKind::Data,
+                    token: Token::CoreBasic(CoreBasic { type_path: type_path.to_string() }),
+                })
+                .collect(),
+            generic_args: vec![],
+            r#type: CompositeType::Struct,
+            is_event: false,
+            alias: None,
+        };
+
+        let result = writer.generate(&token, &mut buff).unwrap();
+        assert_eq!(
+            result,
+            "// Type definition for `dojo_examples::models::Balance` struct
+export interface Balance {
+\tfieldOrder: string[];
+\tsmall: number;
+\ttimestamp: number;
+\tamount: bigint;
+\tid: bigint;
+\tplayer: bigint;
+\tclass: bigint;
+\tname: string;
+}
+"
         );
     }
 
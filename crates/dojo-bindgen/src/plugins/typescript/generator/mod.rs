@@ -49,21 +49,27 @@ pub(crate) fn generate_type_init(token: &Composite) -> String {
     )
 }
 
+/// TypeScript type of a Cairo type.
+///
+/// Integers up to `u64` are mapped to `number`. Larger integers and field elements, e.g.
+/// `felt252`, `ContractAddress` and `ClassHash`, don't fit in a `number` without losing precision
+/// and are mapped to `bigint`.
 #[derive(Debug)]
 pub(crate) struct JsType(String);
 impl From<&str> for JsType {
     fn from(value: &str) -> Self {
         match value {
-            "felt252" => JsType("number".to_owned()),
-            "ContractAddress" => JsType("string".to_owned()),
+            "felt252" => JsType("bigint".to_owned()),
+            "ContractAddress" => JsType("bigint".to_owned()),
+            "ClassHash" => JsType("bigint".to_owned()),
             "ByteArray" => JsType("string".to_owned()),
             "u8" => JsType("number".to_owned()),
             "u16" => JsType("number".to_owned()),
             "u32" => JsType("number".to_owned()),
             "u64" => JsType("number".to_owned()),
-            "u128" => JsType("number".to_owned()),
-            "u256" => JsType("number".to_owned()),
-            "U256" => JsType("number".to_owned()),
+            "u128" => JsType("bigint".to_owned()),
+            "u256" => JsType("bigint".to_owned()),
+            "U256" => JsType("bigint".to_owned()),
             "bool" => JsType("boolean".to_owned()),
             _ => JsType(value.to_owned()),
         }
@@ -102,16 +108,17 @@ pub(crate) struct JsDefaultValue(String);
 impl From<&str> for JsDefaultValue {
     fn from(value: &str) -> Self {
         match value {
-            "felt252" => JsDefaultValue("0".to_string()),
-            "ContractAddress" => JsDefaultValue("\"\"".to_string()),
+            "felt252" => JsDefaultValue("0n".to_string()),
+            "ContractAddress" => JsDefaultValue("0n".to_string()),
+            "ClassHash" => JsDefaultValue("0n".to_string()),
             "ByteArray" => JsDefaultValue("\"\"".to_string()),
             "u8" => JsDefaultValue("0".to_string()),
             "u16" => JsDefaultValue("0".to_string()),
             "u32" => JsDefaultValue("0".to_string()),
             "u64" => JsDefaultValue("0".to_string()),
-            "u128" => JsDefaultValue("0".to_string()),
-            "u256" => JsDefaultValue("0".to_string()),
-            "U256" => JsDefaultValue("0".to_string()),
+            "u128" => JsDefaultValue("0n".to_string()),
+            "u256" => JsDefaultValue("0n".to_string()),
+            "U256" => JsDefaultValue("0n".to_string()),
             "bool" => JsDefaultValue("false".to_string()),
             _ => JsDefaultValue(value.to_string()),
         }
@@ -197,7 +204,7 @@ mod tests {
             }))
         );
         assert_eq!(
-            "number",
+            "bigint",
             JsType::from(&Token::CoreBasic(CoreBasic { type_path: "core::felt252".to_owned() }))
         )
     }
@@ -205,7 +212,7 @@ mod tests {
     #[test]
     fn test_tuple_type() {
         assert_eq!(
-            "[number, number]",
+            "[number, bigint]",
             JsType::from(&Token::Tuple(Tuple {
                 type_path: "(core::integer::u8,core::integer::u128)".to_owned(),
                 inners: vec![
@@ -219,7 +226,7 @@ mod tests {
     #[test]
     fn test_array_type() {
         assert_eq!(
-            "Array<[number, number]>",
+            "Array<[number, bigint]>",
             JsType::from(&Token::Array(Array {
                 type_path: "core::array::Span<(core::integer::u8,core::integer::u128)>".to_owned(),
                 inner: Box::new(Token::Tuple(Tuple {
@@ -243,7 +250,7 @@ mod tests {
             }))
         );
         assert_eq!(
-            "0",
+            "0n",
             JsDefaultValue::from(&Token::CoreBasic(CoreBasic {
                 type_path: "core::felt252".to_owned()
             }))
@@ -253,7 +260,7 @@ mod tests {
     #[test]
     fn test_tuple_default_value() {
         assert_eq!(
-            "[0, 0]",
+            "[0, 0n]",
             JsDefaultValue::from(&Token::Tuple(Tuple {
                 type_path: "(core::integer::u8,core::integer::u128)".to_owned(),
                 inners: vec![
@@ -267,7 +274,7 @@ mod tests {
     #[test]
     fn test_array_default_value() {
         assert_eq!(
-            "[[0, 0]]",
+            "[[0, 0n]]",
             JsDefaultValue::from(&Token::Array(Array {
                 type_path: "core::array::Span<(core::integer::u8,core::integer::u128)>".to_owned(),
                 inner: Box::new(Token::Tuple(Tuple {
@@ -292,9 +299,9 @@ mod tests {
         // after
         let expected = "{
 \t\t\tfieldOrder: ['field1', 'field2', 'field3'],
-\t\t\tfield1: 0,
-\t\t\tfield2: 0,
-\t\t\tfield3: 0,
+\t\t\tfield1: 0n,
+\t\t\tfield2: 0n,
+\t\t\tfield3: 0n,
 \t\t}";
         assert_eq!(expected, init_type);
     }
@@ -181,7 +181,7 @@ mod tests {
             buffer[0],
             "export const schema: OnchainDashSchemaType = {\n\tonchain_dash: {\n\t\tTestStruct: \
              {\n\t\t\tfieldOrder: ['field1', 'field2', 'field3'],\n\t\t\tfield1: \
-             0,\n\t\t\tfield2: 0,\n\t\t\tfield3: 0,\n\t\t},\n\t},\n};"
+             0n,\n\t\t\tfield2: 0n,\n\t\t\tfield3: 0n,\n\t\t},\n\t},\n};"
         );
 
         let token_2 = create_test_struct_token("AvailableTheme");
@@ -191,9 +191,9 @@ mod tests {
             buffer[0],
             "export const schema: OnchainDashSchemaType = {\n\tonchain_dash: {\n\t\tTestStruct: \
              {\n\t\t\tfieldOrder: ['field1', 'field2', 'field3'],\n\t\t\tfield1: \
-             0,\n\t\t\tfield2: 0,\n\t\t\tfield3: 0,\n\t\t},\n\t\tAvailableTheme: \
+             0n,\n\t\t\tfield2: 0n,\n\t\t\tfield3: 0n,\n\t\t},\n\t\tAvailableTheme: \
              {\n\t\t\tfieldOrder: ['field1', 'field2', 'field3'],\n\t\t\tfield1: \
-             0,\n\t\t\tfield2: 0,\n\t\t\tfield3: 0,\n\t\t},\n\t},\n};"
+             0n,\n\t\t\tfield2: 0n,\n\t\t\tfield3: 0n,\n\t\t},\n\t},\n};"
         );
     }
 
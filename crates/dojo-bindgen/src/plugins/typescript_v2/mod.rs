@@ -84,10 +84,7 @@ impl TypeScriptV2Plugin {
     }
 
     fn generate_header() -> String {
-        format!(
-            "// Generated by dojo-bindgen on {}. Do not modify this file manually.\n",
-            chrono::Utc::now().to_rfc2822()
-        )
+        "// Generated by dojo-bindgen. Do not modify this file manually.\n".to_string()
     }
 
     fn generate_imports() -> String {
@@ -1,24 +1,246 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use async_trait::async_trait;
-use cainome::parser::tokens::{Composite, CompositeType, Function, FunctionOutputKind, Token};
+use cainome::parser::tokens::{
+    Composite, CompositeInnerKind, CompositeType, Function, FunctionOutputKind, StateMutability,
+    Token,
+};
+use convert_case::{Case, Casing};
 use dojo_world::contracts::naming::{self, get_namespace_from_tag};
+use starknet::core::utils::get_selector_from_name;
 
-use crate::error::BindgenResult;
-use crate::plugins::BuiltinPlugin;
+use crate::error::{BindgenResult, Error};
+use crate::merge::MergedAbi;
+use crate::plugins::{BuiltinPlugin, ReservedWords};
 use crate::{compare_tokens_by_type_name, DojoContract, DojoData, DojoModel};
 
+#[cfg(test)]
+mod tests;
+
+/// Options controlling the code generated by the [`UnityPlugin`].
+#[derive(Debug, Clone)]
+pub struct UnityPluginConfig {
+    /// Whether to emit empty `Start()` and `Update()` MonoBehaviour lifecycle methods in the
+    /// generated models.
+    pub lifecycle_methods: bool,
+    /// The C# type Cairo `usize` is mapped to. Defaults to `uint`.
+    pub usize_type: String,
+    /// Whether to emit models and structs as immutable positional `record struct`s instead of
+    /// mutable classes and structs.
+    pub records: bool,
+    /// Generated members only compiled in the Unity editor, ie. wrapped in `#if UNITY_EDITOR`.
+    pub editor_only: HashSet<UnityMember>,
+    /// Whether to generate, for each contract, an interface of its systems and a mock
+    /// implementation recording the calls and returning canned transaction hashes.
+    pub mocks: bool,
+    /// The identifiers escaped when used as field, argument or method names. Defaults to the C#
+    /// keywords, escaped with `@`.
+    pub reserved_words: ReservedWords,
+    /// How the Cairo types which have no C# equivalent are handled. Defaults to
+    /// [`UnknownTypePolicy::Error`].
+    pub unknown_types: UnknownTypePolicy,
+    /// Whether to map Cairo `Option<T>` to C# nullables, ie. `T?` for value types and `T` for
+    /// reference types, `null` being `None`. Otherwise `Option<T>` is generated as a record like
+    /// the other enums. Defaults to `true`.
+    pub nullable_options: bool,
+}
+
+impl Default for UnityPluginConfig {
+    fn default() -> Self {
+        Self {
+            lifecycle_methods: false,
+            usize_type: "uint".to_string(),
+            records: false,
+            editor_only: HashSet::new(),
+            mocks: false,
+            reserved_words: ReservedWords::new(CSHARP_KEYWORDS.iter().copied(), "@"),
+            unknown_types: UnknownTypePolicy::default(),
+            nullable_options: true,
+        }
+    }
+}
+
+/// The C# keywords, which can't be used as identifiers unless prefixed with `@`.
+pub const CSHARP_KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// The optional members generated in the Unity models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnityMember {
+    /// The parameterless constructor zero-initializing the fields.
+    DefaultConstructor,
+    /// The `Keys` struct, `GetKeys()` and `Key()` methods.
+    Keys,
+    /// The `Start()` and `Update()` lifecycle methods.
+    LifecycleMethods,
+}
+
+/// How the [`UnityPlugin`] handles the Cairo types it can't map to a C# type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownTypePolicy {
+    /// Fails the generation, listing the unknown types.
+    #[default]
+    Error,
+    /// Uses the Cairo type name as is, which most likely doesn't compile.
+    Passthrough,
+    /// Uses the Cairo type name, and emits an empty placeholder type of that name marked with a
+    /// `TODO` in `Placeholders.gen.cs`.
+    Placeholder,
+}
+
 #[derive(Debug)]
-pub struct UnityPlugin {}
+pub struct UnityPlugin {
+    config: UnityPluginConfig,
+    /// The unknown types encountered while mapping types, by type path, with their name and the
+    /// fields using them.
+    unknown_types: Mutex<BTreeMap<String, (String, BTreeSet<String>)>>,
+    /// The field whose type is being mapped, eg. `Position.vec`.
+    field: Mutex<Option<String>>,
+    /// The C# namespaces of the types sharing their name with another type, by type path.
+    namespaces: Mutex<HashMap<String, String>>,
+}
 
 impl UnityPlugin {
     pub fn new() -> Self {
-        Self {}
+        Self::with_config(UnityPluginConfig::default())
+    }
+
+    pub fn with_config(config: UnityPluginConfig) -> Self {
+        Self {
+            config,
+            unknown_types: Mutex::new(BTreeMap::new()),
+            field: Mutex::new(None),
+            namespaces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Puts the types sharing their name with another type in a C# namespace, so they don't
+    // collide. Types are given as (type path, type name, namespace) tuples.
+    fn namespace_colliding_types(&self, types: Vec<(String, String, String)>) {
+        let mut paths = HashMap::<String, HashSet<String>>::new();
+        for (type_path, name, _) in &types {
+            paths.entry(name.clone()).or_default().insert(base_type_path(type_path).to_string());
+        }
+
+        let mut namespaces = self.namespaces.lock().unwrap();
+        namespaces.clear();
+        for (type_path, name, namespace) in types {
+            if paths[&name].len() > 1 {
+                namespaces.insert(base_type_path(&type_path).to_string(), namespace);
+            }
+        }
+    }
+
+    // Returns the C# namespace of a type, if it's emitted in one
+    fn csharp_namespace(&self, type_path: &str) -> Option<String> {
+        self.namespaces.lock().unwrap().get(base_type_path(type_path)).cloned()
+    }
+
+    // Wraps the definition of a type in its C# namespace, if it's emitted in one
+    fn in_namespace(&self, type_path: &str, code: String) -> String {
+        let Some(namespace) = self.csharp_namespace(type_path) else { return code };
+
+        let code = code
+            .trim_end()
+            .lines()
+            .map(|line| if line.is_empty() { String::new() } else { format!("    {line}") })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!("\nnamespace {namespace} {{{code}\n}}\n")
+    }
+
+    // Escapes a field, argument or method name if it's a reserved word
+    fn ident(&self, name: &str) -> String {
+        self.config.reserved_words.escape(name)
     }
 
     // Maps cairo types to C#/Unity SDK defined types
-    fn map_type(token: &Token) -> String {
+    fn map_type(&self, token: &Token) -> String {
+        if let Some(inner) = self.nullable_option(token) {
+            let nullable = if UnityPlugin::is_value_type(inner) { "?" } else { "" };
+            return format!("{}{nullable}", self.map_type(inner));
+        }
+
         match token.type_name().as_str() {
             "i8" => "sbyte".to_string(),
             "i16" => "short".to_string(),
@@ -30,16 +252,19 @@ impl UnityPlugin {
             "u32" => "uint".to_string(),
             "u64" => "ulong".to_string(),
             "u128" => "BigInteger".to_string(),
+            // Round-trips through its low and high u128 parts, the low part being the first felt.
             "u256" => "BigInteger".to_string(),
-            "usize" => "uint".to_string(),
+            "usize" => self.config.usize_type.clone(),
             "felt252" => "FieldElement".to_string(),
-            "bytes31" => "string".to_string(),
+            "bytes31" => "Bytes31".to_string(),
             "ClassHash" => "FieldElement".to_string(),
             "ContractAddress" => "FieldElement".to_string(),
+            "bool" => "bool".to_string(),
             "ByteArray" => "string".to_string(),
+            "Bytes" => "byte[]".to_string(),
             "array" => {
                 if let Token::Array(array) = token {
-                    format!("{}[]", UnityPlugin::map_type(&array.inner))
+                    format!("List<{}>", self.map_type(&array.inner))
                 } else {
                     panic!("Invalid array token: {:?}", token);
                 }
@@ -49,7 +274,7 @@ impl UnityPlugin {
                     let inners = tuple
                         .inners
                         .iter()
-                        .map(UnityPlugin::map_type)
+                        .map(|t| self.map_type(t))
                         .collect::<Vec<String>>()
                         .join(", ");
                     format!("({})", inners)
@@ -66,7 +291,28 @@ impl UnityPlugin {
             }
 
             _ => {
-                let mut type_name = token.type_name().to_string();
+                let mut type_name = match token {
+                    Token::Composite(composite) => {
+                        match self.csharp_namespace(&composite.type_path) {
+                            Some(namespace) => format!("{namespace}.{}", token.type_name()),
+                            None => token.type_name(),
+                        }
+                    }
+                    _ => token.type_name().to_string(),
+                };
+
+                if let Token::CoreBasic(basic) = token {
+                    if basic.type_path != "()" {
+                        let field = self.field.lock().unwrap().clone();
+                        self.unknown_types
+                            .lock()
+                            .unwrap()
+                            .entry(basic.type_path.clone())
+                            .or_insert_with(|| (type_name.clone(), BTreeSet::new()))
+                            .1
+                            .extend(field);
+                    }
+                }
 
                 if let Token::Composite(composite) = token {
                     if !composite.generic_args.is_empty() {
@@ -75,7 +321,7 @@ impl UnityPlugin {
                             composite
                                 .generic_args
                                 .iter()
-                                .map(|(_, t)| UnityPlugin::map_type(t))
+                                .map(|(_, t)| self.map_type(t))
                                 .collect::<Vec<_>>()
                                 .join(", ")
                         )
@@ -87,11 +333,109 @@ impl UnityPlugin {
         }
     }
 
+    // Returns the type wrapped by a Cairo `Option`, if the token is one
+    // and it's mapped to a C# nullable
+    fn nullable_option<'a>(&self, token: &'a Token) -> Option<&'a Token> {
+        if !self.config.nullable_options {
+            return None;
+        }
+
+        let Token::Composite(t) = token else { return None };
+        if !t.type_path.starts_with("core::option::Option") {
+            return None;
+        }
+
+        t.generic_args
+            .first()
+            .map(|(_, inner)| inner)
+            .or_else(|| t.inners.iter().find(|f| f.name == "Some").map(|f| &f.token))
+            .filter(|inner| !matches!(inner, Token::GenericArg(_)))
+    }
+
+    // Returns the `T` and `E` types of a Cairo `Result<T, E>`, if the token is one
+    fn result_types(token: &Token) -> Option<(&Token, &Token)> {
+        let Token::Composite(t) = token else { return None };
+        match t.generic_args.as_slice() {
+            [(_, ok), (_, err)] if t.type_path.starts_with("core::result::Result") => {
+                Some((ok, err))
+            }
+            _ => None,
+        }
+    }
+
+    // Whether the C# type a token is mapped to is a value type, which must be suffixed with `?`
+    // to be nullable
+    // Strings, arrays, felts and the enum records are reference types
+    fn is_value_type(token: &Token) -> bool {
+        if token.type_name() == "u256" {
+            return true;
+        }
+
+        match token {
+            Token::Array(_) | Token::GenericArg(_) => false,
+            Token::Composite(t) => {
+                matches!(t.r#type, CompositeType::Struct)
+                    && !matches!(t.type_name().as_str(), "ByteArray" | "Bytes")
+            }
+            _ => !matches!(
+                token.type_name().as_str(),
+                "felt252" | "ClassHash" | "ContractAddress" | "ByteArray"
+            ),
+        }
+    }
+
+    // Maps the type of the `field` of `owner` (a struct, an enum, a system...)
+    // The field is recorded as a usage of the unknown types it references
+    fn map_field_type(&self, owner: &str, field: &str, token: &Token) -> String {
+        let previous = self.field.lock().unwrap().replace(format!("{owner}.{field}"));
+        let mapped = self.map_type(token);
+        *self.field.lock().unwrap() = previous;
+        mapped
+    }
+
+    // Applies the unknown types policy to the unknown types encountered during the generation.
+    // In placeholder mode, the placeholder types are added to the generated files.
+    fn handle_unknown_types(&self, out: &mut HashMap<PathBuf, Vec<u8>>) -> BindgenResult<()> {
+        let unknown_types = std::mem::take(&mut *self.unknown_types.lock().unwrap());
+        if unknown_types.is_empty() {
+            return Ok(());
+        }
+
+        match self.config.unknown_types {
+            UnknownTypePolicy::Error => Err(Error::UnsupportedTypes(
+                unknown_types
+                    .into_iter()
+                    .map(|(type_path, (_, fields))| {
+                        if fields.is_empty() {
+                            type_path
+                        } else {
+                            let fields = fields.into_iter().collect::<Vec<_>>().join(", ");
+                            format!("{type_path} (used by {fields})")
+                        }
+                    })
+                    .collect(),
+            )),
+            UnknownTypePolicy::Passthrough => Ok(()),
+            UnknownTypePolicy::Placeholder => {
+                let mut placeholders = UnityPlugin::generated_header();
+                for (type_path, (name, _)) in unknown_types {
+                    placeholders += &format!(
+                        "
+// TODO: `{type_path}` is not supported by the bindgen, replace this placeholder.
+public struct {name} {{
+}}
+"
+                    );
+                }
+
+                out.insert(PathBuf::from("Placeholders.gen.cs"), placeholders.into_bytes());
+                Ok(())
+            }
+        }
+    }
+
     fn generated_header() -> String {
-        format!(
-            "// Generated by dojo-bindgen on {}. Do not modify this file manually.\n",
-            chrono::Utc::now().to_rfc2822()
-        )
+        "// Generated by dojo-bindgen. Do not modify this file manually.\n".to_string()
     }
 
     fn contract_imports() -> String {
@@ -115,6 +459,7 @@ using Dojo.Starknet;
 using System.Reflection;
 using System.Linq;
 using System.Collections.Generic;
+using dojo_bindings;
 using Enum = Dojo.Starknet.Enum;
 "
         .to_string()
@@ -123,11 +468,35 @@ using Enum = Dojo.Starknet.Enum;
     // Token should be a struct
     // This will be formatted into a C# struct
     // using C# and unity SDK types
-    fn format_struct(token: &Composite) -> String {
+    fn format_struct(&self, token: &Composite) -> String {
+        let code = self.format_struct_definition(token);
+        self.in_namespace(&token.type_path, code)
+    }
+
+    fn format_struct_definition(&self, token: &Composite) -> String {
+        if self.config.records {
+            return format!(
+                "
+// Type definition for `{}` struct
+[Serializable]
+public record struct {}({});
+",
+                token.type_path,
+                token.type_name(),
+                self.format_record_parameters(token)
+            );
+        }
+
         let fields = token
             .inners
             .iter()
-            .map(|field| format!("public {} {};", UnityPlugin::map_type(&field.token), field.name))
+            .map(|field| {
+                format!(
+                    "public {} {};",
+                    self.map_field_type(&token.type_name(), &field.name, &field.token),
+                    self.ident(&field.name)
+                )
+            })
             .collect::<Vec<String>>()
             .join("\n    ");
 
@@ -148,7 +517,12 @@ public struct {} {{
     // Token should be an enum
     // This will be formatted into a C# enum
     // Enum is mapped using index of cairo enum
-    fn format_enum(token: &Composite) -> String {
+    fn format_enum(&self, token: &Composite) -> String {
+        let code = self.format_enum_definition(token);
+        self.in_namespace(&token.type_path, code)
+    }
+
+    fn format_enum_definition(&self, token: &Composite) -> String {
         let name = token.type_name();
         let mut name_with_generics = name.clone();
         if !token.generic_args.is_empty() {
@@ -166,7 +540,8 @@ public abstract record {}() : Enum {{",
         );
 
         for field in &token.inners {
-            let type_name = UnityPlugin::map_type(&field.token).replace(['(', ')'], "");
+            let type_name =
+                self.map_field_type(&name, &field.name, &field.token).replace(['(', ')'], "");
 
             result += format!(
                 "\n    public record {}({}) : {name_with_generics};",
@@ -176,44 +551,375 @@ public abstract record {}() : Enum {{",
             .as_str();
         }
 
+        result += UnityPlugin::format_enum_from_felts(token, &name_with_generics).as_str();
         result += "\n}\n";
 
         result
     }
 
+    // Formats the `FromFelts` deserializer of an enum
+    // The variant tag is bounds checked, and a `DeserializationException` is thrown
+    // if it doesn't match any variant.
+    // Only generated for enums whose variants don't carry any data.
+    fn format_enum_from_felts(token: &Composite, name: &str) -> String {
+        if !UnityPlugin::is_unit_enum(token) {
+            return String::new();
+        }
+
+        let cases = token
+            .inners
+            .iter()
+            .enumerate()
+            .map(|(i, field)| format!("case {i}: return new {}();", field.name))
+            .collect::<Vec<String>>()
+            .join("\n            ");
+
+        format!(
+            "
+
+    // Deserializes the enum from its felts representation, starting at `offset`
+    public static {name} FromFelts(FieldElement[] felts, ref int offset) {{
+        if (offset >= felts.Length) {{
+            throw new DeserializationException(\"Missing variant tag for enum `{name}`\");
+        }}
+
+        var tag = FeltsReader.ToBigInteger(felts[offset]);
+        if (tag >= {count}) {{
+            throw new DeserializationException($\"Invalid variant tag {{tag}} for enum `{name}`\");
+        }}
+        offset++;
+
+        switch ((int)tag) {{
+            {cases}
+            default: throw new DeserializationException($\"Invalid variant tag {{tag}} for enum \
+             `{name}`\");
+        }}
+    }}",
+            count = token.inners.len(),
+        )
+    }
+
+    // Whether none of the variants of the enum carry any data
+    fn is_unit_enum(token: &Composite) -> bool {
+        token.inners.iter().all(|field| match &field.token {
+            Token::CoreBasic(basic) => basic.type_path == "()",
+            Token::Tuple(tuple) => tuple.inners.is_empty(),
+            _ => false,
+        })
+    }
+
+    // Formats an expression reading a value of the given type from `felts`, starting at `offset`
+    // which is advanced past the value
+    // Returns `None` for the types which can't be read, eg. of a variable length
+    fn format_felts_reader(
+        &self,
+        token: &Token,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> Option<String> {
+        let read = "FeltsReader.ToBigInteger(felts[offset++])";
+
+        match token.type_name().as_str() {
+            "felt252" | "ClassHash" | "ContractAddress" => return Some("felts[offset++]".into()),
+            "bool" => return Some(format!("{read} != 0")),
+            "u8" | "u16" | "u32" | "u64" | "usize" => {
+                return Some(format!("({}){read}", self.map_type(token)));
+            }
+            "u128" => return Some(read.to_string()),
+            // Serialized as its low and high parts
+            "u256" => return Some(format!("{read} + ({read} << 128)")),
+            "bytes31" => return Some("Bytes31.FromFelts(felts, ref offset)".to_string()),
+            "ByteArray" => {
+                return Some("ByteArrayDeserializer.FromFelts(felts, ref offset)".into())
+            }
+            "Bytes" => return None,
+            _ => {}
+        }
+
+        // The elements of a tuple literal are evaluated, and thus read, in order
+        if let Token::Tuple(tuple) = token {
+            if tuple.inners.is_empty() {
+                return None;
+            }
+
+            let elements = tuple
+                .inners
+                .iter()
+                .map(|t| self.format_felts_reader(t, handled_tokens))
+                .collect::<Option<Vec<String>>>()?;
+            return Some(format!("({})", elements.join(", ")));
+        }
+
+        // The variant tag is followed by the value for `Some`, and nothing for `None`
+        if let Some(inner) = self.nullable_option(token) {
+            let reader = self.format_felts_reader(inner, handled_tokens)?;
+            return Some(format!(
+                "(FeltsReader.ReadVariant(felts, ref offset, 2, \"Option\") == 0 ? ({}){reader} : \
+                 null)",
+                self.map_type(token)
+            ));
+        }
+
+        // Generic enums can't be read in general, but `Result` is read like `Option`: the variant
+        // tag, followed by the value of the `Ok` or `Err` variant
+        if let Some((ok, err)) = UnityPlugin::result_types(token) {
+            let ok = self.format_felts_reader(ok, handled_tokens)?;
+            let err = self.format_felts_reader(err, handled_tokens)?;
+            let ty = self.map_type(token);
+            return Some(format!(
+                "(FeltsReader.ReadVariant(felts, ref offset, 2, \"Result\") == 0 ? ({ty})new \
+                 {ty}.Ok({ok}) : new {ty}.Err({err}))"
+            ));
+        }
+
+        let Token::Composite(t) = token else { return None };
+        let t = handled_tokens.get(&t.type_path).unwrap_or(t);
+        if !t.generic_args.is_empty() {
+            return None;
+        }
+
+        match t.r#type {
+            CompositeType::Struct => {
+                let fields = t
+                    .inners
+                    .iter()
+                    .map(|field| {
+                        let reader = self.format_felts_reader(&field.token, handled_tokens)?;
+                        Some(format!("{} = {reader}", self.ident(&field.name)))
+                    })
+                    .collect::<Option<Vec<String>>>()?;
+
+                Some(format!("new {} {{ {} }}", self.map_type(token), fields.join(", ")))
+            }
+            CompositeType::Enum if UnityPlugin::is_unit_enum(t) => {
+                Some(format!("{}.FromFelts(felts, ref offset)", self.map_type(token)))
+            }
+            _ => None,
+        }
+    }
+
+    // Shared types used by the generated deserializers
+    fn format_deserialization_helpers() -> String {
+        let mut out = UnityPlugin::generated_header();
+        out += "using System;
+using System.Collections.Generic;
+using System.Globalization;
+using System.Numerics;
+using System.Text;
+using Dojo.Starknet;
+
+// Thrown when felts can't be deserialized into the expected type
+public class DeserializationException : Exception {
+    public DeserializationException(string message) : base(message) { }
+}
+
+public static class FeltsReader {
+    // Converts a felt into its (unsigned) integer value
+    public static BigInteger ToBigInteger(FieldElement felt) {
+        return BigInteger.Parse(\"0\" + felt.Hex().Substring(2), NumberStyles.AllowHexSpecifier);
+    }
+
+    // Converts an unsigned integer into its big-endian bytes, left padded to `length` bytes
+    public static byte[] ToBytes(BigInteger value, int length) {
+        var bytes = value.ToByteArray(isUnsigned: true, isBigEndian: true);
+        var padded = new byte[Math.Max(length, bytes.Length)];
+        Array.Copy(bytes, 0, padded, padded.Length - bytes.Length, bytes.Length);
+        return padded;
+    }
+
+    // Converts big-endian bytes into a felt
+    public static FieldElement FromBytes(byte[] bytes, int offset, int count) {
+        var hex = BitConverter.ToString(bytes, offset, count).Replace(\"-\", \"\");
+        return new FieldElement(\"0x\" + (hex.Length > 0 ? hex : \"0\"));
+    }
+
+    // Reads the variant tag of an enum with `count` variants, starting at `offset`
+    public static int ReadVariant(FieldElement[] felts, ref int offset, int count, string name) {
+        if (offset >= felts.Length) {
+            throw new DeserializationException($\"Missing variant tag for enum `{name}`\");
+        }
+
+        var tag = ToBigInteger(felts[offset]);
+        if (tag >= count) {
+            throw new DeserializationException($\"Invalid variant tag {tag} for enum `{name}`\");
+        }
+        offset++;
+
+        return (int)tag;
+    }
+}
+
+// Cairo `bytes31`, up to 31 bytes packed in a single felt
+[Serializable]
+public struct Bytes31 {
+    public FieldElement value;
+
+    public Bytes31(FieldElement value) {
+        this.value = value;
+    }
+
+    public static Bytes31 FromBytes(byte[] bytes) {
+        if (bytes.Length > 31) {
+            throw new ArgumentException($\"bytes31 can't hold {bytes.Length} bytes\");
+        }
+        return new Bytes31(FeltsReader.FromBytes(bytes, 0, bytes.Length));
+    }
+
+    // Returns the bytes without the leading zeros
+    public byte[] ToBytes() {
+        return FeltsReader.ToBytes(FeltsReader.ToBigInteger(value), 0);
+    }
+
+    // Deserializes the value from its felts representation, starting at `offset`
+    public static Bytes31 FromFelts(FieldElement[] felts, ref int offset) {
+        if (offset >= felts.Length) {
+            throw new DeserializationException(\"Missing felt for `bytes31`\");
+        }
+        return new Bytes31(felts[offset++]);
+    }
+}
+
+// Serialization of the alexandria `Bytes` type: the size in bytes, followed by the bytes
+// packed in 16 bytes words. The last word holds the remaining bytes.
+public static class BytesSerializer {
+    public static FieldElement[] Serialize(byte[] bytes) {
+        var words = (bytes.Length + 15) / 16;
+        var felts = new List<FieldElement> { new FieldElement(bytes.Length), new FieldElement(words) };
+        for (var i = 0; i < words; i++) {
+            felts.Add(FeltsReader.FromBytes(bytes, i * 16, Math.Min(16, bytes.Length - i * 16)));
+        }
+        return felts.ToArray();
+    }
+
+    // Deserializes the bytes from their felts representation, starting at `offset`
+    public static byte[] FromFelts(FieldElement[] felts, ref int offset) {
+        if (offset + 2 > felts.Length) {
+            throw new DeserializationException(\"Missing felts for `Bytes`\");
+        }
+        var size = (int)FeltsReader.ToBigInteger(felts[offset]);
+        var words = (int)FeltsReader.ToBigInteger(felts[offset + 1]);
+        if (words != (size + 15) / 16 || offset + 2 + words > felts.Length) {
+            throw new DeserializationException($\"Invalid `Bytes` of {size} bytes in {words} words\");
+        }
+        offset += 2;
+
+        var bytes = new byte[size];
+        for (var i = 0; i < words; i++) {
+            var length = Math.Min(16, size - i * 16);
+            var word = FeltsReader.ToBytes(FeltsReader.ToBigInteger(felts[offset++]), length);
+            if (word.Length > length) {
+                throw new DeserializationException(\"Invalid `Bytes` word\");
+            }
+            Array.Copy(word, 0, bytes, i * 16, length);
+        }
+        return bytes;
+    }
+}
+
+// Deserialization of the Cairo `ByteArray`, ie. UTF-8 strings: the number of full 31 bytes
+// words, the full words, then the pending word holding the remaining bytes and its length.
+public static class ByteArrayDeserializer {
+    // Deserializes the string from its felts representation, starting at `offset`
+    public static string FromFelts(FieldElement[] felts, ref int offset) {
+        if (offset >= felts.Length) {
+            throw new DeserializationException(\"Missing felts for `ByteArray`\");
+        }
+        var words = (int)FeltsReader.ToBigInteger(felts[offset]);
+        if (offset + 1 + words + 2 > felts.Length) {
+            throw new DeserializationException($\"Missing felts for `ByteArray` of {words} words\");
+        }
+        offset++;
+
+        var bytes = new List<byte>();
+        for (var i = 0; i < words; i++) {
+            var word = FeltsReader.ToBytes(FeltsReader.ToBigInteger(felts[offset++]), 31);
+            if (word.Length > 31) {
+                throw new DeserializationException(\"Invalid `ByteArray` word\");
+            }
+            bytes.AddRange(word);
+        }
+
+        var pending = FeltsReader.ToBigInteger(felts[offset++]);
+        var pendingLength = (int)FeltsReader.ToBigInteger(felts[offset++]);
+        if (pendingLength > 30 || pending >> (8 * pendingLength) != 0) {
+            throw new DeserializationException(\"Invalid `ByteArray` pending word\");
+        }
+        if (pendingLength > 0) {
+            bytes.AddRange(FeltsReader.ToBytes(pending, pendingLength));
+        }
+
+        return Encoding.UTF8.GetString(bytes.ToArray());
+    }
+}
+";
+
+        out
+    }
+
     // Token should be a model
     // This will be formatted into a C# class inheriting from ModelInstance
     // Fields are mapped using C# and unity SDK types
-    fn format_model(namespace: &str, model: &Composite) -> String {
+    fn format_model(
+        &self,
+        namespace: &str,
+        model: &Composite,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> String {
+        if self.config.records {
+            return self.format_model_record(namespace, model, handled_tokens);
+        }
+
         let fields = model
             .inners
             .iter()
             .map(|field| {
+                // Key members are flagged so the SDK can tell them apart from the values
+                let key = if matches!(field.kind, CompositeInnerKind::Key) {
+                    "[Key]\n        "
+                } else {
+                    ""
+                };
+
                 format!(
-                    "[ModelField(\"{}\")]\n        public {} {};",
-                    field.name,
-                    UnityPlugin::map_type(&field.token),
+                    "{key}[ModelField(\"{}\")]\n        public {} {};",
                     field.name,
+                    self.map_field_type(&model.type_name(), &field.name, &field.token),
+                    self.ident(&field.name),
                 )
             })
             .collect::<Vec<String>>()
             .join("\n\n        ");
 
+        let constructor = self.editor_guard(
+            UnityMember::DefaultConstructor,
+            self.format_model_constructor(model, handled_tokens),
+        );
+        let keys =
+            self.editor_guard(UnityMember::Keys, self.format_model_keys(model, handled_tokens));
+        let accessors = self.format_model_accessors(model, handled_tokens);
+
+        let lifecycle_methods = if self.config.lifecycle_methods {
+            "
+        // Start is called before the first frame update
+        void Start() {
+        }
+    
+        // Update is called once per frame
+        void Update() {
+        }
+"
+        } else {
+            ""
+        };
+        let lifecycle_methods =
+            self.editor_guard(UnityMember::LifecycleMethods, lifecycle_methods.to_string());
+
         format!(
             "
 namespace {namespace} {{
     // Model definition for `{}` model
     public class {} : ModelInstance {{
         {}
-
-        // Start is called before the first frame update
-        void Start() {{
-        }}
-    
-        // Update is called once per frame
-        void Update() {{
-        }}
-    }}
+{constructor}{keys}{accessors}{lifecycle_methods}    }}
 }}
 
         ",
@@ -223,255 +929,624 @@ namespace {namespace} {{
         )
     }
 
-    // Handles a model definition and its referenced tokens
-    // Will map all structs and enums to C# types
-    // Will format the model into a C# class
-    fn handle_model(
+    // Formats the static accessors deserializing a nested struct field of the model from the
+    // felts of the model values (ie. without the keys), without deserializing the other fields
+    // Only generated for the fields at a fixed offset, and whose struct has a fixed length.
+    fn format_model_accessors(
         &self,
-        model: &DojoModel,
-        handled_tokens: &mut HashMap<String, Composite>,
+        model: &Composite,
+        handled_tokens: &HashMap<String, Composite>,
     ) -> String {
-        let mut out = String::new();
-        out += UnityPlugin::generated_header().as_str();
-        out += UnityPlugin::model_imports().as_str();
-
-        let mut model_struct: Option<&Composite> = None;
-        let tokens = &model.tokens;
-
-        let mut sorted_structs = tokens.structs.clone();
-        sorted_structs.sort_by(compare_tokens_by_type_name);
+        let mut accessors = String::new();
+        let mut offset = Some(0);
 
-        let mut sorted_enums = tokens.enums.clone();
-        sorted_enums.sort_by(compare_tokens_by_type_name);
+        for field in model.inners.iter().filter(|f| !matches!(f.kind, CompositeInnerKind::Key)) {
+            let Some(start) = offset else { break };
+            let length = UnityPlugin::calldata_length(&field.token, handled_tokens);
+            offset = length.map(|length| start + length);
 
-        for token in &sorted_structs {
-            if handled_tokens.contains_key(&token.type_path()) {
+            let Token::Composite(nested) = &field.token else { continue };
+            let nested = handled_tokens.get(&nested.type_path).unwrap_or(nested);
+            if !matches!(nested.r#type, CompositeType::Struct) {
                 continue;
             }
 
-            handled_tokens.insert(token.type_path(), token.to_composite().unwrap().to_owned());
-
-            // first index is our model struct
-            if token.type_name() == naming::get_name_from_tag(&model.tag) {
-                model_struct = Some(token.to_composite().unwrap());
+            let (Some(end), Some(reader)) =
+                (offset, self.format_felts_reader(&field.token, handled_tokens))
+            else {
                 continue;
-            }
+            };
+
+            accessors += &format!(
+                "
+        // Deserializes only the `{name}` field from the felts of the model values
+        public static {ty} Get{method}(FieldElement[] felts) {{
+            if (felts.Length < {end}) {{
+                throw new DeserializationException(\"Missing felts for field `{name}` of model \
+                 `{model}`\");
+            }}
 
-            out += UnityPlugin::format_struct(token.to_composite().unwrap()).as_str();
+            var offset = {start};
+            return {reader};
+        }}
+",
+                name = field.name,
+                ty = self.map_type(&field.token),
+                method = field.name.to_case(Case::Pascal),
+                model = model.type_name(),
+            );
         }
 
-        for token in &sorted_enums {
-            if handled_tokens.contains_key(&token.type_path()) {
-                continue;
-            }
+        accessors
+    }
 
-            handled_tokens.insert(token.type_path(), token.to_composite().unwrap().to_owned());
-            out += UnityPlugin::format_enum(token.to_composite().unwrap()).as_str();
+    // Wraps the code of a member in `#if UNITY_EDITOR` guards
+    // if the member is configured to be editor only
+    fn editor_guard(&self, member: UnityMember, code: String) -> String {
+        if code.is_empty() || !self.config.editor_only.contains(&member) {
+            return code;
         }
 
-        out += "\n";
-
-        out += UnityPlugin::format_model(
-            &get_namespace_from_tag(&model.tag),
-            model_struct.expect("model struct not found"),
-        )
-        .as_str();
-
-        out
+        format!("#if UNITY_EDITOR{code}#endif\n")
     }
 
-    // Formats a system into a C# method used by the contract class
-    // Handled tokens should be a list of all structs and enums used by the contract
-    // Such as a set of referenced tokens from a model
-    fn format_system(system: &Function, handled_tokens: &HashMap<String, Composite>) -> String {
-        fn handle_arg_recursive(
-            arg_name: &str,
-            token: &Token,
-            handled_tokens: &HashMap<String, Composite>,
-            // variant name
-            // if its an enum variant data
-            enum_variant: Option<String>,
-        ) -> Vec<(
-            // formatted arg
-            String,
-            // if its an array
-            bool,
-            // enum name and variant name
-            // if its an enum variant data
-            Option<String>,
-        )> {
-            let mapped_type = UnityPlugin::map_type(token);
-
-            match token {
-                Token::Composite(t) => {
-                    let t = handled_tokens.get(&t.type_path).unwrap_or(t);
-
-                    // Need to flatten the struct members.
-                    match t.r#type {
-                        CompositeType::Struct if t.type_name() == "ByteArray" => vec![(
-                            format!("ByteArray.Serialize({}).Select(f => f.Inner)", arg_name),
-                            true,
-                            enum_variant,
-                        )],
-                        CompositeType::Struct => {
-                            let mut tokens = vec![];
-                            t.inners.iter().for_each(|f| {
-                                tokens.extend(handle_arg_recursive(
-                                    &format!("{}.{}", arg_name, f.name),
-                                    &f.token,
-                                    handled_tokens,
-                                    enum_variant.clone(),
-                                ));
-                            });
-
-                            tokens
-                        }
-                        CompositeType::Enum => {
-                            let mut tokens = vec![(
-                                format!("new FieldElement(Enum.GetIndex({})).Inner", arg_name),
-                                false,
-                                enum_variant,
-                            )];
-
-                            t.inners.iter().for_each(|field| {
-                                if let Token::CoreBasic(basic) = &field.token {
-                                    // ignore unit type
-                                    if basic.type_path == "()" {
-                                        return;
-                                    }
-                                }
+    // Formats the fields of a struct into the parameters of a positional record
+    fn format_record_parameters(&self, token: &Composite) -> String {
+        let owner = token.type_name();
+        token
+            .inners
+            .iter()
+            .map(|field| {
+                format!(
+                    "{} {}",
+                    self.map_field_type(&owner, &field.name, &field.token),
+                    self.ident(&field.name)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
 
-                                tokens.extend(handle_arg_recursive(
-                                    &format!(
-                                        "(({}.{}){}).value",
-                                        mapped_type,
-                                        field.name.clone(),
-                                        arg_name
-                                    ),
-                                    &if let Token::GenericArg(generic_arg) = &field.token {
-                                        let generic_token = t
-                                            .generic_args
-                                            .iter()
-                                            .find(|(name, _)| name == generic_arg)
-                                            .unwrap()
-                                            .1
-                                            .clone();
-                                        generic_token
-                                    } else {
-                                        field.token.clone()
-                                    },
-                                    handled_tokens,
-                                    Some(field.name.clone()),
-                                ))
-                            });
-
-                            tokens
-                        }
-                        CompositeType::Unknown => panic!("Unknown composite type: {:?}", t),
-                    }
-                }
-                Token::Array(array) => {
-                    let is_inner_array = matches!(array.inner.as_ref(), Token::Array(_));
-                    let inner = handle_arg_recursive(
-                        &format!("{arg_name}Item"),
-                        &array.inner,
-                        handled_tokens,
-                        enum_variant.clone(),
-                    );
+    // Token should be a model
+    // This will be formatted into a C# positional record struct
+    // with methods serializing it into felts and deserializing it back
+    // Records can't derive from `ModelInstance`, so the members are flagged for the SDK with
+    // attributes on their backing fields
+    fn format_model_record(
+        &self,
+        namespace: &str,
+        model: &Composite,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> String {
+        let owner = model.type_name();
+        let parameters = model
+            .inners
+            .iter()
+            .map(|field| {
+                let key = if matches!(field.kind, CompositeInnerKind::Key) { "Key, " } else { "" };
 
-                    let inners = inner
-                        .into_iter()
-                        .map(|(arg, _, _)| arg)
-                        .collect::<Vec<String>>()
-                        .join(", ");
+                format!(
+                    "[field: {key}ModelField(\"{}\")] {} {}",
+                    field.name,
+                    self.map_field_type(&owner, &field.name, &field.token),
+                    self.ident(&field.name)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
 
-                    vec![
-                        (
-                            format!("new FieldElement({arg_name}.Length).Inner",),
-                            false,
-                            enum_variant.clone(),
-                        ),
-                        (
-                            if is_inner_array {
-                                format!(
-                                    "{arg_name}.SelectMany({arg_name}Item => new \
-                                     dojo.FieldElement[] {{ }}.Concat({inners}))"
-                                )
-                            } else {
-                                format!(
-                                    "{arg_name}.SelectMany({arg_name}Item => new [] {{ {inners} \
-                                     }})"
-                                )
-                            },
-                            true,
-                            enum_variant.clone(),
-                        ),
-                    ]
-                }
-                Token::Tuple(tuple) => tuple
+        let inputs =
+            model.inners.iter().map(|f| (f.name.clone(), f.token.clone())).collect::<Vec<_>>();
+        let serialization =
+            self.format_calldata(&inputs, "felts", handled_tokens).join("\n            ");
+
+        // Reads the felts written by `ToFelts`, ie. the keys followed by the values
+        let deserialization = self
+            .format_felts_reader(&Token::Composite(model.clone()), handled_tokens)
+            .map(|reader| {
+                format!(
+                    "
+
+        // Deserializes the model from its felts representation, starting at `offset`
+        public static {owner} FromFelts(FieldElement[] felts, ref int offset) {{
+            return {reader};
+        }}"
+                )
+            })
+            .unwrap_or_default();
+
+        let keys =
+            self.editor_guard(UnityMember::Keys, self.format_model_keys(model, handled_tokens));
+
+        format!(
+            "
+namespace {namespace} {{
+    // Model definition for `{}` model
+    public record struct {owner}({parameters}) {{
+        // Serializes the model into its felts representation
+        public dojo.FieldElement[] ToFelts() {{
+            List<dojo.FieldElement> felts = new List<dojo.FieldElement>();
+            {serialization}
+            return felts.ToArray();
+        }}{deserialization}
+{keys}    }}
+}}
+",
+            model.type_path,
+        )
+    }
+
+    // Formats a parameterless constructor initializing each field of the model
+    // to the equivalent of its Cairo zero value
+    fn format_model_constructor(
+        &self,
+        model: &Composite,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> String {
+        let fields = model
+            .inners
+            .iter()
+            .filter_map(|field| {
+                self.default_value(&field.token, handled_tokens)
+                    .map(|value| format!("{} = {value};", self.ident(&field.name)))
+            })
+            .collect::<Vec<String>>()
+            .join("\n            ");
+
+        format!(
+            "
+        // Initializes the fields to their Cairo zero values
+        public {}() {{
+            {fields}
+        }}
+",
+            model.type_name()
+        )
+    }
+
+    // Maps a token to the C# expression of its Cairo zero value
+    // Returns None if the type has no zero value (eg. enums whose first variant has data)
+    fn default_value(
+        &self,
+        token: &Token,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> Option<String> {
+        let mapped_type = self.map_type(token);
+
+        match token {
+            // `None` is the zero value of the nullables
+            _ if self.nullable_option(token).is_some() => Some("null".to_string()),
+            // Checked first, as u256 is a composite mapped to a BigInteger.
+            _ if mapped_type == "BigInteger" => Some("BigInteger.Zero".to_string()),
+            Token::Array(array) => Some(format!("new List<{}>()", self.map_type(&array.inner))),
+            Token::Tuple(tuple) => {
+                let values = tuple
                     .inners
                     .iter()
-                    .enumerate()
-                    .flat_map(|(idx, token)| {
-                        handle_arg_recursive(
-                            &format!("{}.Item{}", arg_name, idx + 1),
-                            token,
-                            handled_tokens,
-                            enum_variant.clone(),
-                        )
-                    })
-                    .collect(),
-                _ => match mapped_type.as_str() {
-                    "FieldElement" => vec![(format!("{}.Inner", arg_name), false, enum_variant)],
-                    _ => {
-                        vec![(format!("new FieldElement({}).Inner", arg_name), false, enum_variant)]
+                    .map(|t| self.default_value(t, handled_tokens))
+                    .collect::<Option<Vec<String>>>()?;
+
+                Some(format!("({})", values.join(", ")))
+            }
+            Token::Composite(t) => {
+                let t = handled_tokens.get(&t.type_path).unwrap_or(t);
+
+                match t.r#type {
+                    CompositeType::Struct if t.type_name() == "ByteArray" => {
+                        Some("\"\"".to_string())
+                    }
+                    CompositeType::Struct if t.type_name() == "Bytes" => {
+                        Some("Array.Empty<byte>()".to_string())
+                    }
+                    CompositeType::Struct => Some(format!("new {mapped_type}()")),
+                    CompositeType::Enum => {
+                        let variant = t.inners.first()?;
+                        match &variant.token {
+                            Token::CoreBasic(basic) if basic.type_path == "()" => {
+                                Some(format!("new {mapped_type}.{}()", variant.name))
+                            }
+                            _ => None,
+                        }
                     }
-                },
+                    CompositeType::Unknown => None,
+                }
             }
+            _ => match mapped_type.as_str() {
+                "FieldElement" => Some("new FieldElement(0)".to_string()),
+                "Bytes31" => Some("new Bytes31(new FieldElement(0))".to_string()),
+                "string" => Some("\"\"".to_string()),
+                "bool" => Some("false".to_string()),
+                "sbyte" | "short" | "int" | "long" | "byte" | "ushort" | "uint" | "ulong" => {
+                    Some("0".to_string())
+                }
+                _ => None,
+            },
         }
+    }
 
-        let args = system
-            .inputs
+    // Formats the keys of a model into a nested C# `Keys` struct
+    // and a `Key()` method computing the entity id, which is the
+    // poseidon hash of the serialized keys in declaration order
+    // Returns an empty string if the model has no keys
+    fn format_model_keys(
+        &self,
+        model: &Composite,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> String {
+        let keys = model
+            .inners
             .iter()
-            .map(|arg| format!("{} {}", UnityPlugin::map_type(&arg.1), &arg.0))
+            .filter(|field| matches!(field.kind, CompositeInnerKind::Key))
+            .map(|field| (self.ident(&field.name), field.token.clone()))
+            .collect::<Vec<(String, Token)>>();
+
+        if keys.is_empty() {
+            return String::new();
+        }
+
+        let fields = keys
+            .iter()
+            .map(|(name, token)| format!("public {} {};", self.map_type(token), name))
+            .collect::<Vec<String>>()
+            .join("\n            ");
+
+        let init = keys
+            .iter()
+            .map(|(name, _)| format!("{name} = {name}"))
             .collect::<Vec<String>>()
             .join(", ");
 
-        let calldata = system
-            .inputs
+        let serialized = self.format_calldata(&keys, "keys", handled_tokens).join("\n            ");
+
+        format!(
+            "
+        // Keys of the `{name}` model, in declaration order
+        public struct Keys {{
+            {fields}
+        }}
+
+        // Returns the keys of this model instance
+        public Keys GetKeys() {{
+            return new Keys {{ {init} }};
+        }}
+
+        // Computes the entity id of this model instance
+        // which is the poseidon hash of its serialized keys
+        public FieldElement Key() {{
+            List<dojo.FieldElement> keys = new List<dojo.FieldElement>();
+            {serialized}
+
+            var felts = keys.ToArray();
+            unsafe {{
+                fixed (dojo.FieldElement* ptr = felts) {{
+                    return new FieldElement(dojo.poseidon_hash_many(ptr, (UIntPtr)felts.Length));
+                }}
+            }}
+        }}
+",
+            name = model.type_name(),
+        )
+    }
+
+    // Handles a model definition and its referenced tokens
+    // Will map all structs and enums to C# types
+    // Will format the model into a C# class
+    fn handle_model(
+        &self,
+        model: &DojoModel,
+        handled_tokens: &mut HashMap<String, Composite>,
+    ) -> String {
+        let mut out = String::new();
+        out += UnityPlugin::generated_header().as_str();
+        out += UnityPlugin::model_imports().as_str();
+
+        let mut model_struct: Option<&Composite> = None;
+        let tokens = &model.tokens;
+
+        let mut sorted_structs = tokens.structs.clone();
+        sorted_structs.sort_by(compare_tokens_by_type_name);
+
+        let mut sorted_enums = tokens.enums.clone();
+        sorted_enums.sort_by(compare_tokens_by_type_name);
+
+        for token in &sorted_structs {
+            if handled_tokens.contains_key(&token.type_path()) {
+                continue;
+            }
+
+            handled_tokens.insert(token.type_path(), token.to_composite().unwrap().to_owned());
+
+            // first index is our model struct
+            if token.type_name() == naming::get_name_from_tag(&model.tag) {
+                model_struct = Some(token.to_composite().unwrap());
+                continue;
+            }
+
+            out += self.format_struct(token.to_composite().unwrap()).as_str();
+        }
+
+        for token in &sorted_enums {
+            if handled_tokens.contains_key(&token.type_path()) {
+                continue;
+            }
+
+            handled_tokens.insert(token.type_path(), token.to_composite().unwrap().to_owned());
+
+            // mapped to C# nullables, so no record is needed
+            if self.config.nullable_options && token.type_path().starts_with("core::option::Option")
+            {
+                continue;
+            }
+
+            out += self.format_enum(token.to_composite().unwrap()).as_str();
+        }
+
+        out += "\n";
+
+        out += self
+            .format_model(
+                &get_namespace_from_tag(&model.tag),
+                model_struct.expect("model struct not found"),
+                handled_tokens,
+            )
+            .as_str();
+
+        out
+    }
+
+    // Flattens an argument into the expressions used to serialize it as felts
+    // Handled tokens should be a list of all structs and enums used by the contract
+    // Such as a set of referenced tokens from a model
+    fn serialize_arg(
+        &self,
+        arg_name: &str,
+        token: &Token,
+        handled_tokens: &HashMap<String, Composite>,
+        // condition on the arg path
+        // if its an enum variant or option data
+        condition: Option<String>,
+    ) -> Vec<(
+        // formatted arg
+        String,
+        // if its an array
+        bool,
+        // condition on the arg path, eg. `s.x != null`
+        // if its an enum variant or option data
+        Option<String>,
+    )> {
+        let mapped_type = self.map_type(token);
+
+        // The variant tag, `0` for `Some` and `1` for `None`, followed by the value for `Some`.
+        if let Some(inner) = self.nullable_option(token) {
+            let value = if UnityPlugin::is_value_type(inner) {
+                format!("{arg_name}.Value")
+            } else {
+                arg_name.to_string()
+            };
+
+            let some = UnityPlugin::and_condition(&condition, format!("{arg_name} != null"));
+            let mut tokens = vec![(
+                format!("new FieldElement({arg_name} != null ? 0 : 1).Inner"),
+                false,
+                condition,
+            )];
+            tokens.extend(self.serialize_arg(&value, inner, handled_tokens, some));
+            return tokens;
+        }
+
+        // u256 is mapped to a BigInteger, but serialized as its low and high u128 parts.
+        if token.type_name() == "u256" {
+            return vec![
+                (
+                    format!("new FieldElement({arg_name} & ((BigInteger.One << 128) - 1)).Inner"),
+                    false,
+                    condition.clone(),
+                ),
+                (format!("new FieldElement({arg_name} >> 128).Inner"), false, condition),
+            ];
+        }
+
+        match token {
+            Token::Composite(t) => {
+                let t = handled_tokens.get(&t.type_path).unwrap_or(t);
+
+                // Need to flatten the struct members.
+                match t.r#type {
+                    CompositeType::Struct if t.type_name() == "ByteArray" => vec![(
+                        format!("ByteArray.Serialize({}).Select(f => f.Inner)", arg_name),
+                        true,
+                        condition,
+                    )],
+                    CompositeType::Struct if t.type_name() == "Bytes" => vec![(
+                        format!("BytesSerializer.Serialize({}).Select(f => f.Inner)", arg_name),
+                        true,
+                        condition,
+                    )],
+                    CompositeType::Struct => {
+                        let mut tokens = vec![];
+                        t.inners.iter().for_each(|f| {
+                            tokens.extend(self.serialize_arg(
+                                &format!("{}.{}", arg_name, self.ident(&f.name)),
+                                &f.token,
+                                handled_tokens,
+                                condition.clone(),
+                            ));
+                        });
+
+                        tokens
+                    }
+                    CompositeType::Enum => {
+                        let mut tokens = vec![(
+                            format!("new FieldElement(Enum.GetIndex({})).Inner", arg_name),
+                            false,
+                            condition.clone(),
+                        )];
+
+                        t.inners.iter().for_each(|field| {
+                            if let Token::CoreBasic(basic) = &field.token {
+                                // ignore unit type
+                                if basic.type_path == "()" {
+                                    return;
+                                }
+                            }
+
+                            tokens.extend(self.serialize_arg(
+                                &format!(
+                                    "(({}.{}){}).value",
+                                    mapped_type,
+                                    field.name.clone(),
+                                    arg_name
+                                ),
+                                &if let Token::GenericArg(generic_arg) = &field.token {
+                                    let generic_token = t
+                                        .generic_args
+                                        .iter()
+                                        .find(|(name, _)| name == generic_arg)
+                                        .unwrap()
+                                        .1
+                                        .clone();
+                                    generic_token
+                                } else {
+                                    field.token.clone()
+                                },
+                                handled_tokens,
+                                UnityPlugin::and_condition(
+                                    &condition,
+                                    format!("{arg_name} is {mapped_type}.{}", field.name),
+                                ),
+                            ))
+                        });
+
+                        tokens
+                    }
+                    CompositeType::Unknown => panic!("Unknown composite type: {:?}", t),
+                }
+            }
+            Token::Array(array) => {
+                let is_inner_array = matches!(array.inner.as_ref(), Token::Array(_));
+                let inner = self.serialize_arg(
+                    &format!("{arg_name}Item"),
+                    &array.inner,
+                    handled_tokens,
+                    condition.clone(),
+                );
+
+                let inners =
+                    inner.into_iter().map(|(arg, _, _)| arg).collect::<Vec<String>>().join(", ");
+
+                vec![
+                    (
+                        format!("new FieldElement({arg_name}.Count).Inner",),
+                        false,
+                        condition.clone(),
+                    ),
+                    (
+                        if is_inner_array {
+                            format!(
+                                "{arg_name}.SelectMany({arg_name}Item => new \
+                                 dojo.FieldElement[] {{ }}.Concat({inners}))"
+                            )
+                        } else {
+                            format!(
+                                "{arg_name}.SelectMany({arg_name}Item => new [] {{ {inners} \
+                                 }})"
+                            )
+                        },
+                        true,
+                        condition.clone(),
+                    ),
+                ]
+            }
+            Token::Tuple(tuple) => tuple
+                .inners
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, token)| {
+                    self.serialize_arg(
+                        &format!("{}.Item{}", arg_name, idx + 1),
+                        token,
+                        handled_tokens,
+                        condition.clone(),
+                    )
+                })
+                .collect(),
+            _ => match mapped_type.as_str() {
+                "FieldElement" => vec![(format!("{}.Inner", arg_name), false, condition)],
+                "Bytes31" => vec![(format!("{}.value.Inner", arg_name), false, condition)],
+                _ => vec![(format!("new FieldElement({}).Inner", arg_name), false, condition)],
+            },
+        }
+    }
+
+    // Combines the condition of the enclosing value, if any, with the given one
+    fn and_condition(parent: &Option<String>, condition: String) -> Option<String> {
+        match parent {
+            Some(parent) => Some(format!("{parent} && {condition}")),
+            None => Some(condition),
+        }
+    }
+
+    // Formats the statements serializing the given inputs into the `list` of felts
+    fn format_calldata(
+        &self,
+        inputs: &[(String, Token)],
+        list: &str,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> Vec<String> {
+        inputs
             .iter()
             .flat_map(|(name, token)| {
-                let tokens = handle_arg_recursive(name, token, handled_tokens, None);
+                let name = self.ident(name);
+                let tokens = self.serialize_arg(&name, token, handled_tokens, None);
 
                 tokens
                     .iter()
-                    .map(|(arg, is_array, enum_variant)| {
+                    .map(|(arg, is_array, condition)| {
                         let calldata_op = if *is_array {
-                            format!("calldata.AddRange({arg});")
+                            format!("{list}.AddRange({arg});")
                         } else {
-                            format!("calldata.Add({arg});")
+                            format!("{list}.Add({arg});")
                         };
 
-                        if let Some(variant) = enum_variant {
-                            let mapped_token = UnityPlugin::map_type(token);
-                            let mapped_variant_type = format!("{}.{}", mapped_token, variant);
-
-                            format!("if ({name} is {mapped_variant_type}) {calldata_op}",)
-                        } else {
-                            calldata_op
+                        match condition {
+                            Some(condition) => format!("if ({condition}) {calldata_op}"),
+                            None => calldata_op,
                         }
                     })
                     .collect::<Vec<String>>()
             })
             .collect::<Vec<String>>()
-            .join("\n\t\t");
+    }
+
+    // Formats a system into a C# method used by the contract class
+    // Handled tokens should be a list of all structs and enums used by the contract
+    // Such as a set of referenced tokens from a model
+    fn format_system(
+        &self,
+        system: &Function,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> String {
+        let args = self.format_system_args(system);
+
+        let calldata =
+            self.format_calldata(&system.inputs, "calldata", handled_tokens).join("\n\t\t");
+
+        let calldata_length = system
+            .inputs
+            .iter()
+            .map(|(_, token)| UnityPlugin::calldata_length(token, handled_tokens))
+            .sum::<Option<usize>>()
+            .map_or("DynamicCalldataLength".to_string(), |len| len.to_string());
 
         format!(
             "
+    // Number of felts in the `{system_name}` calldata
+    public const int {system_name}CalldataLength = {calldata_length};
+
     // Call the `{system_name}` system with the specified Account and calldata
     // Returns the transaction hash. Use `WaitForTransaction` to wait for the transaction to be \
              confirmed.
-    public async Task<FieldElement> {system_name}(Account account{arg_sep}{args}) {{
+    public async Task<FieldElement> {method_name}(Account account{arg_sep}{args}) {{
         List<dojo.FieldElement> calldata = new List<dojo.FieldElement>();
         {calldata}
 
@@ -486,6 +1561,7 @@ namespace {namespace} {{
             ",
             // selector for execute
             system_name = system.name,
+            method_name = self.ident(&system.name),
             // add comma if we have args
             arg_sep = if !args.is_empty() { ", " } else { "" },
             // formatted args to use our mapped types
@@ -495,12 +1571,291 @@ namespace {namespace} {{
         )
     }
 
+    // Formats a view function into a C# method used by the contract class
+    // View functions can't be executed as a transaction, so they're called read-only through the
+    // provider, against the pending block
+    // Returns the raw felts output by the function
+    fn format_view(&self, view: &Function, handled_tokens: &HashMap<String, Composite>) -> String {
+        let args = self.format_system_args(view);
+
+        let calldata =
+            self.format_calldata(&view.inputs, "calldata", handled_tokens).join("\n\t\t");
+
+        format!(
+            "
+    // Call the `{view_name}` view function with the specified provider and calldata
+    // Returns the felts output by the function. No transaction is sent.
+    public async Task<FieldElement[]> {method_name}(JsonRpcClient provider{arg_sep}{args}) {{
+        List<dojo.FieldElement> calldata = new List<dojo.FieldElement>();
+        {calldata}
+
+        return await provider.Call(new dojo.Call{{
+            to = contractAddress,
+            selector = \"{view_name}\",
+            calldata = calldata.ToArray()
+        }}, new dojo.BlockId {{
+            tag = dojo.BlockId_Tag.BlockTag_,
+            block_tag = dojo.BlockTag.Pending
+        }});
+    }}
+            ",
+            view_name = view.name,
+            method_name = self.ident(&view.name),
+            arg_sep = if !args.is_empty() { ", " } else { "" },
+        )
+    }
+
+    // Computes the number of felts a value of the given type is serialized into
+    // Returns `None` if it depends on the value, eg. for arrays
+    fn calldata_length(
+        token: &Token,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> Option<usize> {
+        // Serialized as its low and high parts, see `serialize_arg`.
+        if token.type_name() == "u256" {
+            return Some(2);
+        }
+
+        match token {
+            Token::Array(_) | Token::GenericArg(_) => None,
+            Token::Tuple(tuple) => {
+                tuple.inners.iter().map(|t| UnityPlugin::calldata_length(t, handled_tokens)).sum()
+            }
+            Token::Composite(t) => {
+                let t = handled_tokens.get(&t.type_path).unwrap_or(t);
+                let resolve = |token: &Token| match token {
+                    Token::GenericArg(arg) => {
+                        t.generic_args.iter().find(|(name, _)| name == arg).and_then(
+                            |(_, token)| UnityPlugin::calldata_length(token, handled_tokens),
+                        )
+                    }
+                    token => UnityPlugin::calldata_length(token, handled_tokens),
+                };
+
+                match t.r#type {
+                    CompositeType::Struct
+                        if matches!(t.type_name().as_str(), "ByteArray" | "Bytes") =>
+                    {
+                        None
+                    }
+                    CompositeType::Struct => t.inners.iter().map(|f| resolve(&f.token)).sum(),
+                    // The variant index, followed by the variant data which must have the same
+                    // length for all the variants.
+                    CompositeType::Enum => {
+                        let mut lengths = t.inners.iter().map(|f| resolve(&f.token));
+                        let first = lengths.next().unwrap_or(Some(0))?;
+                        lengths.all(|len| len == Some(first)).then_some(1 + first)
+                    }
+                    CompositeType::Unknown => None,
+                }
+            }
+            Token::CoreBasic(basic) if basic.type_path == "()" => Some(0),
+            _ => Some(1),
+        }
+    }
+
+    // Formats the arguments of a system method, without the account
+    fn format_system_args(&self, system: &Function) -> String {
+        system
+            .inputs
+            .iter()
+            .map(|arg| {
+                format!(
+                    "{} {}",
+                    self.map_field_type(&system.name, &arg.0, &arg.1),
+                    self.ident(&arg.0)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    // Formats the C# interface implemented by both the contract class and its mock
+    fn format_contract_interface(&self, name: &str, systems: &[&Function]) -> String {
+        let methods = systems
+            .iter()
+            .map(|system| {
+                let args = self.format_system_args(system);
+                let arg_sep = if !args.is_empty() { ", " } else { "" };
+                let method_name = self.ident(&system.name);
+                format!("Task<FieldElement> {method_name}(Account account{arg_sep}{args});")
+            })
+            .collect::<Vec<String>>()
+            .join("\n    ");
+
+        format!(
+            "
+// Systems of the `{name}` contract, implemented by `{name}` and `Mock{name}`
+public interface I{name} {{
+    {methods}
+}}
+"
+        )
+    }
+
+    // Formats a mock of the contract, recording the system calls instead of executing them
+    // The returned transaction hashes can be configured per system through `responses`
+    fn format_contract_mock(&self, tag: &str, name: &str, systems: &[&Function]) -> String {
+        let methods = systems
+            .iter()
+            .map(|system| {
+                let args = self.format_system_args(system);
+                let arg_sep = if !args.is_empty() { ", " } else { "" };
+                let arg_names = system
+                    .inputs
+                    .iter()
+                    .map(|arg| format!(" {}", self.ident(&arg.0)))
+                    .collect::<Vec<String>>()
+                    .join(",");
+
+                format!(
+                    "public Task<FieldElement> {method_name}(Account account{arg_sep}{args}) {{
+        calls.Add(new MockCall {{ system = \"{system_name}\", args = new object[] \
+                     {{{arg_names} }} }});
+        return Task.FromResult(Response(\"{system_name}\"));
+    }}",
+                    system_name = system.name,
+                    method_name = self.ident(&system.name),
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n    ");
+
+        format!(
+            "
+// Mock of the `{tag}` contract, recording the system calls without sending any transaction
+public class Mock{name} : I{name} {{
+    // A recorded system call
+    public struct MockCall {{
+        public string system;
+        public object[] args;
+    }}
+
+    // The system calls, in call order
+    public List<MockCall> calls = new List<MockCall>();
+    // The transaction hashes returned by the systems, by system name
+    public Dictionary<string, FieldElement> responses = new Dictionary<string, FieldElement>();
+
+    {methods}
+
+    FieldElement Response(string system) {{
+        return responses.TryGetValue(system, out var hash) ? hash : new FieldElement(\"0x0\");
+    }}
+}}
+"
+        )
+    }
+
     // Formats a contract tag into a pretty contract name
     // eg. dojo_examples-actions.json -> Actions
     fn formatted_contract_name(tag: &str) -> String {
         naming::capitalize(&naming::get_name_from_tag(tag))
     }
 
+    // Token should be an event struct
+    // This will be formatted into a C# class, nested in the contract class, with a `TryParse`
+    // deserializer of the emitted events
+    // The deserializer is only generated if all the members can be read from felts
+    fn format_event(
+        &self,
+        event: &Composite,
+        handled_tokens: &HashMap<String, Composite>,
+    ) -> String {
+        let name = event.type_name();
+
+        let fields = event
+            .inners
+            .iter()
+            .map(|field| {
+                // Key members are flagged so the SDK can tell them apart from the data members
+                let key = if matches!(field.kind, CompositeInnerKind::Key) {
+                    "[Key]\n        "
+                } else {
+                    ""
+                };
+                format!(
+                    "{key}public {} {};",
+                    self.map_field_type(&name, &field.name, &field.token),
+                    self.ident(&field.name)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n        ");
+
+        // The key members are serialized in the keys after the selector, and the data members in
+        // the data.
+        let (keys, data): (Vec<_>, Vec<_>) =
+            event.inners.iter().partition(|field| matches!(field.kind, CompositeInnerKind::Key));
+        let reads = [("keys", 1, keys), ("data", 0, data)]
+            .into_iter()
+            .filter(|(_, _, fields)| !fields.is_empty())
+            .map(|(felts, offset, fields)| {
+                let reads = fields
+                    .iter()
+                    .map(|field| {
+                        let reader = self.format_felts_reader(&field.token, handled_tokens)?;
+                        Some(format!("var {} = {reader};", self.ident(&field.name)))
+                    })
+                    .collect::<Option<Vec<String>>>()?;
+                Some(format!(
+                    "felts = {felts};\n                offset = {offset};\n                {}",
+                    reads.join("\n                ")
+                ))
+            })
+            .collect::<Option<Vec<String>>>();
+
+        let try_parse = reads.map_or(String::new(), |reads| {
+            let init = event
+                .inners
+                .iter()
+                .map(|field| format!("{name} = {name}", name = self.ident(&field.name)))
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            format!(
+                "
+
+        // Deserializes the event from the keys and data of an emitted event
+        // Returns false if the event isn't a `{name}` event
+        public static bool TryParse(FieldElement[] keys, FieldElement[] data, out {name} ev) {{
+            ev = null;
+            if (keys.Length == 0 || keys[0].Hex() != Selector.Hex()) {{
+                return false;
+            }}
+
+            FieldElement[] felts;
+            int offset;
+            try {{
+                {reads}
+                ev = new {name} {{ {init} }};
+            }} catch (IndexOutOfRangeException) {{
+                throw new DeserializationException(\"Missing felts for event `{name}`\");
+            }}
+            return true;
+        }}",
+                reads = reads.join("\n                "),
+            )
+        });
+
+        // The selector is the name of the variant of the contract `Event` enum, which is the
+        // name of the event struct.
+        let selector = get_selector_from_name(&name).expect("event names are ASCII");
+
+        format!(
+            "
+    // Event definition for `{}` event
+    [Serializable]
+    public class {name} {{
+        {fields}
+
+        // Selector of the event, ie. the first key of the emitted events
+        public static readonly FieldElement Selector = new FieldElement(\"{selector:#x}\");{try_parse}
+    }}
+",
+            event.type_path,
+        )
+    }
+
     // Handles a contract definition and its underlying systems
     // Will format the contract into a C# class and
     // all systems into C# methods
@@ -514,37 +1869,148 @@ namespace {namespace} {{
         out += UnityPlugin::generated_header().as_str();
         out += UnityPlugin::contract_imports().as_str();
 
-        let systems = contract
+        let functions = contract
             .systems
             .iter()
-            // we assume systems dont have outputs
-            .filter(|s| s.to_function().unwrap().get_output_kind() as u8 == FunctionOutputKind::NoOutput as u8)
-            .map(|system| UnityPlugin::format_system(system.to_function().unwrap(), handled_tokens))
+            .map(|system| system.to_function().unwrap())
+            .collect::<Vec<&Function>>();
+
+        // view functions can't be executed as a transaction, they're called read-only instead
+        let (views, functions): (Vec<&Function>, Vec<&Function>) = functions
+            .into_iter()
+            .partition(|s| matches!(s.state_mutability, StateMutability::View));
+        // we assume systems dont have outputs
+        let functions = functions
+            .into_iter()
+            .filter(|s| s.get_output_kind() as u8 == FunctionOutputKind::NoOutput as u8)
+            .collect::<Vec<&Function>>();
+
+        let systems = functions
+            .iter()
+            .map(|system| self.format_system(system, handled_tokens))
+            .chain(views.iter().map(|view| self.format_view(view, handled_tokens)))
             .collect::<Vec<String>>()
             .join("\n\n    ");
 
+        let mut events = contract
+            .tokens
+            .structs
+            .iter()
+            .filter_map(|token| token.to_composite().ok())
+            .filter(|token| token.is_event)
+            .collect::<Vec<&Composite>>();
+        events.sort_by_key(|token| token.type_name());
+        let events =
+            events.iter().map(|event| self.format_event(event, handled_tokens)).collect::<String>();
+
+        // capitalize contract name
+        let name = UnityPlugin::formatted_contract_name(&contract.tag);
+
+        if self.config.mocks {
+            out += &self.format_contract_interface(&name, &functions);
+        }
+
         out += &format!(
             "
 // System definitions for `{}` contract
-public class {} : MonoBehaviour {{
+public class {} : MonoBehaviour{} {{
     // The address of this contract
     public string contractAddress;
 
+    // Calldata length of the systems whose arguments have a variable length
+    public const int DynamicCalldataLength = -1;
+
     {}
-}}
+{events}}}
         ",
             contract.tag,
-            // capitalize contract name
-            UnityPlugin::formatted_contract_name(&contract.tag),
+            name,
+            if self.config.mocks { format!(", I{name}") } else { String::new() },
             systems
         );
 
+        if self.config.mocks {
+            out += &self.format_contract_mock(&contract.tag, &name, &functions);
+        }
+
         out
     }
 }
 
+impl UnityPlugin {
+    // Generates the bindings of contracts whose ABIs were merged together
+    // The types shared by all contracts are emitted once in `Types.gen.cs`
+    pub(crate) fn generate_merged_code(
+        &self,
+        merged: &MergedAbi,
+    ) -> BindgenResult<HashMap<PathBuf, Vec<u8>>> {
+        let mut out: HashMap<PathBuf, Vec<u8>> = HashMap::new();
+        let mut handled_tokens = HashMap::<String, Composite>::new();
+
+        self.namespace_colliding_types(
+            merged
+                .structs
+                .iter()
+                .chain(merged.enums.iter())
+                .filter_map(|token| token.to_composite().ok())
+                .map(|c| (c.type_path.clone(), c.type_name(), module_namespace(&c.type_path)))
+                .collect(),
+        );
+
+        let mut types = UnityPlugin::generated_header();
+        types += UnityPlugin::model_imports().as_str();
+
+        for token in &merged.structs {
+            let composite = token.to_composite().unwrap();
+            handled_tokens.insert(token.type_path(), composite.to_owned());
+            types += self.format_struct(composite).as_str();
+        }
+
+        for token in &merged.enums {
+            let composite = token.to_composite().unwrap();
+            handled_tokens.insert(token.type_path(), composite.to_owned());
+            types += self.format_enum(composite).as_str();
+        }
+
+        out.insert(PathBuf::from("Types.gen.cs"), types.into_bytes());
+        out.insert(
+            PathBuf::from("Deserialization.gen.cs"),
+            UnityPlugin::format_deserialization_helpers().into_bytes(),
+        );
+
+        for contract in &merged.contracts {
+            let path = PathBuf::from(format!("Contracts/{}.gen.cs", contract.tag));
+            let code = self.handle_contract(contract, &handled_tokens);
+            out.insert(path, code.into_bytes());
+        }
+
+        self.handle_unknown_types(&mut out)?;
+        Ok(out)
+    }
+}
+
+// Strips the generic arguments of a type path
+// eg. core::option::Option::<core::integer::u32> -> core::option::Option
+fn base_type_path(type_path: &str) -> &str {
+    type_path.split("::<").next().unwrap_or(type_path)
+}
+
+// Maps the Cairo module of a type to a C# namespace
+// eg. dojo_examples::models::Vec2 -> dojo_examples.models
+fn module_namespace(type_path: &str) -> String {
+    let mut segments = base_type_path(type_path).split("::").collect::<Vec<_>>();
+    segments.pop();
+    segments.join(".")
+}
+
 #[async_trait]
 impl BuiltinPlugin for UnityPlugin {
+    // Types sharing their name are emitted in the namespace of their Cairo module, so they only
+    // collide if they're defined in the same module, ie. never
+    fn type_namespace(&self, type_path: &str) -> String {
+        module_namespace(type_path)
+    }
+
     async fn generate_code(&self, data: &DojoData) -> BindgenResult<HashMap<PathBuf, Vec<u8>>> {
         let mut out: HashMap<PathBuf, Vec<u8>> = HashMap::new();
         let mut handled_tokens = HashMap::<String, Composite>::new();
@@ -553,16 +2019,44 @@ impl BuiltinPlugin for UnityPlugin {
         // Sort models based on their tag to ensure deterministic output.
         models.sort_by(|(_, a), (_, b)| a.tag.cmp(&b.tag));
 
+        // The models are namespaced by their Dojo namespace, like their class, and the other
+        // types by their Cairo module.
+        let types = models
+            .iter()
+            .flat_map(|(_, model)| {
+                let name = naming::get_name_from_tag(&model.tag);
+                let namespace = get_namespace_from_tag(&model.tag);
+                model.tokens.structs.iter().chain(model.tokens.enums.iter()).filter_map(
+                    move |token| {
+                        let composite = token.to_composite().ok()?;
+                        let type_name = composite.type_name();
+                        let namespace = if type_name == name {
+                            namespace.clone()
+                        } else {
+                            module_namespace(&composite.type_path)
+                        };
+                        Some((composite.type_path.clone(), type_name, namespace))
+                    },
+                )
+            })
+            .collect();
+        self.namespace_colliding_types(types);
+
         // Handle codegen for models
         for (name, model) in &models {
             let models_path = Path::new(&format!("Models/{}.gen.cs", name)).to_owned();
 
-            println!("Generating model: {}", name);
+            log::debug!("Generating model: {}", name);
             let code = self.handle_model(model, &mut handled_tokens);
 
             out.insert(models_path, code.as_bytes().to_vec());
         }
 
+        out.insert(
+            PathBuf::from("Deserialization.gen.cs"),
+            UnityPlugin::format_deserialization_helpers().into_bytes(),
+        );
+
         let mut contracts = data.contracts.iter().collect::<Vec<_>>();
         // Sort contracts based on their tag to ensure deterministic output.
         contracts.sort_by(|(_, a), (_, b)| a.tag.cmp(&b.tag));
@@ -571,12 +2065,13 @@ impl BuiltinPlugin for UnityPlugin {
         for (name, contract) in &contracts {
             let contracts_path = Path::new(&format!("Contracts/{}.gen.cs", name)).to_owned();
 
-            println!("Generating contract: {}", name);
+            log::debug!("Generating contract: {}", name);
             let code = self.handle_contract(contract, &handled_tokens);
 
             out.insert(contracts_path, code.as_bytes().to_vec());
         }
 
+        self.handle_unknown_types(&mut out)?;
         Ok(out)
     }
 }
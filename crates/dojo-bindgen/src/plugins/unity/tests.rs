@@ -0,0 +1,1275 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use cainome::parser::tokens::{
+    Array, Composite, CompositeInner, CompositeInnerKind, CompositeType, Function, StateMutability,
+    Token, Tuple,
+};
+use cainome::parser::TokenizedAbi;
+use camino::Utf8PathBuf;
+use starknet::core::utils::get_selector_from_name;
+
+use crate::error::Error;
+use crate::plugins::test_utils::{basic, composite, field};
+use crate::plugins::unity::{
+    UnityMember, UnityPlugin, UnityPluginConfig, UnknownTypePolicy, CSHARP_KEYWORDS,
+};
+use crate::plugins::{BuiltinPlugins, ReservedWords};
+use crate::{
+    generate_unity_sdk, BuiltinPlugin, DojoContract, DojoData, DojoModel, DojoWorld, PluginManager,
+};
+
+#[test]
+fn test_format_model_with_composite_keys() {
+    let model = composite(
+        "dojo_examples::models::Tile",
+        CompositeType::Struct,
+        vec![
+            field(0, "x", CompositeInnerKind::Key, basic("core::integer::u32")),
+            field(
+                1,
+                "player",
+                CompositeInnerKind::Key,
+                basic("core::starknet::contract_address::ContractAddress"),
+            ),
+            field(2, "value", CompositeInnerKind::Data, basic("core::felt252")),
+        ],
+    );
+
+    let formatted = UnityPlugin::new().format_model("dojo_examples", &model, &HashMap::new());
+
+    let keys_start = formatted.find("public struct Keys {").expect("keys struct is generated");
+    let keys_end = keys_start + formatted[keys_start..].find('}').unwrap();
+    let keys_struct = &formatted[keys_start..keys_end];
+    assert!(keys_struct.contains("public uint x;"));
+    assert!(keys_struct.contains("public FieldElement player;"));
+    assert!(!keys_struct.contains("value"));
+    assert!(formatted.contains("return new Keys { x = x, player = player };"));
+    assert!(formatted.contains("public FieldElement Key() {"));
+
+    // Keys must be serialized in declaration order before being hashed.
+    let x = formatted.find("keys.Add(new FieldElement(x).Inner);").expect("x is serialized");
+    let player = formatted.find("keys.Add(player.Inner);").expect("player is serialized");
+    let hash = formatted.find("dojo.poseidon_hash_many").expect("keys are hashed");
+    assert!(x < player && player < hash);
+}
+
+#[test]
+fn test_format_model_without_keys() {
+    let model = composite(
+        "dojo_examples::models::Config",
+        CompositeType::Struct,
+        vec![field(0, "value", CompositeInnerKind::Data, basic("core::felt252"))],
+    );
+
+    let formatted = UnityPlugin::new().format_model("dojo_examples", &model, &HashMap::new());
+
+    assert!(!formatted.contains("struct Keys"));
+    assert!(!formatted.contains("Key()"));
+}
+
+#[test]
+fn test_format_model_lifecycle_methods() {
+    let model = composite(
+        "dojo_examples::models::Config",
+        CompositeType::Struct,
+        vec![field(0, "value", CompositeInnerKind::Data, basic("core::felt252"))],
+    );
+
+    let formatted = UnityPlugin::new().format_model("dojo_examples", &model, &HashMap::new());
+    assert!(!formatted.contains("void Start()"));
+    assert!(!formatted.contains("void Update()"));
+
+    let plugin = UnityPlugin::with_config(UnityPluginConfig {
+        lifecycle_methods: true,
+        ..Default::default()
+    });
+    let formatted = plugin.format_model("dojo_examples", &model, &HashMap::new());
+    assert!(formatted.contains("void Start()"));
+    assert!(formatted.contains("void Update()"));
+}
+
+#[test]
+fn test_format_calldata_transfer() {
+    let u256 = composite(
+        "core::integer::u256",
+        CompositeType::Struct,
+        vec![
+            field(0, "low", CompositeInnerKind::Data, basic("core::integer::u128")),
+            field(1, "high", CompositeInnerKind::Data, basic("core::integer::u128")),
+        ],
+    );
+    let inputs = vec![
+        ("to".to_string(), basic("core::starknet::contract_address::ContractAddress")),
+        ("amount".to_string(), Token::Composite(u256)),
+    ];
+
+    let calldata = UnityPlugin::new().format_calldata(&inputs, "calldata", &HashMap::new());
+
+    assert_eq!(
+        calldata,
+        vec![
+            "calldata.Add(to.Inner);",
+            "calldata.Add(new FieldElement(amount & ((BigInteger.One << 128) - 1)).Inner);",
+            "calldata.Add(new FieldElement(amount >> 128).Inner);",
+        ]
+    );
+}
+
+#[test]
+fn test_format_calldata_array_is_length_prefixed() {
+    let inputs = vec![(
+        "players".to_string(),
+        Token::Array(Array {
+            type_path: "core::array::Array::<core::starknet::contract_address::ContractAddress>"
+                .to_string(),
+            inner: Box::new(basic("core::starknet::contract_address::ContractAddress")),
+            is_legacy: false,
+        }),
+    )];
+
+    let calldata = UnityPlugin::new().format_calldata(&inputs, "calldata", &HashMap::new());
+
+    assert_eq!(
+        calldata,
+        vec![
+            "calldata.Add(new FieldElement(players.Count).Inner);",
+            "calldata.AddRange(players.SelectMany(playersItem => new [] { playersItem.Inner }));",
+        ]
+    );
+}
+
+#[test]
+fn test_format_enum_from_felts_checks_tag_bounds() {
+    let direction = composite(
+        "dojo_examples::models::Direction",
+        CompositeType::Enum,
+        ["None", "Left", "Right", "Up", "Down"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| field(i, name, CompositeInnerKind::Data, basic("()")))
+            .collect(),
+    );
+
+    let formatted = UnityPlugin::new().format_enum(&direction);
+
+    let from_felts = formatted
+        .find("public static Direction FromFelts(FieldElement[] felts, ref int offset)")
+        .expect("deserializer is generated");
+    let bounds_check = formatted.find("if (tag >= 5) {").expect("tag is bounds checked");
+    let throw = formatted[bounds_check..]
+        .find("throw new DeserializationException(")
+        .map(|i| i + bounds_check)
+        .expect("out of range tag throws");
+    let switch = formatted.find("switch ((int)tag) {").expect("tag is matched");
+
+    assert!(from_felts < bounds_check && bounds_check < throw && throw < switch);
+    assert!(formatted.contains("case 0: return new None();"));
+    assert!(formatted.contains("case 4: return new Down();"));
+    assert!(!formatted.contains("case 5:"));
+}
+
+#[test]
+fn test_format_enum_with_data_has_no_from_felts() {
+    let option = composite(
+        "core::option::Option::<core::integer::u32>",
+        CompositeType::Enum,
+        vec![
+            field(0, "Some", CompositeInnerKind::Data, basic("core::integer::u32")),
+            field(1, "None", CompositeInnerKind::Data, basic("()")),
+        ],
+    );
+
+    assert!(!UnityPlugin::new().format_enum(&option).contains("FromFelts"));
+}
+
+#[test]
+fn test_format_enum_with_unit_and_data_variants() {
+    let action = composite(
+        "dojo_examples::models::Action",
+        CompositeType::Enum,
+        vec![
+            field(0, "Idle", CompositeInnerKind::Data, basic("()")),
+            field(
+                1,
+                "Move",
+                CompositeInnerKind::Data,
+                Token::Composite(vec2("dojo_examples::models::Vec2", "core::integer::u32")),
+            ),
+            field(2, "Attack", CompositeInnerKind::Data, basic("core::felt252")),
+        ],
+    );
+
+    assert_eq!(
+        UnityPlugin::new().format_enum(&action),
+        "
+// Type definition for `dojo_examples::models::Action` enum
+public abstract record Action() : Enum {
+    public record Idle() : Action;
+    public record Move(Vec2 value) : Action;
+    public record Attack(FieldElement value) : Action;
+}
+"
+    );
+}
+
+#[test]
+fn test_format_struct_with_wide_integers() {
+    let u256 = composite(
+        "core::integer::u256",
+        CompositeType::Struct,
+        vec![
+            field(0, "low", CompositeInnerKind::Data, basic("core::integer::u128")),
+            field(1, "high", CompositeInnerKind::Data, basic("core::integer::u128")),
+        ],
+    );
+    let balance = composite(
+        "dojo_examples::models::Balance",
+        CompositeType::Struct,
+        vec![
+            field(0, "amount", CompositeInnerKind::Data, Token::Composite(u256)),
+            field(1, "fees", CompositeInnerKind::Data, basic("core::integer::u128")),
+        ],
+    );
+
+    let formatted = UnityPlugin::new().format_struct(&balance);
+
+    assert!(formatted.contains("public BigInteger amount;"));
+    assert!(formatted.contains("public BigInteger fees;"));
+    assert!(!formatted.contains("Span"));
+}
+
+#[test]
+fn test_format_struct_with_arrays() {
+    let array = |type_path: &str, inner: Token| {
+        Token::Array(Array {
+            type_path: type_path.to_string(),
+            inner: Box::new(inner),
+            is_legacy: false,
+        })
+    };
+    let felts = array("core::array::Array::<core::felt252>", basic("core::felt252"));
+    let inventory = composite(
+        "dojo_examples::models::Inventory",
+        CompositeType::Struct,
+        vec![
+            field(
+                0,
+                "items",
+                CompositeInnerKind::Data,
+                array("core::array::Array::<core::integer::u32>", basic("core::integer::u32")),
+            ),
+            field(
+                1,
+                "tags",
+                CompositeInnerKind::Data,
+                array("core::array::Span::<core::felt252>", basic("core::felt252")),
+            ),
+            field(
+                2,
+                "grid",
+                CompositeInnerKind::Data,
+                array("core::array::Array::<core::array::Array::<core::felt252>>", felts),
+            ),
+        ],
+    );
+
+    let formatted = UnityPlugin::new().format_struct(&inventory);
+
+    assert!(formatted.contains("public List<uint> items;"));
+    assert!(formatted.contains("public List<FieldElement> tags;"));
+    assert!(formatted.contains("public List<List<FieldElement>> grid;"));
+}
+
+fn option(inner: Token) -> Token {
+    let mut option = composite(
+        "core::option::Option",
+        CompositeType::Enum,
+        vec![
+            field(0, "Some", CompositeInnerKind::Data, Token::GenericArg("T".to_string())),
+            field(1, "None", CompositeInnerKind::Data, basic("()")),
+        ],
+    );
+    option.generic_args = vec![("T".to_string(), inner)];
+    Token::Composite(option)
+}
+
+#[test]
+fn test_map_type_option() {
+    let vec2 = Token::Composite(vec2("dojo_examples::models::Vec2", "core::integer::u32"));
+
+    let plugin = UnityPlugin::new();
+    assert_eq!(plugin.map_type(&option(basic("core::integer::u32"))), "uint?");
+    assert_eq!(plugin.map_type(&option(vec2.clone())), "Vec2?");
+    // Reference types are nullable as is.
+    assert_eq!(plugin.map_type(&option(basic("core::felt252"))), "FieldElement");
+    assert_eq!(
+        plugin.map_type(&option(Token::Composite(composite(
+            "dojo_examples::models::Direction",
+            CompositeType::Enum,
+            vec![field(0, "Left", CompositeInnerKind::Data, basic("()"))],
+        )))),
+        "Direction"
+    );
+
+    let plugin = UnityPlugin::with_config(UnityPluginConfig {
+        nullable_options: false,
+        ..Default::default()
+    });
+    assert_eq!(plugin.map_type(&option(basic("core::integer::u32"))), "Option<uint>");
+    assert_eq!(plugin.map_type(&option(vec2)), "Option<Vec2>");
+}
+
+#[test]
+fn test_format_felts_reader_option_and_result() {
+    let plugin = UnityPlugin::new();
+
+    assert_eq!(
+        plugin.format_felts_reader(&option(basic("core::integer::u32")), &HashMap::new()).unwrap(),
+        "(FeltsReader.ReadVariant(felts, ref offset, 2, \"Option\") == 0 ? \
+         (uint?)(uint)FeltsReader.ToBigInteger(felts[offset++]) : null)"
+    );
+
+    let mut result = composite(
+        "core::result::Result",
+        CompositeType::Enum,
+        vec![
+            field(0, "Ok", CompositeInnerKind::Data, Token::GenericArg("T".to_string())),
+            field(1, "Err", CompositeInnerKind::Data, Token::GenericArg("E".to_string())),
+        ],
+    );
+    result.generic_args = vec![
+        ("T".to_string(), basic("core::integer::u8")),
+        ("E".to_string(), basic("core::felt252")),
+    ];
+    assert_eq!(
+        plugin.format_felts_reader(&Token::Composite(result), &HashMap::new()).unwrap(),
+        "(FeltsReader.ReadVariant(felts, ref offset, 2, \"Result\") == 0 ? (Result<byte, \
+         FieldElement>)new Result<byte, FieldElement>.Ok((byte)FeltsReader.ToBigInteger(felts[\
+         offset++])) : new Result<byte, FieldElement>.Err(felts[offset++]))"
+    );
+}
+
+#[test]
+fn test_format_calldata_option() {
+    let inputs = vec![
+        ("amount".to_string(), option(basic("core::integer::u32"))),
+        ("owner".to_string(), option(basic("core::starknet::contract_address::ContractAddress"))),
+    ];
+
+    let calldata = UnityPlugin::new().format_calldata(&inputs, "calldata", &HashMap::new());
+
+    assert_eq!(
+        calldata,
+        vec![
+            "calldata.Add(new FieldElement(amount != null ? 0 : 1).Inner);",
+            "if (amount != null) calldata.Add(new FieldElement(amount.Value).Inner);",
+            "calldata.Add(new FieldElement(owner != null ? 0 : 1).Inner);",
+            "if (owner != null) calldata.Add(owner.Inner);",
+        ]
+    );
+
+    // The options nested in a struct are checked through their field path.
+    let settings = composite(
+        "dojo_examples::models::Settings",
+        CompositeType::Struct,
+        vec![field(0, "x", CompositeInnerKind::Data, option(basic("core::integer::u32")))],
+    );
+    let inputs = vec![("s".to_string(), Token::Composite(settings))];
+
+    let calldata = UnityPlugin::new().format_calldata(&inputs, "calldata", &HashMap::new());
+
+    assert_eq!(
+        calldata,
+        vec![
+            "calldata.Add(new FieldElement(s.x != null ? 0 : 1).Inner);",
+            "if (s.x != null) calldata.Add(new FieldElement(s.x.Value).Inner);",
+        ]
+    );
+}
+
+#[test]
+fn test_handle_model_with_option_fields() {
+    let vec2 = vec2("dojo_examples::models::Vec2", "core::integer::u32");
+    let model = composite(
+        "dojo_examples::models::Target",
+        CompositeType::Struct,
+        vec![
+            field(
+                0,
+                "player",
+                CompositeInnerKind::Key,
+                basic("core::starknet::contract_address::ContractAddress"),
+            ),
+            field(1, "count", CompositeInnerKind::Data, option(basic("core::integer::u32"))),
+            field(2, "vec", CompositeInnerKind::Data, option(Token::Composite(vec2.clone()))),
+        ],
+    );
+    let Token::Composite(option_enum) = option(Token::GenericArg("T".to_string())) else {
+        unreachable!()
+    };
+    let model = DojoModel {
+        tag: "dojo_examples-Target".to_string(),
+        tokens: TokenizedAbi {
+            structs: vec![Token::Composite(vec2), Token::Composite(model)],
+            enums: vec![Token::Composite(Composite { generic_args: vec![], ..option_enum })],
+            ..Default::default()
+        },
+    };
+
+    let formatted = UnityPlugin::new().handle_model(&model, &mut HashMap::new());
+
+    assert!(formatted.contains("public uint? count;"));
+    assert!(formatted.contains("public Vec2? vec;"));
+    assert!(formatted.contains("count = null;"));
+    assert!(formatted.contains("vec = null;"));
+    // No record is generated for the options.
+    assert!(!formatted.contains("record Option"));
+}
+
+#[test]
+fn test_format_struct_with_nested_tuple() {
+    let tuple = |type_path: &str, inners: Vec<Token>| {
+        Token::Tuple(Tuple { type_path: type_path.to_string(), inners })
+    };
+    let pair = tuple(
+        "(core::integer::u8, (core::integer::u16, core::felt252))",
+        vec![
+            basic("core::integer::u8"),
+            tuple(
+                "(core::integer::u16, core::felt252)",
+                vec![basic("core::integer::u16"), basic("core::felt252")],
+            ),
+        ],
+    );
+    let cell = composite(
+        "dojo_examples::models::Cell",
+        CompositeType::Struct,
+        vec![field(0, "pair", CompositeInnerKind::Data, pair.clone())],
+    );
+
+    let plugin = UnityPlugin::new();
+    assert!(plugin.format_struct(&cell).contains("public (byte, (ushort, FieldElement)) pair;"));
+    assert_eq!(
+        plugin.format_felts_reader(&pair, &HashMap::new()).unwrap(),
+        "((byte)FeltsReader.ToBigInteger(felts[offset++]), \
+         ((ushort)FeltsReader.ToBigInteger(felts[offset++]), felts[offset++]))"
+    );
+}
+
+#[test]
+fn test_format_model_with_byte_array() {
+    let name = basic("core::byte_array::ByteArray");
+    let player = composite(
+        "dojo_examples::models::Player",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "name", CompositeInnerKind::Data, name.clone()),
+        ],
+    );
+
+    let plugin = UnityPlugin::new();
+    let formatted = plugin.format_model("dojo_examples", &player, &HashMap::new());
+    assert!(formatted.contains("[ModelField(\"name\")]\n        public string name;"));
+
+    assert_eq!(
+        plugin.format_felts_reader(&name, &HashMap::new()).unwrap(),
+        "ByteArrayDeserializer.FromFelts(felts, ref offset)"
+    );
+    let helpers = UnityPlugin::format_deserialization_helpers();
+    assert!(
+        helpers.contains("public static string FromFelts(FieldElement[] felts, ref int offset) {")
+    );
+    // Empty strings only have an empty pending word.
+    assert!(helpers.contains("if (pendingLength > 0) {"));
+}
+
+#[test]
+fn test_map_type_usize_override() {
+    let default = UnityPlugin::new();
+    assert_eq!(default.map_type(&basic("core::integer::usize")), "uint");
+    assert_eq!(default.map_type(&basic("core::integer::u32")), "uint");
+
+    let plugin = UnityPlugin::with_config(UnityPluginConfig {
+        usize_type: "int".to_string(),
+        ..Default::default()
+    });
+    assert_eq!(plugin.map_type(&basic("core::integer::usize")), "int");
+    assert_eq!(plugin.map_type(&basic("core::integer::u32")), "uint");
+}
+
+#[test]
+fn test_format_model_default_constructor() {
+    let u256 = composite(
+        "core::integer::u256",
+        CompositeType::Struct,
+        vec![
+            field(0, "low", CompositeInnerKind::Data, basic("core::integer::u128")),
+            field(1, "high", CompositeInnerKind::Data, basic("core::integer::u128")),
+        ],
+    );
+    let model = composite(
+        "dojo_examples::models::Inventory",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "gold", CompositeInnerKind::Data, Token::Composite(u256)),
+            field(
+                2,
+                "items",
+                CompositeInnerKind::Data,
+                Token::Array(Array {
+                    type_path: "core::array::Array::<core::integer::u32>".to_string(),
+                    inner: Box::new(basic("core::integer::u32")),
+                    is_legacy: false,
+                }),
+            ),
+        ],
+    );
+
+    let formatted = UnityPlugin::new().format_model("dojo_examples", &model, &HashMap::new());
+
+    let constructor = formatted.find("public Inventory() {").expect("constructor is generated");
+    let end = constructor + formatted[constructor..].find('}').unwrap();
+    let constructor = &formatted[constructor..end];
+    assert!(constructor.contains("player = new FieldElement(0);"));
+    assert!(constructor.contains("gold = BigInteger.Zero;"));
+    assert!(constructor.contains("items = new List<uint>();"));
+}
+
+#[test]
+fn test_format_model_as_record() {
+    let vec2 = composite(
+        "dojo_examples::models::Vec2",
+        CompositeType::Struct,
+        vec![
+            field(0, "x", CompositeInnerKind::Data, basic("core::integer::u32")),
+            field(1, "y", CompositeInnerKind::Data, basic("core::integer::u32")),
+        ],
+    );
+    let model = composite(
+        "dojo_examples::models::Position",
+        CompositeType::Struct,
+        vec![
+            field(
+                0,
+                "player",
+                CompositeInnerKind::Key,
+                basic("core::starknet::contract_address::ContractAddress"),
+            ),
+            field(1, "vec", CompositeInnerKind::Data, Token::Composite(vec2.clone())),
+        ],
+    );
+
+    let plugin =
+        UnityPlugin::with_config(UnityPluginConfig { records: true, ..Default::default() });
+
+    let formatted = plugin.format_model("dojo_examples", &model, &HashMap::new());
+    assert!(formatted.contains(
+        "public record struct Position([field: Key, ModelField(\"player\")] FieldElement player, \
+         [field: ModelField(\"vec\")] Vec2 vec) {"
+    ));
+    assert!(!formatted.contains("ModelInstance"));
+
+    // Serialized into the keys followed by the values, and read back in the same order.
+    assert!(formatted.contains("public dojo.FieldElement[] ToFelts() {"));
+    let player = formatted.find("felts.Add(player.Inner);").expect("player is serialized");
+    let x = formatted.find("felts.Add(new FieldElement(vec.x).Inner);").expect("x is serialized");
+    let y = formatted.find("felts.Add(new FieldElement(vec.y).Inner);").expect("y is serialized");
+    assert!(player < x && x < y);
+    assert!(formatted.contains(
+        "public static Position FromFelts(FieldElement[] felts, ref int offset) {\n            \
+         return new Position { player = felts[offset++], vec = new Vec2 { x = \
+         (uint)FeltsReader.ToBigInteger(felts[offset++]), y = \
+         (uint)FeltsReader.ToBigInteger(felts[offset++]) } };"
+    ));
+
+    // The keys are still hashed into the entity id.
+    assert!(formatted.contains("public struct Keys {"));
+    assert!(formatted.contains("public FieldElement Key() {"));
+
+    let formatted = plugin.format_struct(&vec2);
+    assert!(formatted.contains("public record struct Vec2(uint x, uint y);"));
+}
+
+#[test]
+fn test_format_model_key_attributes() {
+    let model = composite(
+        "dojo_examples::models::Moves",
+        CompositeType::Struct,
+        vec![
+            field(
+                0,
+                "player",
+                CompositeInnerKind::Key,
+                basic("core::starknet::contract_address::ContractAddress"),
+            ),
+            field(1, "remaining", CompositeInnerKind::Data, basic("core::integer::u8")),
+        ],
+    );
+
+    let formatted = UnityPlugin::new().format_model("dojo_examples", &model, &HashMap::new());
+
+    assert!(formatted.contains("[Key]\n        [ModelField(\"player\")]"));
+    assert!(formatted.contains("[ModelField(\"remaining\")]"));
+    assert!(!formatted.contains("[Key]\n        [ModelField(\"remaining\")]"));
+    assert_eq!(formatted.matches("[Key]").count(), 1);
+}
+
+fn contract_abi(vec2: &Composite, system: &str) -> TokenizedAbi {
+    let function = Token::Function(Function {
+        name: system.to_string(),
+        state_mutability: StateMutability::External,
+        inputs: vec![("dir".to_string(), Token::Composite(vec2.clone()))],
+        outputs: vec![],
+        named_outputs: vec![],
+    });
+
+    TokenizedAbi {
+        structs: vec![Token::Composite(vec2.clone())],
+        interfaces: HashMap::from([(format!("dojo_examples::I{system}"), vec![function])]),
+        ..Default::default()
+    }
+}
+
+fn vec2(type_path: &str, y_type: &str) -> Composite {
+    composite(
+        type_path,
+        CompositeType::Struct,
+        vec![
+            field(0, "x", CompositeInnerKind::Data, basic("core::integer::u32")),
+            field(1, "y", CompositeInnerKind::Data, basic(y_type)),
+        ],
+    )
+}
+
+#[test]
+fn test_generate_sdk_from_multiple_abis() {
+    let inputs = vec![
+        (
+            "actions".to_string(),
+            contract_abi(&vec2("dojo_examples::actions::Vec2", "core::integer::u32"), "Move"),
+        ),
+        (
+            "others".to_string(),
+            contract_abi(&vec2("dojo_examples::others::Vec2", "core::integer::u32"), "Teleport"),
+        ),
+    ];
+
+    let files = generate_unity_sdk(inputs, UnityPluginConfig::default()).unwrap();
+
+    let types = String::from_utf8(files[&PathBuf::from("Types.gen.cs")].clone()).unwrap();
+    assert_eq!(types.matches("public struct Vec2 {").count(), 1);
+
+    let actions =
+        String::from_utf8(files[&PathBuf::from("Contracts/actions.gen.cs")].clone()).unwrap();
+    assert!(actions.contains("public async Task<FieldElement> Move(Account account, Vec2 dir)"));
+
+    let others =
+        String::from_utf8(files[&PathBuf::from("Contracts/others.gen.cs")].clone()).unwrap();
+    assert!(others.contains("public async Task<FieldElement> Teleport(Account account, Vec2 dir)"));
+}
+
+#[test]
+fn test_generate_sdk_with_conflicting_types() {
+    let inputs = vec![
+        (
+            "actions".to_string(),
+            contract_abi(&vec2("dojo_examples::actions::Vec2", "core::integer::u32"), "Move"),
+        ),
+        (
+            "others".to_string(),
+            contract_abi(&vec2("dojo_examples::others::Vec2", "core::felt252"), "Teleport"),
+        ),
+    ];
+
+    let err = generate_unity_sdk(inputs, UnityPluginConfig::default()).unwrap_err();
+    assert!(matches!(err, Error::TypeConflict(name) if name == "Vec2"));
+}
+
+fn unknown_type_inputs() -> Vec<(String, TokenizedAbi)> {
+    let vec2 =
+        vec2("dojo_examples::actions::Vec2", "core::starknet::storage_access::StorageAddress");
+    vec![("actions".to_string(), contract_abi(&vec2, "Move"))]
+}
+
+#[test]
+fn test_unknown_types_error() {
+    let err = generate_unity_sdk(unknown_type_inputs(), UnityPluginConfig::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::UnsupportedTypes(types)
+            if types == ["core::starknet::storage_access::StorageAddress (used by Vec2.y)"]
+    ));
+}
+
+#[test]
+fn test_unknown_types_passthrough() {
+    let config =
+        UnityPluginConfig { unknown_types: UnknownTypePolicy::Passthrough, ..Default::default() };
+    let files = generate_unity_sdk(unknown_type_inputs(), config).unwrap();
+
+    let types = String::from_utf8(files[&PathBuf::from("Types.gen.cs")].clone()).unwrap();
+    assert!(types.contains("public StorageAddress y;"));
+    assert!(!files.contains_key(&PathBuf::from("Placeholders.gen.cs")));
+}
+
+#[test]
+fn test_unknown_types_placeholder() {
+    let config =
+        UnityPluginConfig { unknown_types: UnknownTypePolicy::Placeholder, ..Default::default() };
+    let files = generate_unity_sdk(unknown_type_inputs(), config).unwrap();
+
+    let types = String::from_utf8(files[&PathBuf::from("Types.gen.cs")].clone()).unwrap();
+    assert!(types.contains("public StorageAddress y;"));
+
+    let placeholders =
+        String::from_utf8(files[&PathBuf::from("Placeholders.gen.cs")].clone()).unwrap();
+    assert!(placeholders.contains(
+        "// TODO: `core::starknet::storage_access::StorageAddress` is not supported by the \
+         bindgen, replace this placeholder.\npublic struct StorageAddress {\n}"
+    ));
+}
+
+#[test]
+fn test_format_model_editor_only_members() {
+    let model = composite(
+        "dojo_examples::models::Moves",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "remaining", CompositeInnerKind::Data, basic("core::integer::u8")),
+        ],
+    );
+
+    let plugin = UnityPlugin::with_config(UnityPluginConfig {
+        lifecycle_methods: true,
+        editor_only: HashSet::from([UnityMember::LifecycleMethods]),
+        ..Default::default()
+    });
+    let formatted = plugin.format_model("dojo_examples", &model, &HashMap::new());
+
+    let guard = formatted.find("#if UNITY_EDITOR").expect("guard is emitted");
+    let start = formatted.find("void Start()").unwrap();
+    let update = formatted.find("void Update()").unwrap();
+    let end = formatted.find("#endif").expect("guard is closed");
+    assert!(guard < start && start < update && update < end);
+    assert_eq!(formatted.matches("#if UNITY_EDITOR").count(), 1);
+
+    // Other members aren't guarded.
+    assert!(formatted.find("public FieldElement Key()").unwrap() < guard);
+
+    let formatted = UnityPlugin::new().format_model("dojo_examples", &model, &HashMap::new());
+    assert!(!formatted.contains("#if UNITY_EDITOR"));
+}
+
+#[test]
+fn test_format_model_nested_struct_accessor() {
+    let stats = composite(
+        "dojo_examples::models::Stats",
+        CompositeType::Struct,
+        vec![field(0, "hp", CompositeInnerKind::Data, basic("core::integer::u32"))],
+    );
+    let model = composite(
+        "dojo_examples::models::Player",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "level", CompositeInnerKind::Data, basic("core::integer::u8")),
+            field(2, "stats", CompositeInnerKind::Data, Token::Composite(stats.clone())),
+            field(3, "name", CompositeInnerKind::Data, basic("core::felt252")),
+        ],
+    );
+
+    let handled_tokens = HashMap::from([(stats.type_path.clone(), stats)]);
+    let formatted = UnityPlugin::new().format_model("dojo_examples", &model, &handled_tokens);
+
+    // Only the `stats` felt is read, right after the `level` value, the key being excluded.
+    assert!(formatted.contains("public static Stats GetStats(FieldElement[] felts) {"));
+    assert!(formatted.contains("if (felts.Length < 2) {"));
+    assert!(formatted.contains(
+        "var offset = 1;
+            return new Stats { hp = (uint)FeltsReader.ToBigInteger(felts[offset++]) };"
+    ));
+    assert!(!formatted.contains("GetLevel"));
+    assert!(!formatted.contains("GetName"));
+}
+
+#[tokio::test]
+async fn test_generate_code_outputs() {
+    let position = composite(
+        "dojo_examples::models::Position",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "x", CompositeInnerKind::Data, basic("core::integer::u32")),
+        ],
+    );
+    let model = DojoModel {
+        tag: "dojo_examples-Position".to_string(),
+        tokens: TokenizedAbi { structs: vec![Token::Composite(position)], ..Default::default() },
+    };
+    let contract = DojoContract {
+        tag: "dojo_examples-actions".to_string(),
+        tokens: TokenizedAbi::default(),
+        systems: vec![],
+    };
+    let data = DojoData {
+        world: DojoWorld { name: "dojo_examples".to_string() },
+        models: HashMap::from([(model.tag.clone(), model)]),
+        contracts: HashMap::from([(contract.tag.clone(), contract)]),
+    };
+
+    let out = UnityPlugin::new().generate_code(&data).await.unwrap();
+
+    let mut paths = out.keys().cloned().collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            "Contracts/dojo_examples-actions.gen.cs",
+            "Deserialization.gen.cs",
+            "Models/dojo_examples-Position.gen.cs",
+        ]
+        .map(PathBuf::from)
+    );
+
+    let model =
+        String::from_utf8(out[&PathBuf::from("Models/dojo_examples-Position.gen.cs")].clone())
+            .unwrap();
+    assert!(model.contains("public class Position : ModelInstance {"));
+}
+
+#[tokio::test]
+async fn test_generate_code_with_unknown_model_field() {
+    let position = composite(
+        "dojo_examples::models::Position",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(
+                1,
+                "slot",
+                CompositeInnerKind::Data,
+                basic("core::starknet::storage_access::StorageAddress"),
+            ),
+        ],
+    );
+    let model = DojoModel {
+        tag: "dojo_examples-Position".to_string(),
+        tokens: TokenizedAbi { structs: vec![Token::Composite(position)], ..Default::default() },
+    };
+    let data = DojoData {
+        world: DojoWorld { name: "dojo_examples".to_string() },
+        models: HashMap::from([(model.tag.clone(), model)]),
+        contracts: HashMap::new(),
+    };
+
+    let err = UnityPlugin::new().generate_code(&data).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Unsupported types: core::starknet::storage_access::StorageAddress (used by Position.slot)"
+    );
+    assert!(matches!(
+        err,
+        Error::UnsupportedTypes(types)
+            if types == ["core::starknet::storage_access::StorageAddress (used by Position.slot)"]
+    ));
+}
+
+#[tokio::test]
+async fn test_generate_code_emits_shared_structs_once() {
+    let vec2 = vec2("dojo_examples::models::Vec2", "core::integer::u32");
+    let model = |name: &str, fields: Vec<CompositeInner>| {
+        let model =
+            composite(&format!("dojo_examples::models::{name}"), CompositeType::Struct, fields);
+        let tag = format!("dojo_examples-{name}");
+        let tokens = TokenizedAbi {
+            structs: vec![Token::Composite(model), Token::Composite(vec2.clone())],
+            ..Default::default()
+        };
+        (tag.clone(), DojoModel { tag, tokens })
+    };
+    let player = || field(0, "player", CompositeInnerKind::Key, basic("core::felt252"));
+    let data = DojoData {
+        world: DojoWorld { name: "dojo_examples".to_string() },
+        models: HashMap::from([
+            model(
+                "Position",
+                vec![
+                    player(),
+                    field(1, "vec", CompositeInnerKind::Data, Token::Composite(vec2.clone())),
+                ],
+            ),
+            model(
+                "Target",
+                vec![
+                    player(),
+                    field(1, "from", CompositeInnerKind::Data, Token::Composite(vec2.clone())),
+                    field(2, "to", CompositeInnerKind::Data, Token::Composite(vec2.clone())),
+                ],
+            ),
+        ]),
+        contracts: HashMap::new(),
+    };
+
+    let out = UnityPlugin::new().generate_code(&data).await.unwrap();
+
+    let definitions = out
+        .values()
+        .map(|code| String::from_utf8_lossy(code).matches("public struct Vec2 {").count())
+        .sum::<usize>();
+    assert_eq!(definitions, 1);
+}
+
+#[test]
+fn test_handle_model_with_nested_structs() {
+    let inner = composite(
+        "dojo_examples::models::Inner",
+        CompositeType::Struct,
+        vec![field(0, "x", CompositeInnerKind::Data, basic("core::felt252"))],
+    );
+    let outer = composite(
+        "dojo_examples::models::Outer",
+        CompositeType::Struct,
+        vec![field(0, "inner", CompositeInnerKind::Data, Token::Composite(inner.clone()))],
+    );
+    let holder = composite(
+        "dojo_examples::models::Holder",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "outer", CompositeInnerKind::Data, Token::Composite(outer.clone())),
+        ],
+    );
+    // The ABI of the model lists all the structs it references, at any depth.
+    let model = DojoModel {
+        tag: "dojo_examples-Holder".to_string(),
+        tokens: TokenizedAbi {
+            structs: [holder, outer, inner].into_iter().map(Token::Composite).collect(),
+            ..Default::default()
+        },
+    };
+
+    let formatted = UnityPlugin::new().handle_model(&model, &mut HashMap::new());
+
+    assert!(formatted.contains("public struct Outer {\n    public Inner inner;\n}"));
+    assert!(formatted.contains("public struct Inner {\n    public FieldElement x;\n}"));
+    assert!(formatted.contains("public Outer outer;"));
+}
+
+#[tokio::test]
+async fn test_generate_code_same_model_name_in_namespaces() {
+    let model = |namespace: &str| {
+        let vec2 = vec2(&format!("{namespace}::models::Vec2"), "core::integer::u32");
+        let position = composite(
+            &format!("{namespace}::models::Position"),
+            CompositeType::Struct,
+            vec![
+                field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+                field(1, "vec", CompositeInnerKind::Data, Token::Composite(vec2.clone())),
+            ],
+        );
+        let tag = format!("{namespace}-Position");
+        let tokens = TokenizedAbi {
+            structs: vec![Token::Composite(position), Token::Composite(vec2)],
+            ..Default::default()
+        };
+        (tag.clone(), DojoModel { tag, tokens })
+    };
+    let data = DojoData {
+        world: DojoWorld { name: "dojo_examples".to_string() },
+        models: HashMap::from([model("dojo_examples"), model("dojo_others")]),
+        contracts: HashMap::new(),
+    };
+    let manager = |output_path: PathBuf, builtin_plugins: Vec<BuiltinPlugins>| PluginManager {
+        profile_name: "dev".to_string(),
+        root_package_name: "dojo_examples".to_string(),
+        output_path,
+        manifest_path: Utf8PathBuf::from("Scarb.toml"),
+        builtin_plugins,
+        plugins: vec![],
+        unity_config: UnityPluginConfig::default(),
+        post_generation_hook: None,
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    manager(dir.path().to_path_buf(), vec![BuiltinPlugins::Unity])
+        .generate_from_data(&data)
+        .await
+        .unwrap();
+
+    for namespace in ["dojo_examples", "dojo_others"] {
+        let path = dir.path().join(format!("unity/Models/{namespace}-Position.gen.cs"));
+        let code = std::fs::read_to_string(path).unwrap();
+        assert!(code.contains(&format!(
+            "namespace {namespace} {{\n    // Model definition for \
+             `{namespace}::models::Position` model\n    public class Position : ModelInstance {{"
+        )));
+        assert!(code.contains(&format!(
+            "namespace {namespace}.models {{\n    // Type definition for \
+             `{namespace}::models::Vec2` struct\n    [Serializable]\n    public struct Vec2 {{"
+        )));
+        assert!(code.contains(&format!("public {namespace}.models.Vec2 vec;")));
+    }
+
+    // The types still collide for the plugins which don't namespace them, so nothing is written.
+    let dir = tempfile::tempdir().unwrap();
+    let result =
+        manager(dir.path().to_path_buf(), vec![BuiltinPlugins::Unity, BuiltinPlugins::Typescript])
+            .generate_from_data(&data)
+            .await;
+    assert!(matches!(result, Err(Error::NameCollision(_))));
+    assert!(!dir.path().join("unity").exists());
+}
+
+#[test]
+fn test_handle_contract_with_mock() {
+    let spawn = Token::Function(Function {
+        name: "spawn".to_string(),
+        state_mutability: StateMutability::External,
+        inputs: vec![],
+        outputs: vec![],
+        named_outputs: vec![],
+    });
+    let contract = DojoContract {
+        tag: "dojo_examples-actions".to_string(),
+        tokens: TokenizedAbi::default(),
+        systems: vec![spawn],
+    };
+
+    let plugin = UnityPlugin::with_config(UnityPluginConfig { mocks: true, ..Default::default() });
+    let formatted = plugin.handle_contract(&contract, &HashMap::new());
+
+    assert!(formatted.contains("public interface IActions {"));
+    assert!(formatted.contains("Task<FieldElement> spawn(Account account);"));
+    assert!(formatted.contains("public class Actions : MonoBehaviour, IActions {"));
+    assert!(formatted.contains("public async Task<FieldElement> spawn(Account account) {"));
+    assert!(formatted.contains("public class MockActions : IActions {"));
+    assert!(formatted.contains("public Task<FieldElement> spawn(Account account) {"));
+    assert!(formatted
+        .contains("calls.Add(new MockCall { system = \"spawn\", args = new object[] { } });"));
+
+    let formatted = UnityPlugin::new().handle_contract(&contract, &HashMap::new());
+    assert!(formatted.contains("public class Actions : MonoBehaviour {"));
+    assert!(!formatted.contains("IActions"));
+}
+
+#[test]
+fn test_handle_contract_systems() {
+    let vec2 = vec2("dojo_examples::models::Vec2", "core::integer::u32");
+    let system = |name: &str, state_mutability, inputs| {
+        Token::Function(Function {
+            name: name.to_string(),
+            state_mutability,
+            inputs,
+            outputs: vec![],
+            named_outputs: vec![],
+        })
+    };
+    let contract = DojoContract {
+        tag: "dojo_examples-actions".to_string(),
+        tokens: TokenizedAbi::default(),
+        systems: vec![
+            system(
+                "move_to",
+                StateMutability::External,
+                vec![
+                    ("x".to_string(), basic("core::felt252")),
+                    ("y".to_string(), basic("core::felt252")),
+                    ("dir".to_string(), Token::Composite(vec2.clone())),
+                ],
+            ),
+            system("check", StateMutability::View, vec![("x".to_string(), basic("core::felt252"))]),
+        ],
+    };
+    let handled_tokens = HashMap::from([(vec2.type_path.clone(), vec2)]);
+
+    let formatted = UnityPlugin::new().handle_contract(&contract, &handled_tokens);
+
+    assert!(formatted.contains(
+        "public async Task<FieldElement> move_to(Account account, FieldElement x, FieldElement y, \
+         Vec2 dir) {"
+    ));
+    assert!(formatted.contains(
+        "calldata.Add(x.Inner);\n\t\tcalldata.Add(y.Inner);\n\t\tcalldata.Add(new \
+         FieldElement(dir.x).Inner);\n\t\tcalldata.Add(new FieldElement(dir.y).Inner);"
+    ));
+    assert!(formatted.contains("public const int move_toCalldataLength = 4;"));
+    assert!(formatted.contains("selector = \"move_to\","));
+    assert!(formatted.contains(
+        "public async Task<FieldElement[]> check(JsonRpcClient provider, FieldElement x) {
+        List<dojo.FieldElement> calldata = new List<dojo.FieldElement>();
+        calldata.Add(x.Inner);
+
+        return await provider.Call(new dojo.Call{
+            to = contractAddress,
+            selector = \"check\",
+            calldata = calldata.ToArray()
+        }, new dojo.BlockId {
+            tag = dojo.BlockId_Tag.BlockTag_,
+            block_tag = dojo.BlockTag.Pending
+        });
+    }"
+    ));
+    // The view function is only called, never executed.
+    assert!(!formatted.contains("checkCalldataLength"));
+    assert!(!formatted.contains("check(Account account"));
+}
+
+#[test]
+fn test_handle_contract_events() {
+    let mut moved = composite(
+        "dojo_examples::actions::actions::Moved",
+        CompositeType::Struct,
+        vec![
+            field(
+                0,
+                "player",
+                CompositeInnerKind::Key,
+                basic("core::starknet::contract_address::ContractAddress"),
+            ),
+            field(1, "direction", CompositeInnerKind::Data, basic("core::integer::u8")),
+        ],
+    );
+    moved.is_event = true;
+    let contract = DojoContract {
+        tag: "dojo_examples-actions".to_string(),
+        tokens: TokenizedAbi {
+            structs: vec![
+                Token::Composite(moved.clone()),
+                Token::Composite(vec2("dojo_examples::models::Vec2", "core::integer::u32")),
+            ],
+            ..Default::default()
+        },
+        systems: vec![],
+    };
+
+    let selector = get_selector_from_name("Moved").unwrap();
+    assert_eq!(
+        UnityPlugin::new().format_event(&moved, &HashMap::new()),
+        format!(
+            "
+    // Event definition for `dojo_examples::actions::actions::Moved` event
+    [Serializable]
+    public class Moved {{
+        [Key]
+        public FieldElement player;
+        public byte direction;
+
+        // Selector of the event, ie. the first key of the emitted events
+        public static readonly FieldElement Selector = new FieldElement(\"{selector:#x}\");
+
+        // Deserializes the event from the keys and data of an emitted event
+        // Returns false if the event isn't a `Moved` event
+        public static bool TryParse(FieldElement[] keys, FieldElement[] data, out Moved ev) {{
+            ev = null;
+            if (keys.Length == 0 || keys[0].Hex() != Selector.Hex()) {{
+                return false;
+            }}
+
+            FieldElement[] felts;
+            int offset;
+            try {{
+                felts = keys;
+                offset = 1;
+                var player = felts[offset++];
+                felts = data;
+                offset = 0;
+                var direction = (byte)FeltsReader.ToBigInteger(felts[offset++]);
+                ev = new Moved {{ player = player, direction = direction }};
+            }} catch (IndexOutOfRangeException) {{
+                throw new DeserializationException(\"Missing felts for event `Moved`\");
+            }}
+            return true;
+        }}
+    }}
+"
+        )
+    );
+
+    // Only the events are nested in the contract class.
+    let formatted = UnityPlugin::new().handle_contract(&contract, &HashMap::new());
+    assert!(formatted.contains("    public class Moved {"));
+    assert!(!formatted.contains("Vec2"));
+}
+
+#[test]
+fn test_bytes31_type() {
+    let model = composite(
+        "dojo_examples::models::Name",
+        CompositeType::Struct,
+        vec![
+            field(0, "player", CompositeInnerKind::Key, basic("core::felt252")),
+            field(1, "name", CompositeInnerKind::Data, basic("core::bytes_31::bytes31")),
+        ],
+    );
+
+    let plugin = UnityPlugin::new();
+    let formatted = plugin.format_model("dojo_examples", &model, &HashMap::new());
+    assert!(formatted.contains("public Bytes31 name;"));
+    assert!(formatted.contains("name = new Bytes31(new FieldElement(0));"));
+
+    let inputs = vec![("name".to_string(), basic("core::bytes_31::bytes31"))];
+    let calldata = plugin.format_calldata(&inputs, "calldata", &HashMap::new());
+    assert_eq!(calldata, vec!["calldata.Add(name.value.Inner);"]);
+
+    let helpers = UnityPlugin::format_deserialization_helpers();
+    assert!(helpers.contains("public struct Bytes31 {"));
+    assert!(
+        helpers.contains("public static Bytes31 FromFelts(FieldElement[] felts, ref int offset)")
+    );
+}
+
+#[test]
+fn test_format_system_calldata_length() {
+    let system = |name: &str, token: Token| Function {
+        name: name.to_string(),
+        state_mutability: StateMutability::External,
+        inputs: vec![("dir".to_string(), token)],
+        outputs: vec![],
+        named_outputs: vec![],
+    };
+
+    let plugin = UnityPlugin::new();
+
+    let formatted =
+        plugin.format_system(&system("move", basic("core::integer::u8")), &HashMap::new());
+    assert!(formatted.contains("public const int moveCalldataLength = 1;"));
+
+    let array = Token::Array(Array {
+        type_path: "core::array::Array::<core::integer::u8>".to_string(),
+        inner: Box::new(basic("core::integer::u8")),
+        is_legacy: false,
+    });
+    let formatted = plugin.format_system(&system("moves", array), &HashMap::new());
+    assert!(formatted.contains("public const int movesCalldataLength = DynamicCalldataLength;"));
+}
+
+#[test]
+fn test_reserved_words_are_escaped() {
+    let token = composite(
+        "dojo_examples::models::Item",
+        CompositeType::Struct,
+        vec![
+            field(0, "class", CompositeInnerKind::Data, basic("core::integer::u8")),
+            field(1, "level", CompositeInnerKind::Data, basic("core::integer::u8")),
+        ],
+    );
+
+    let formatted = UnityPlugin::new().format_struct(&token);
+    assert!(formatted.contains("public byte @class;"));
+    assert!(formatted.contains("public byte level;"));
+
+    // The reserved words are part of the plugin configuration.
+    let mut reserved_words = ReservedWords::new(CSHARP_KEYWORDS.iter().copied(), "@");
+    reserved_words.remove("class");
+    reserved_words.insert("level");
+
+    let plugin =
+        UnityPlugin::with_config(UnityPluginConfig { reserved_words, ..Default::default() });
+    let formatted = plugin.format_struct(&token);
+    assert!(formatted.contains("public byte class;"));
+    assert!(formatted.contains("public byte @level;"));
+}
@@ -0,0 +1,469 @@
+//! Resolved representation of the models types.
+//!
+//! The plugins render the cainome tokens directly, but tools generating code on their own
+//! (fixtures, custom SDKs...) are better served by a type graph where every model field has been
+//! resolved to its concrete type. [`resolve_types`] builds this graph from the [`DojoData`].
+
+use std::collections::{HashMap, HashSet};
+
+use cainome::parser::tokens::{Composite, CompositeInnerKind, CompositeType, Token};
+use dojo_world::contracts::naming;
+use serde::{Deserialize, Serialize};
+
+use crate::DojoData;
+
+/// The resolved types of all the models of a world.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedSchema {
+    /// Models, sorted by tag.
+    pub models: Vec<ResolvedModel>,
+}
+
+impl ResolvedSchema {
+    /// Returns the model with the given tag, if any.
+    pub fn model(&self, tag: &str) -> Option<&ResolvedModel> {
+        self.models.iter().find(|m| m.tag == tag)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedModel {
+    /// Model tag.
+    pub tag: String,
+    /// Model name, without the namespace.
+    pub name: String,
+    /// Fields of the model, in declaration order.
+    pub fields: Vec<ResolvedField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedField {
+    /// Field name.
+    pub name: String,
+    /// Whether the field is part of the model keys.
+    pub key: bool,
+    /// The resolved type of the field.
+    pub ty: ResolvedType,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResolvedType {
+    /// A core type (integers, felt, addresses...), identified by its full type path.
+    Primitive(String),
+    /// A generic argument which could not be resolved.
+    Generic(String),
+    /// A reference to a composite type being resolved, identified by its full type path. Recursive
+    /// types can't be expanded, so the recursion is cut with a reference.
+    Recursive(String),
+    Array(Box<ResolvedType>),
+    Tuple(Vec<ResolvedType>),
+    Struct {
+        type_path: String,
+        fields: Vec<ResolvedField>,
+    },
+    Enum {
+        type_path: String,
+        variants: Vec<ResolvedVariant>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedVariant {
+    /// Variant name.
+    pub name: String,
+    /// The type of the variant data, `None` for unit variants.
+    pub ty: Option<ResolvedType>,
+}
+
+/// Resolves the types of all the models found in `data`.
+///
+/// Composite types referenced by the model fields are looked up in the structs and enums of the
+/// model ABI, so that each field carries its complete type definition.
+pub fn resolve_types(data: &DojoData) -> ResolvedSchema {
+    let mut models = Vec::new();
+
+    for model in data.models.values() {
+        let name = naming::get_name_from_tag(&model.tag);
+
+        let composites: HashMap<&str, &Composite> = model
+            .tokens
+            .structs
+            .iter()
+            .chain(model.tokens.enums.iter())
+            .filter_map(|t| t.to_composite().ok())
+            .map(|c| (c.type_path.as_str(), c))
+            .collect();
+
+        let Some(model_struct) = model
+            .tokens
+            .structs
+            .iter()
+            .filter_map(|t| t.to_composite().ok())
+            .find(|c| c.type_name() == name)
+        else {
+            continue;
+        };
+
+        let fields =
+            resolve_fields(model_struct, &composites, &HashMap::new(), &mut HashSet::new());
+        models.push(ResolvedModel { tag: model.tag.clone(), name, fields });
+    }
+
+    models.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    ResolvedSchema { models }
+}
+
+fn resolve_fields(
+    composite: &Composite,
+    composites: &HashMap<&str, &Composite>,
+    generics: &HashMap<String, ResolvedType>,
+    visiting: &mut HashSet<String>,
+) -> Vec<ResolvedField> {
+    let mut inners = composite.inners.iter().collect::<Vec<_>>();
+    inners.sort_by_key(|inner| inner.index);
+
+    inners
+        .into_iter()
+        .map(|inner| ResolvedField {
+            name: inner.name.clone(),
+            key: matches!(inner.kind, CompositeInnerKind::Key),
+            ty: resolve_token(&inner.token, composites, generics, visiting),
+        })
+        .collect()
+}
+
+// `generics` are the resolved generic arguments of the enclosing composite, by name.
+fn resolve_token<'a>(
+    token: &'a Token,
+    composites: &HashMap<&str, &'a Composite>,
+    generics: &HashMap<String, ResolvedType>,
+    visiting: &mut HashSet<String>,
+) -> ResolvedType {
+    match token {
+        Token::CoreBasic(basic) => ResolvedType::Primitive(basic.type_path.clone()),
+        Token::GenericArg(arg) => {
+            generics.get(arg).cloned().unwrap_or_else(|| ResolvedType::Generic(arg.clone()))
+        }
+        Token::Array(array) => ResolvedType::Array(Box::new(resolve_token(
+            &array.inner,
+            composites,
+            generics,
+            visiting,
+        ))),
+        Token::Tuple(tuple) => ResolvedType::Tuple(
+            tuple.inners.iter().map(|t| resolve_token(t, composites, generics, visiting)).collect(),
+        ),
+        Token::Composite(reference) => {
+            // Prefer the definition found in the ABI, which is fully hydrated.
+            let composite =
+                composites.get(reference.type_path.as_str()).copied().unwrap_or(reference);
+            let type_path = composite.type_path.clone();
+
+            // The generic arguments are resolved in the scope of the enclosing composite, and
+            // substituted in the members of this one.
+            let generic_args = if reference.generic_args.is_empty() {
+                &composite.generic_args
+            } else {
+                &reference.generic_args
+            };
+            let generics = generic_args
+                .iter()
+                .map(|(name, token)| {
+                    (name.clone(), resolve_token(token, composites, generics, visiting))
+                })
+                .collect::<HashMap<_, _>>();
+
+            // Recursive types can't be expanded, they are kept as a reference.
+            if !visiting.insert(type_path.clone()) {
+                return ResolvedType::Recursive(type_path);
+            }
+
+            let ty = match composite.r#type {
+                CompositeType::Enum => ResolvedType::Enum {
+                    type_path: type_path.clone(),
+                    variants: composite
+                        .inners
+                        .iter()
+                        .map(|inner| ResolvedVariant {
+                            name: inner.name.clone(),
+                            ty: (!is_unit(&inner.token)).then(|| {
+                                resolve_token(&inner.token, composites, &generics, visiting)
+                            }),
+                        })
+                        .collect(),
+                },
+                _ => ResolvedType::Struct {
+                    type_path: type_path.clone(),
+                    fields: resolve_fields(composite, composites, &generics, visiting),
+                },
+            };
+
+            visiting.remove(&type_path);
+            ty
+        }
+        token => ResolvedType::Primitive(token.type_path()),
+    }
+}
+
+// Whether the enum variant doesn't carry any data
+fn is_unit(token: &Token) -> bool {
+    match token {
+        Token::CoreBasic(basic) => basic.type_path == "()",
+        Token::Tuple(tuple) => tuple.inners.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cainome::parser::tokens::{CompositeInner, CoreBasic, Tuple};
+    use cainome::parser::TokenizedAbi;
+
+    use super::*;
+    use crate::{DojoModel, DojoWorld};
+
+    fn basic(type_path: &str) -> Token {
+        Token::CoreBasic(CoreBasic { type_path: type_path.to_string() })
+    }
+
+    fn composite(type_path: &str, inners: Vec<(&str, CompositeInnerKind, Token)>) -> Composite {
+        Composite {
+            type_path: type_path.to_string(),
+            r#type: CompositeType::Struct,
+            generic_args: vec![],
+            inners: inners
+                .into_iter()
+                .enumerate()
+                .map(|(index, (name, kind, token))| CompositeInner {
+                    index,
+                    name: name.to_string(),
+                    kind,
+                    token,
+                })
+                .collect(),
+            is_event: false,
+            alias: None,
+        }
+    }
+
+    #[test]
+    fn resolve_models_fields_in_order() {
+        let vec2 = composite(
+            "dojo_examples::models::Vec2",
+            vec![
+                ("x", CompositeInnerKind::Data, basic("core::integer::u32")),
+                ("y", CompositeInnerKind::Data, basic("core::integer::u32")),
+            ],
+        );
+        let position = composite(
+            "dojo_examples::models::Position",
+            vec![
+                (
+                    "player",
+                    CompositeInnerKind::Key,
+                    basic("core::starknet::contract_address::ContractAddress"),
+                ),
+                ("vec", CompositeInnerKind::Data, Token::Composite(vec2.clone())),
+            ],
+        );
+
+        let tag = "dojo_examples-Position".to_string();
+        let model = DojoModel {
+            tag: tag.clone(),
+            tokens: TokenizedAbi {
+                structs: vec![Token::Composite(vec2), Token::Composite(position)],
+                ..Default::default()
+            },
+        };
+
+        let data = DojoData {
+            world: DojoWorld { name: "dojo_examples".to_string() },
+            models: HashMap::from([(tag.clone(), model)]),
+            contracts: HashMap::new(),
+        };
+
+        let schema = resolve_types(&data);
+        assert_eq!(schema.models.len(), 1);
+
+        let model = schema.model(&tag).unwrap();
+        assert_eq!(model.name, "Position");
+        assert_eq!(
+            model.fields,
+            vec![
+                ResolvedField {
+                    name: "player".to_string(),
+                    key: true,
+                    ty: ResolvedType::Primitive(
+                        "core::starknet::contract_address::ContractAddress".to_string()
+                    ),
+                },
+                ResolvedField {
+                    name: "vec".to_string(),
+                    key: false,
+                    ty: ResolvedType::Struct {
+                        type_path: "dojo_examples::models::Vec2".to_string(),
+                        fields: vec![
+                            ResolvedField {
+                                name: "x".to_string(),
+                                key: false,
+                                ty: ResolvedType::Primitive("core::integer::u32".to_string()),
+                            },
+                            ResolvedField {
+                                name: "y".to_string(),
+                                key: false,
+                                ty: ResolvedType::Primitive("core::integer::u32".to_string()),
+                            },
+                        ],
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_generic_args() {
+        let option = Composite {
+            r#type: CompositeType::Enum,
+            generic_args: vec![("T".to_string(), basic("core::integer::u32"))],
+            ..composite(
+                "core::option::Option::<core::integer::u32>",
+                vec![
+                    ("Some", CompositeInnerKind::Data, Token::GenericArg("T".to_string())),
+                    ("None", CompositeInnerKind::Data, basic("()")),
+                ],
+            )
+        };
+        let stats = composite(
+            "dojo_examples::models::Stats",
+            vec![
+                (
+                    "player",
+                    CompositeInnerKind::Key,
+                    basic("core::starknet::contract_address::ContractAddress"),
+                ),
+                ("best", CompositeInnerKind::Data, Token::Composite(option.clone())),
+            ],
+        );
+
+        let tag = "dojo_examples-Stats".to_string();
+        let model = DojoModel {
+            tag: tag.clone(),
+            tokens: TokenizedAbi {
+                structs: vec![Token::Composite(stats)],
+                enums: vec![Token::Composite(option)],
+                ..Default::default()
+            },
+        };
+
+        let data = DojoData {
+            world: DojoWorld { name: "dojo_examples".to_string() },
+            models: HashMap::from([(tag.clone(), model)]),
+            contracts: HashMap::new(),
+        };
+
+        let schema = resolve_types(&data);
+        let model = schema.model(&tag).unwrap();
+        assert_eq!(
+            model.fields[1].ty,
+            ResolvedType::Enum {
+                type_path: "core::option::Option::<core::integer::u32>".to_string(),
+                variants: vec![
+                    ResolvedVariant {
+                        name: "Some".to_string(),
+                        ty: Some(ResolvedType::Primitive("core::integer::u32".to_string())),
+                    },
+                    ResolvedVariant { name: "None".to_string(), ty: None },
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_enum_variants_and_recursive_types() {
+        let direction = Composite {
+            r#type: CompositeType::Enum,
+            ..composite(
+                "dojo_examples::models::Direction",
+                vec![
+                    ("None", CompositeInnerKind::Data, basic("()")),
+                    (
+                        "Left",
+                        CompositeInnerKind::Data,
+                        Token::Tuple(Tuple { type_path: "()".to_string(), inners: vec![] }),
+                    ),
+                    ("Steps", CompositeInnerKind::Data, basic("core::integer::u8")),
+                ],
+            )
+        };
+        // A node referencing its own type, as cainome leaves it unhydrated.
+        let node_ref = composite("dojo_examples::models::Node", vec![]);
+        let node = composite(
+            "dojo_examples::models::Node",
+            vec![
+                ("direction", CompositeInnerKind::Data, Token::Composite(direction.clone())),
+                ("next", CompositeInnerKind::Data, Token::Composite(node_ref)),
+            ],
+        );
+        let moves = composite(
+            "dojo_examples::models::Moves",
+            vec![
+                (
+                    "player",
+                    CompositeInnerKind::Key,
+                    basic("core::starknet::contract_address::ContractAddress"),
+                ),
+                ("head", CompositeInnerKind::Data, Token::Composite(node.clone())),
+            ],
+        );
+
+        let tag = "dojo_examples-Moves".to_string();
+        let model = DojoModel {
+            tag: tag.clone(),
+            tokens: TokenizedAbi {
+                structs: vec![Token::Composite(node), Token::Composite(moves)],
+                enums: vec![Token::Composite(direction)],
+                ..Default::default()
+            },
+        };
+
+        let data = DojoData {
+            world: DojoWorld { name: "dojo_examples".to_string() },
+            models: HashMap::from([(tag.clone(), model)]),
+            contracts: HashMap::new(),
+        };
+
+        let schema = resolve_types(&data);
+        let model = schema.model(&tag).unwrap();
+        assert_eq!(
+            model.fields[1].ty,
+            ResolvedType::Struct {
+                type_path: "dojo_examples::models::Node".to_string(),
+                fields: vec![
+                    ResolvedField {
+                        name: "direction".to_string(),
+                        key: false,
+                        ty: ResolvedType::Enum {
+                            type_path: "dojo_examples::models::Direction".to_string(),
+                            variants: vec![
+                                ResolvedVariant { name: "None".to_string(), ty: None },
+                                ResolvedVariant { name: "Left".to_string(), ty: None },
+                                ResolvedVariant {
+                                    name: "Steps".to_string(),
+                                    ty: Some(ResolvedType::Primitive(
+                                        "core::integer::u8".to_string()
+                                    )),
+                                },
+                            ],
+                        },
+                    },
+                    ResolvedField {
+                        name: "next".to_string(),
+                        key: false,
+                        ty: ResolvedType::Recursive("dojo_examples::models::Node".to_string()),
+                    },
+                ],
+            }
+        );
+    }
+}
@@ -1,4 +1,4 @@
-// Generated by dojo-bindgen on Tue, 28 May 2024 15:30:47 +0000. Do not modify this file manually.
+// Generated by dojo-bindgen. Do not modify this file manually.
 import { Account } from "starknet";
 import {
     Clause,
@@ -241,6 +241,7 @@ pub fn cfg() -> CfgEnv {
         validate_max_n_steps: 1_000_000,
         invoke_tx_max_n_steps: 1_000_000,
         chain_id: ChainId::parse("KATANA").unwrap(),
+        block_budget: Default::default(),
     }
 }
 
@@ -2,12 +2,16 @@ pub const MAX_RECURSION_DEPTH: usize = 1000;
 
 pub const DEFAULT_INVOCATION_MAX_STEPS: u32 = 10_000_000;
 pub const DEFAULT_VALIDATION_MAX_STEPS: u32 = 1_000_000;
+pub const DEFAULT_BLOCK_MAX_STEPS: u64 = u64::MAX;
+pub const DEFAULT_BLOCK_MAX_GAS: u64 = u64::MAX;
 
 #[derive(Debug, Clone)]
 pub struct ExecutionConfig {
     pub invocation_max_steps: u32,
     pub validation_max_steps: u32,
     pub max_recursion_depth: usize,
+    pub block_max_steps: u64,
+    pub block_max_gas: u64,
 }
 
 impl std::default::Default for ExecutionConfig {
@@ -16,6 +20,8 @@ impl std::default::Default for ExecutionConfig {
             max_recursion_depth: MAX_RECURSION_DEPTH,
             invocation_max_steps: DEFAULT_INVOCATION_MAX_STEPS,
             validation_max_steps: DEFAULT_VALIDATION_MAX_STEPS,
+            block_max_steps: DEFAULT_BLOCK_MAX_STEPS,
+            block_max_gas: DEFAULT_BLOCK_MAX_GAS,
         }
     }
 }
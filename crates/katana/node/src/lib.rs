@@ -30,7 +30,7 @@ use katana_pipeline::{stage, Pipeline};
 use katana_pool::ordering::FiFo;
 use katana_pool::validation::stateful::TxValidator;
 use katana_pool::TxPool;
-use katana_primitives::env::{CfgEnv, FeeTokenAddressses};
+use katana_primitives::env::{BlockResourceBudget, CfgEnv, FeeTokenAddressses};
 use katana_rpc::dev::DevApi;
 use katana_rpc::metrics::RpcServerMetrics;
 use katana_rpc::saya::SayaApi;
@@ -167,6 +167,11 @@ pub async fn build(mut config: Config) -> Result<Node> {
             eth: config.chain.fee_contracts.eth,
             strk: config.chain.fee_contracts.strk,
         },
+        block_budget: BlockResourceBudget::new(
+            config.execution.block_max_steps,
+            config.execution.block_max_gas,
+            config.execution.invocation_max_steps,
+        ),
     };
 
     let simulation_flags = SimulationFlag {
@@ -30,6 +30,33 @@ pub struct CfgEnv {
     pub validate_max_n_steps: u32,
     /// The maximum recursion depth allowed.
     pub max_recursion_depth: usize,
+    /// The resources that can be consumed by all the transactions of a block.
+    pub block_budget: BlockResourceBudget,
+}
+
+/// The resources budget of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockResourceBudget {
+    /// The maximum number of Cairo steps.
+    pub max_steps: u64,
+    /// The maximum amount of L1 gas.
+    pub max_gas: u64,
+}
+
+impl BlockResourceBudget {
+    /// Creates a budget of `max_steps` and `max_gas`. The steps are raised to
+    /// `invoke_tx_max_n_steps` if lower, as a block must at least fit a transaction using all of
+    /// its steps.
+    pub fn new(max_steps: u64, max_gas: u64, invoke_tx_max_n_steps: u32) -> Self {
+        Self { max_steps: max_steps.max(invoke_tx_max_n_steps.into()), max_gas }
+    }
+}
+
+impl Default for BlockResourceBudget {
+    /// Blocks aren't limited by default.
+    fn default() -> Self {
+        Self { max_steps: u64::MAX, max_gas: u64::MAX }
+    }
 }
 
 /// The contract addresses of the tokens used for the fees.
@@ -40,3 +67,62 @@ pub struct FeeTokenAddressses {
     /// The contract address of the `ETH` token.
     pub eth: ContractAddress,
 }
+
+/// The tokens that can be used for paying the transaction fees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeTokenKind {
+    Eth,
+    Strk,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("no fee token configured")]
+pub struct MissingFeeTokenError;
+
+impl CfgEnv {
+    /// Returns the token used by default for paying the transaction fees.
+    ///
+    /// `STRK` is the default whenever the chain has a `STRK` fee token, as it is used by the
+    /// current (V3) transactions. Otherwise, only the legacy `ETH` fee token is available.
+    pub fn default_fee_token(&self) -> Result<FeeTokenKind, MissingFeeTokenError> {
+        let FeeTokenAddressses { strk, eth } = self.fee_token_addresses;
+
+        if strk != ContractAddress::default() {
+            Ok(FeeTokenKind::Strk)
+        } else if eth != ContractAddress::default() {
+            Ok(FeeTokenKind::Eth)
+        } else {
+            Err(MissingFeeTokenError)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn cfg(eth: ContractAddress, strk: ContractAddress) -> CfgEnv {
+        CfgEnv { fee_token_addresses: FeeTokenAddressses { eth, strk }, ..Default::default() }
+    }
+
+    #[test]
+    fn default_fee_token() {
+        let eth = ContractAddress::from(felt!("0x1"));
+        let strk = ContractAddress::from(felt!("0x2"));
+
+        assert_eq!(cfg(eth, strk).default_fee_token().unwrap(), FeeTokenKind::Strk);
+        assert_eq!(cfg(eth, Default::default()).default_fee_token().unwrap(), FeeTokenKind::Eth);
+        assert!(cfg(Default::default(), Default::default()).default_fee_token().is_err());
+    }
+
+    #[test]
+    fn block_budget_fits_a_transaction() {
+        let budget = BlockResourceBudget::new(1_000, 5_000, 10_000_000);
+        assert_eq!(budget, BlockResourceBudget { max_steps: 10_000_000, max_gas: 5_000 });
+
+        let budget = BlockResourceBudget::new(50_000_000, 5_000, 10_000_000);
+        assert_eq!(budget, BlockResourceBudget { max_steps: 50_000_000, max_gas: 5_000 });
+    }
+}
@@ -0,0 +1,81 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use katana_db::abstraction::{Database, DbTxMut};
+use katana_db::init_ephemeral_db;
+use katana_db::mdbx::DbEnv;
+use katana_db::tables::BlockHashes;
+use katana_primitives::Felt;
+
+const ROWS: u64 = 10_000;
+
+fn entries() -> impl Iterator<Item = (u64, Felt)> {
+    (0..ROWS).map(|key| (key, Felt::from(key * 2)))
+}
+
+fn setup() -> DbEnv {
+    init_ephemeral_db().expect("failed to create ephemeral database")
+}
+
+fn insert_entries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert sequential keys");
+
+    group.bench_function("put", |b| {
+        b.iter_batched(
+            setup,
+            |env| {
+                let tx = env.tx_mut().unwrap();
+                for (key, value) in entries() {
+                    tx.put::<BlockHashes>(key, value).unwrap();
+                }
+                tx.commit().unwrap();
+                env
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.bench_function("put_many", |b| {
+        b.iter_batched(
+            setup,
+            |env| {
+                let tx = env.tx_mut().unwrap();
+                tx.put_many::<BlockHashes>(entries()).unwrap();
+                tx.commit().unwrap();
+                env
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.bench_function("append", |b| {
+        b.iter_batched(
+            setup,
+            |env| {
+                let tx = env.tx_mut().unwrap();
+                for (key, value) in entries() {
+                    tx.append::<BlockHashes>(key, value).unwrap();
+                }
+                tx.commit().unwrap();
+                env
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.bench_function("append_many", |b| {
+        b.iter_batched(
+            setup,
+            |env| {
+                let tx = env.tx_mut().unwrap();
+                tx.append_many::<BlockHashes>(entries()).unwrap();
+                tx.commit().unwrap();
+                env
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(write, insert_entries);
+criterion_main!(write);
@@ -31,11 +31,27 @@ pub trait DbCursor<T: Table>: Sized {
     /// key is greater than or equal to `key`.
     fn seek(&mut self, key: T::Key) -> Result<Option<KeyValue<T>>, DatabaseError>;
 
+    /// Search for exactly `key` in a table, returning and positioning the cursor at the item if
+    /// found. Returns `None` if there is no item with that key.
+    fn seek_exact(&mut self, key: T::Key) -> Result<Option<KeyValue<T>>, DatabaseError>;
+
     /// Creates a walker to iterate over the table items.
     ///
     /// If `start_key` is `None`, the walker will start at the first item of the table. Otherwise,
     /// it will start at the first item whose key is greater than or equal to `start_key`.
+    ///
+    /// For [`DupSort`] tables, every duplicate value is yielded as its own item, so the walker
+    /// yields all the (key, value) pairs ordered by key, and then by value for the same key.
     fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError>;
+
+    /// Creates a walker to iterate over the table items in descending key order.
+    ///
+    /// If `start_key` is `None`, the walker will start at the last item of the table. Otherwise,
+    /// it will start at the last item whose key is less than or equal to `start_key`.
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError>;
 }
 
 /// Cursor trait for read-write operations.
@@ -53,6 +69,9 @@ pub trait DbCursorMut<T: Table>: DbCursor<T> {
     fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError>;
 
     /// Deletes the current key/value pair.
+    ///
+    /// For DUPSORT tables, only the current duplicate is deleted. The cursor stays usable for
+    /// iteration, so entries can be deleted while walking the table.
     fn delete_current(&mut self) -> Result<(), DatabaseError>;
 }
 
@@ -66,6 +85,10 @@ pub trait DbDupSortCursor<T: DupSort>: DbCursor<T> {
     /// only the `value`.
     fn next_dup_val(&mut self) -> Result<Option<T::Value>, DatabaseError>;
 
+    /// Positions the cursor at previous data item of current key, returning the previous
+    /// `key-value` pair of a DUPSORT table.
+    fn prev_dup(&mut self) -> Result<Option<KeyValue<T>>, DatabaseError>;
+
     /// Returns the next key/value pair skipping the duplicates.
     fn next_no_dup(&mut self) -> Result<Option<KeyValue<T>>, DatabaseError>;
 
@@ -146,6 +169,50 @@ where
     }
 }
 
+/// Provides a reverse iterator to a `Cursor` when handling `Table`.
+///
+/// Similar to [`Walker`], but yields the items in descending key order.
+#[derive(Debug)]
+pub struct ReverseWalker<'c, T: Table, C: DbCursor<T>> {
+    /// Cursor to be used to walk through the table.
+    cursor: &'c mut C,
+    /// Initial position of the walker. The value (key/value pair) where to start the walk.
+    start: IterPairResult<T>,
+}
+
+impl<'c, T, C> ReverseWalker<'c, T, C>
+where
+    T: Table,
+    C: DbCursor<T>,
+{
+    /// Create a new [`ReverseWalker`] from a [`Cursor`] and a [`IterPairResult`].
+    pub fn new(cursor: &'c mut C, start: IterPairResult<T>) -> Self {
+        Self { cursor, start }
+    }
+}
+
+impl<T, C> ReverseWalker<'_, T, C>
+where
+    T: Table,
+    C: DbCursorMut<T>,
+{
+    /// Delete the `key/value` pair item at the current position of the walker.
+    pub fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        self.cursor.delete_current()
+    }
+}
+
+impl<T, C> std::iter::Iterator for ReverseWalker<'_, T, C>
+where
+    T: Table,
+    C: DbCursor<T>,
+{
+    type Item = Result<KeyValue<T>, DatabaseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let value @ Some(_) = self.start.take() { value } else { self.cursor.prev().transpose() }
+    }
+}
+
 /// A cursor iterator for `DUPSORT` table.
 ///
 /// Similar to [`Walker`], but for `DUPSORT` table.
@@ -23,13 +23,16 @@ pub trait DbTx {
     fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError>;
 
     /// Returns number of entries in the table.
+    ///
+    /// For dup-sort tables, every duplicate counts as an entry, ie. this is the number of
+    /// key/value pairs rather than the number of distinct keys.
     fn entries<T: Table>(&self) -> Result<usize, DatabaseError>;
 
     /// Commits the transaction.
     fn commit(self) -> Result<bool, DatabaseError>;
 
-    /// Aborts the transaction.
-    fn abort(self);
+    /// Aborts the transaction, discarding all the changes made by it.
+    fn abort(self) -> Result<(), DatabaseError>;
 }
 
 /// Trait for read-write transaction type.
@@ -64,5 +67,8 @@ pub trait DbTxMut: DbTx {
     -> Result<bool, DatabaseError>;
 
     /// Clears all entries in the given database. This will empty the database.
+    ///
+    /// The table itself is kept and can still be written to afterwards. Clearing an empty table
+    /// is a no-op.
     fn clear<T: Table>(&self) -> Result<(), DatabaseError>;
 }
@@ -1,5 +1,7 @@
 #[cfg(feature = "postcard")]
 pub mod postcard;
+#[cfg(feature = "zstd")]
+pub mod zstd;
 
 use katana_primitives::block::FinalityStatus;
 use katana_primitives::class::FlattenedSierraClass;
@@ -11,6 +13,15 @@ use crate::error::CodecError;
 /// A trait for encoding the key of a table.
 pub trait Encode {
     type Encoded: AsRef<[u8]> + Into<Vec<u8>>;
+    /// The length of the encoded key in bytes, if all keys are encoded to the same length.
+    const ENCODED_LEN: Option<usize> = None;
+    /// Whether the key is encoded as a native-endian unsigned integer, in which case the table is
+    /// created with `MDBX_INTEGERKEY` and its keys are ordered by MDBX's integer comparator.
+    ///
+    /// The database files are thus tied to the byte order of the machine, which MDBX already
+    /// requires of its own pages, e.g. a database can't be moved between x86 and big-endian
+    /// hosts.
+    const INTEGER_KEY: bool = false;
     fn encode(self) -> Self::Encoded;
 }
 
@@ -21,6 +32,9 @@ pub trait Decode: Sized {
 /// A trait for compressing data that are stored in the db.
 pub trait Compress {
     type Compressed: AsRef<[u8]>;
+    /// The length of the compressed value in bytes, if all values are compressed to the same
+    /// length.
+    const COMPRESSED_LEN: Option<usize> = None;
     fn compress(self) -> Self::Compressed;
 }
 
@@ -29,21 +43,30 @@ pub trait Decompress: Sized {
     fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError>;
 }
 
+// Unsigned integers are encoded in native endianness, as expected by `MDBX_INTEGERKEY`.
 macro_rules! impl_encode_and_decode_for_uints {
     ($($ty:ty),*) => {
         $(
             impl Encode for $ty {
                 type Encoded = [u8; std::mem::size_of::<$ty>()];
+                const ENCODED_LEN: Option<usize> = Some(std::mem::size_of::<$ty>());
+                const INTEGER_KEY: bool = true;
                 fn encode(self) -> Self::Encoded {
-                    self.to_be_bytes()
+                    self.to_ne_bytes()
                 }
             }
 
             impl Decode for $ty {
                 fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
-                    let mut buf = [0u8; std::mem::size_of::<$ty>()];
-                    buf.copy_from_slice(bytes.as_ref());
-                    Ok(Self::from_be_bytes(buf))
+                    let bytes = bytes.as_ref();
+                    let buf = bytes.try_into().map_err(|_| {
+                        CodecError::Decode(format!(
+                            "expected {} bytes, got {}",
+                            std::mem::size_of::<$ty>(),
+                            bytes.len()
+                        ))
+                    })?;
+                    Ok(Self::from_ne_bytes(buf))
                 }
             }
         )*
@@ -55,6 +78,7 @@ macro_rules! impl_encode_and_decode_for_felts {
         $(
             impl Encode for $ty {
                 type Encoded = [u8; 32];
+                const ENCODED_LEN: Option<usize> = Some(32);
                 fn encode(self) -> Self::Encoded {
                     self.to_bytes_be()
                 }
@@ -87,6 +111,7 @@ impl Decompress for FlattenedSierraClass {
 
 impl Compress for FinalityStatus {
     type Compressed = [u8; 1];
+    const COMPRESSED_LEN: Option<usize> = Some(1);
     fn compress(self) -> Self::Compressed {
         [self as u8]
     }
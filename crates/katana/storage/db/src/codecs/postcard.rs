@@ -32,6 +32,7 @@ macro_rules! impl_compress_and_decompress_for_table_values {
 }
 
 impl_compress_and_decompress_for_table_values!(
+    u32,
     u64,
     Tx,
     TxExecInfo,
@@ -0,0 +1,118 @@
+use super::{Compress, Decompress};
+use crate::error::CodecError;
+
+/// Default compression level of [`Zstd`].
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// A table value compressed with zstd at the given `LEVEL`.
+///
+/// The value is first serialized with its own [`Compress`] implementation, and the resulting bytes
+/// are compressed before being stored. They are inflated again on read, before being deserialized
+/// with the value's [`Decompress`] implementation. This is worth it for large values that
+/// compress well, e.g. headers and receipts, with the level chosen per table through the value
+/// type of the table, e.g. `Zstd<Receipt, 9>`.
+///
+/// Only values are compressed. Keys are still encoded with their [`Encode`](super::Encode)
+/// implementation, so the ordering of the keys and the cursor seeks are unaffected.
+///
+/// # Migration
+///
+/// Wrapping the value type of an existing table changes the format of its values on disk, and
+/// values written before the change can't be read with the new type. Existing tables must be
+/// rewritten, by reading their values with the previous type and writing them back wrapped in
+/// [`Zstd`]. Changing `LEVEL` doesn't require a migration, as zstd frames can be inflated
+/// regardless of the level they were compressed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zstd<T, const LEVEL: i32 = DEFAULT_ZSTD_LEVEL>(pub T);
+
+impl<T: Compress, const LEVEL: i32> Compress for Zstd<T, LEVEL> {
+    type Compressed = Vec<u8>;
+    fn compress(self) -> Self::Compressed {
+        zstd::bulk::compress(self.0.compress().as_ref(), LEVEL).unwrap()
+    }
+}
+
+impl<T: Decompress, const LEVEL: i32> Decompress for Zstd<T, LEVEL> {
+    fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        let bytes =
+            zstd::decode_all(bytes.as_ref()).map_err(|e| CodecError::Decompress(e.to_string()))?;
+        T::decompress(bytes).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::block::{BlockNumber, Header};
+    use katana_primitives::{address, Felt};
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::abstraction::{Database, DbTx, DbTxMut};
+    use crate::mdbx::{DbEnv, DbEnvKind};
+    use crate::tables::{Headers, Table};
+
+    #[test]
+    fn compress_and_decompress_header() {
+        let header = Header {
+            number: 1337,
+            parent_hash: felt!("0x1337"),
+            state_root: felt!("0x1234"),
+            timestamp: 1_700_000_000,
+            transaction_count: 100,
+            ..Default::default()
+        };
+
+        let compressed = Zstd::<Header, 19>(header.clone()).compress();
+        let inflated = zstd::decode_all(compressed.as_slice()).unwrap();
+        assert_eq!(inflated, header.clone().compress());
+
+        let decompressed = Zstd::<Header, 19>::decompress(compressed).unwrap();
+        assert_eq!(decompressed.0, header);
+    }
+
+    #[test]
+    fn store_header_and_reopen() {
+        // Shares the `Headers` table, but with its values compressed.
+        #[derive(Debug)]
+        struct CompressedHeaders;
+
+        impl Table for CompressedHeaders {
+            const NAME: &'static str = Headers::NAME;
+            type Key = BlockNumber;
+            type Value = Zstd<Header, 19>;
+        }
+
+        let header = Header {
+            number: u64::MAX,
+            parent_hash: Felt::MAX,
+            state_diff_commitment: Felt::MAX,
+            transactions_commitment: Felt::MAX,
+            receipts_commitment: Felt::MAX,
+            events_commitment: Felt::MAX,
+            state_root: Felt::MAX,
+            timestamp: u64::MAX,
+            transaction_count: u32::MAX,
+            events_count: u32::MAX,
+            sequencer_address: address!("0x1337"),
+            ..Default::default()
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).expect("failed to open db");
+        env.create_tables().unwrap();
+
+        let tx = env.tx_mut().unwrap();
+        tx.put::<CompressedHeaders>(1, Zstd(header.clone())).unwrap();
+        tx.commit().unwrap();
+        // MDBX doesn't allow opening the same environment twice within a process.
+        drop(env);
+
+        let env = DbEnv::open(dir.path(), DbEnvKind::RO).expect("failed to open db");
+        let tx = env.tx().unwrap();
+        assert_eq!(tx.get::<CompressedHeaders>(1).unwrap(), Some(Zstd(header.clone())));
+
+        // The value is stored as a zstd frame, not as the serialized header.
+        let stored = tx.get_ref::<CompressedHeaders, _>(1, |bytes| bytes.to_vec()).unwrap();
+        assert_eq!(stored, Some(Zstd::<Header, 19>(header).compress()));
+    }
+}
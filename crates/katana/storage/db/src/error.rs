@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
 pub enum DatabaseError {
     #[error("failed to open db environment: {0}")]
@@ -18,15 +20,30 @@ pub enum DatabaseError {
     #[error("failed to write to db table {table} with key {key:?}: {error}")]
     Write { error: libmdbx::Error, table: &'static str, key: Box<[u8]> },
 
+    #[error("key {key:?} is already indexed in db table {table}")]
+    DuplicateIndexKey { table: &'static str, key: Box<[u8]> },
+
+    #[error("key {key:?} isn't greater than the last key of db table {table}")]
+    KeyMismatch { table: &'static str, key: Box<[u8]> },
+
     #[error("failed to open db: {0}")]
     OpenDb(libmdbx::Error),
 
+    #[error("db table {table} doesn't exist, the tables must be created first")]
+    TableNotInitialized { table: &'static str },
+
+    #[error("db table {0} isn't part of the db schema")]
+    UnknownTable(&'static str),
+
     #[error("failed to retrieve db statistics: {0}")]
     Stat(libmdbx::Error),
 
     #[error("failed to create db cursor: {0}")]
     CreateCursor(libmdbx::Error),
 
+    #[error("db cursor isn't positioned on an entry")]
+    CursorNotPositioned,
+
     #[error("failed to create read-only db transaction: {0}")]
     CreateROTx(libmdbx::Error),
 
@@ -41,6 +58,58 @@ pub enum DatabaseError {
 
     #[error("failed to get db stats: {0}")]
     GetStats(libmdbx::Error),
+
+    #[error("failed to grow db environment: {0}")]
+    Grow(libmdbx::Error),
+
+    #[error("failed to sync db environment: {0}")]
+    Sync(libmdbx::Error),
+
+    #[error("failed to check db reader slots: {0}")]
+    CheckReaders(libmdbx::Error),
+
+    #[error("failed to copy db environment to {}: {reason}", .path.display())]
+    Copy { path: PathBuf, reason: String },
+
+    #[error("failed to dump db table {table}: {reason}")]
+    Dump { table: &'static str, reason: String },
+
+    #[error("failed to load db table {table} at line {line}: {reason}")]
+    Load { table: &'static str, line: usize, reason: String },
+
+    #[error(
+        "invalid db page size of {0} bytes, must be a power of two between 256 and 65536 bytes"
+    )]
+    InvalidPageSize(usize),
+
+    #[error("db page size is {actual} bytes, not {requested}, as it is fixed at creation")]
+    PageSizeMismatch { requested: usize, actual: usize },
+
+    #[error("invalid db geometry: {0}")]
+    InvalidGeometry(String),
+
+    #[error("no db migration from schema version {0}")]
+    MissingMigration(u32),
+
+    #[error("db snapshot at transaction {txn_id} expired, the latest transaction is {latest}")]
+    SnapshotExpired { txn_id: u64, latest: u64 },
+
+    #[error("db environment is in maintenance mode")]
+    Maintenance,
+
+    #[error("db environment is read-only while a read guard is held")]
+    ReadGuarded,
+
+    #[error("read-write transaction exceeded the write timeout and was aborted")]
+    WriteTimeout,
+
+    #[error(
+        "invalid encoding length for db table {table}: expected {expected} bytes, got {actual}"
+    )]
+    Encode { table: &'static str, expected: usize, actual: usize },
+
+    #[error("invalid integer key of {0} bytes, expected 4 or 8 bytes")]
+    IntegerKey(usize),
 }
 
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
@@ -0,0 +1,139 @@
+//! Secondary indexes of tables.
+
+use std::str::FromStr;
+
+use libmdbx::RW;
+
+use crate::abstraction::{DbTx, DbTxMut};
+use crate::codecs::Encode;
+use crate::error::DatabaseError;
+use crate::mdbx::tx::Tx;
+use crate::tables::{Table, TableType, Tables};
+
+/// A secondary index of the primary table `P`, stored in the table `S`.
+///
+/// The index maps a secondary key, extracted from the values of `P`, back to their primary key,
+/// e.g. `BlockNumbers` indexing `Headers` by block hash. Writing through [`Index::insert`] keeps
+/// both tables in sync, as the primary and the index entries are written within the same
+/// transaction.
+///
+/// If `S` isn't a [`TableType::DupSort`] table, a secondary key can only map to a single primary
+/// key, and inserting another primary entry with the same secondary key fails with
+/// [`DatabaseError::DuplicateIndexKey`].
+pub struct Index<P: Table, S: Table<Value = P::Key>> {
+    /// Extracts the secondary key from a value of the primary table.
+    extract: Box<dyn Fn(&P::Value) -> S::Key + Send + Sync>,
+    /// Whether the index table allows several primary keys per secondary key.
+    dup_sort: bool,
+}
+
+impl<P, S> Index<P, S>
+where
+    P: Table,
+    S: Table<Value = P::Key>,
+    P::Key: PartialEq,
+{
+    /// Creates an index whose secondary keys are extracted from the primary values by `extract`.
+    pub fn new(extract: impl Fn(&P::Value) -> S::Key + Send + Sync + 'static) -> Self {
+        let table = Tables::from_str(S::NAME).expect("index table should be part of `Tables`.");
+        Self { extract: Box::new(extract), dup_sort: table.table_type() == TableType::DupSort }
+    }
+
+    /// Inserts the entry into the primary table and its secondary key into the index.
+    ///
+    /// If the primary key already exists, its previous secondary key is removed from the index.
+    /// Nothing is written if the secondary key is already mapped to another primary key in a
+    /// non dup-sort index.
+    pub fn insert(&self, tx: &Tx<RW>, key: P::Key, value: P::Value) -> Result<(), DatabaseError> {
+        let secondary = (self.extract)(&value);
+
+        if !self.dup_sort {
+            if let Some(existing) = tx.get::<S>(secondary.clone())? {
+                if existing != key {
+                    let key = Box::from(secondary.encode().as_ref());
+                    return Err(DatabaseError::DuplicateIndexKey { table: S::NAME, key });
+                }
+            }
+        }
+
+        if let Some(previous) = tx.get::<P>(key.clone())? {
+            let previous = (self.extract)(&previous);
+            let value = if self.dup_sort { Some(key.clone()) } else { None };
+            tx.delete::<S>(previous, value)?;
+        }
+
+        tx.put::<P>(key.clone(), value)?;
+        tx.put::<S>(secondary, key)
+    }
+
+    /// Returns the primary entry whose secondary key is `secondary`, if any. If several entries
+    /// share the secondary key in a dup-sort index, the one with the lowest primary key is
+    /// returned.
+    pub fn lookup_by_secondary<D: DbTx>(
+        &self,
+        tx: &D,
+        secondary: S::Key,
+    ) -> Result<Option<(P::Key, P::Value)>, DatabaseError> {
+        let Some(key) = tx.get::<S>(secondary)? else { return Ok(None) };
+        Ok(tx.get::<P>(key.clone())?.map(|value| (key, value)))
+    }
+}
+
+impl<P: Table, S: Table<Value = P::Key>> std::fmt::Debug for Index<P, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Index")
+            .field("primary", &P::NAME)
+            .field("secondary", &S::NAME)
+            .field("dup_sort", &self.dup_sort)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::block::Header;
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::abstraction::Database;
+    use crate::mdbx::test_utils::create_test_db;
+    use crate::tables::{BlockNumbers, Headers};
+
+    fn header(number: u64) -> Header {
+        Header { number, parent_hash: felt!("0x1337"), ..Default::default() }
+    }
+
+    #[test]
+    fn hash_to_number_index() {
+        let env = create_test_db();
+        let index = Index::<Headers, BlockNumbers>::new(Header::compute_hash);
+
+        env.update(|tx| (0..3u64).try_for_each(|number| index.insert(tx, number, header(number))))
+            .unwrap()
+            .unwrap();
+
+        let tx = env.tx().unwrap();
+        for number in 0..3u64 {
+            let hash = header(number).compute_hash();
+            assert_eq!(tx.get::<BlockNumbers>(hash).unwrap(), Some(number));
+            let entry = index.lookup_by_secondary(&tx, hash).unwrap();
+            assert_eq!(entry, Some((number, header(number))));
+        }
+        assert_eq!(index.lookup_by_secondary(&tx, felt!("0xdead")).unwrap(), None);
+        drop(tx);
+
+        // The same header can't be indexed under two block numbers.
+        let tx = env.tx_mut().unwrap();
+        let result = index.insert(&tx, 3, header(0));
+        assert!(matches!(
+            result,
+            Err(DatabaseError::DuplicateIndexKey { table: "BlockNumbers", .. })
+        ));
+        assert_eq!(tx.get::<Headers>(3).unwrap(), None);
+
+        // Overwriting a primary entry replaces its secondary key.
+        index.insert(&tx, 2, header(4)).unwrap();
+        assert_eq!(tx.get::<BlockNumbers>(header(2).compute_hash()).unwrap(), None);
+        assert_eq!(tx.get::<BlockNumbers>(header(4).compute_hash()).unwrap(), Some(2));
+    }
+}
@@ -10,21 +10,29 @@ use anyhow::{anyhow, Context};
 pub mod abstraction;
 pub mod codecs;
 pub mod error;
+pub mod index;
 pub mod mdbx;
+pub mod migration;
 pub mod models;
 pub mod tables;
 pub mod utils;
 pub mod version;
 
 use mdbx::{DbEnv, DbEnvKind};
+use migration::{migrations, MigrationRunner};
 use utils::is_database_empty;
-use version::{check_db_version, create_db_version_file, DatabaseVersionError, CURRENT_DB_VERSION};
+use version::{
+    check_db_version, create_db_version_file, update_db_version_file, DatabaseVersionError,
+    CURRENT_DB_VERSION,
+};
 
 /// Initialize the database at the given path and returning a handle to the its
 /// environment.
 ///
 /// This will create the default tables, if necessary.
 pub fn init_db<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
+    let mut version = CURRENT_DB_VERSION;
+
     if is_database_empty(path.as_ref()) {
         fs::create_dir_all(&path).with_context(|| {
             format!("Creating database directory at path {}", path.as_ref().display())
@@ -43,12 +51,31 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
                     )
                 })?
             }
+            // Older databases are migrated below.
+            Err(DatabaseVersionError::MismatchVersion { found, .. })
+                if found < CURRENT_DB_VERSION =>
+            {
+                version = found
+            }
             Err(err) => return Err(anyhow!(err)),
         }
     }
 
-    let env = open_db(path)?;
+    let env = open_db(&path)?;
+    // The migrations run before the tables are created, as they may recreate tables whose flags
+    // have changed.
+    MigrationRunner::new(migrations())
+        .with_base_version(version)
+        .run(&env)
+        .context("Migrating database schema")?;
     env.create_tables()?;
+
+    if version != CURRENT_DB_VERSION {
+        update_db_version_file(&path, CURRENT_DB_VERSION).with_context(|| {
+            format!("Updating database version file at path {}", path.as_ref().display())
+        })?;
+    }
+
     Ok(env)
 }
 
@@ -87,7 +114,7 @@ mod tests {
     #[test]
     fn initialize_db_in_empty_dir() {
         let path = tempfile::tempdir().unwrap();
-        init_db(path.path()).unwrap();
+        let env = init_db(path.path()).unwrap();
 
         let version_file = fs::File::open(default_version_file_path(path.path())).unwrap();
         let actual_version = get_db_version(path.path()).unwrap();
@@ -97,6 +124,7 @@ mod tests {
             "version file should set to read-only"
         );
         assert_eq!(actual_version, CURRENT_DB_VERSION);
+        assert_eq!(env.schema_version().unwrap(), CURRENT_DB_VERSION);
     }
 
     #[test]
@@ -0,0 +1,181 @@
+//! Feed of the committed read-write transactions.
+
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use parking_lot::{Condvar, Mutex, RwLock};
+
+use crate::tables::{Table, Tables, NUM_TABLES};
+
+/// The default number of events buffered for each subscriber.
+pub const DEFAULT_CHANGEFEED_CAPACITY: usize = 1024;
+
+/// Event emitted for every committed read-write transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitEvent {
+    /// The id of the committed transaction.
+    pub txn_id: u64,
+    /// The names of the tables mutated by the transaction.
+    pub tables: Vec<&'static str>,
+}
+
+/// Dispatches the commit events to all the active subscribers.
+#[derive(Debug, Default)]
+pub(crate) struct Changefeed {
+    subscribers: Mutex<Vec<Weak<Subscription>>>,
+}
+
+impl Changefeed {
+    pub(crate) fn subscribe(&self, capacity: usize) -> CommitReceiver {
+        let subscription = Arc::new(Subscription {
+            capacity: capacity.max(1),
+            queue: Mutex::new(VecDeque::new()),
+            available: Condvar::new(),
+            lagged: AtomicU64::new(0),
+        });
+
+        self.subscribers.lock().push(Arc::downgrade(&subscription));
+        CommitReceiver { subscription }
+    }
+
+    /// Returns `true` if there is at least one subscriber.
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.subscribers.lock().iter().any(|s| s.strong_count() > 0)
+    }
+
+    /// Sends the event to all subscribers. Never blocks on slow subscribers.
+    pub(crate) fn publish(&self, event: CommitEvent) {
+        self.subscribers.lock().retain(|subscriber| match subscriber.upgrade() {
+            Some(subscriber) => {
+                subscriber.push(event.clone());
+                true
+            }
+            // The receiver has been dropped.
+            None => false,
+        });
+    }
+}
+
+/// The tables mutated by a read-write transaction, shared with the cursors it creates.
+#[derive(Debug, Default)]
+pub(crate) struct MutatedTables(RwLock<[bool; NUM_TABLES]>);
+
+impl MutatedTables {
+    pub(crate) fn mark<T: Table>(&self) {
+        let table = Tables::from_str(T::NAME).expect("requested table should be part of `Tables`.");
+        self.0.write()[table as usize] = true;
+    }
+
+    /// Returns the names of the mutated tables, in the order of [`Tables::ALL`].
+    pub(crate) fn names(&self) -> Vec<&'static str> {
+        let mutated = self.0.read();
+        Tables::ALL
+            .iter()
+            .filter(|table| mutated[**table as usize])
+            .map(|table| table.name())
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct Subscription {
+    capacity: usize,
+    queue: Mutex<VecDeque<CommitEvent>>,
+    available: Condvar,
+    lagged: AtomicU64,
+}
+
+impl Subscription {
+    fn push(&self, event: CommitEvent) {
+        let mut queue = self.queue.lock();
+
+        // Drop the oldest event instead of blocking the committer.
+        if queue.len() == self.capacity {
+            queue.pop_front();
+            self.lagged.fetch_add(1, Ordering::Relaxed);
+        }
+
+        queue.push_back(event);
+        self.available.notify_one();
+    }
+}
+
+/// Receiving half of a changefeed subscription, created with
+/// [`DbEnv::subscribe`](super::DbEnv::subscribe).
+///
+/// The events are buffered up to a fixed capacity. If the receiver doesn't keep up, the oldest
+/// events are dropped and counted in [`CommitReceiver::lagged`].
+#[derive(Debug)]
+pub struct CommitReceiver {
+    subscription: Arc<Subscription>,
+}
+
+impl CommitReceiver {
+    /// Returns the next event, if any, without blocking.
+    pub fn try_recv(&self) -> Option<CommitEvent> {
+        self.subscription.queue.lock().pop_front()
+    }
+
+    /// Blocks until an event is available.
+    pub fn recv(&self) -> CommitEvent {
+        let mut queue = self.subscription.queue.lock();
+        loop {
+            if let Some(event) = queue.pop_front() {
+                return event;
+            }
+            self.subscription.available.wait(&mut queue);
+        }
+    }
+
+    /// Blocks until an event is available or the `timeout` expires.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<CommitEvent> {
+        let mut queue = self.subscription.queue.lock();
+        if queue.is_empty() {
+            self.subscription.available.wait_for(&mut queue, timeout);
+        }
+        queue.pop_front()
+    }
+
+    /// Returns the number of events dropped because the receiver was lagging behind.
+    pub fn lagged(&self) -> u64 {
+        self.subscription.lagged.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(txn_id: u64) -> CommitEvent {
+        CommitEvent { txn_id, tables: vec!["Headers"] }
+    }
+
+    #[test]
+    fn slow_subscriber_drops_oldest_events() {
+        let feed = Changefeed::default();
+        let receiver = feed.subscribe(2);
+
+        (1..=5).for_each(|id| feed.publish(event(id)));
+
+        assert_eq!(receiver.lagged(), 3);
+        assert_eq!(receiver.try_recv(), Some(event(4)));
+        assert_eq!(receiver.try_recv(), Some(event(5)));
+        assert_eq!(receiver.try_recv(), None);
+    }
+
+    #[test]
+    fn dropped_receivers_are_unsubscribed() {
+        let feed = Changefeed::default();
+        let receiver = feed.subscribe(DEFAULT_CHANGEFEED_CAPACITY);
+        assert!(feed.has_subscribers());
+
+        drop(receiver);
+        assert!(!feed.has_subscribers());
+
+        feed.publish(event(1));
+        assert!(feed.subscribers.lock().is_empty());
+    }
+}
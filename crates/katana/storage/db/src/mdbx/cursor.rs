@@ -1,17 +1,24 @@
 //! Cursor wrapper for libmdbx-sys.
 
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::marker::PhantomData;
+use std::ops::{Bound, RangeInclusive};
+use std::sync::Arc;
 
-use libmdbx::{self, TransactionKind, WriteFlags, RW};
+use libmdbx::{self, ffi, TransactionKind, WriteFlags, RW};
 
+use super::changefeed::MutatedTables;
+use super::instrument::{self, Operation};
 use crate::abstraction::{
-    DbCursor, DbCursorMut, DbDupSortCursor, DbDupSortCursorMut, DupWalker, Walker,
+    DbCursor, DbCursorMut, DbDupSortCursor, DbDupSortCursorMut, DupWalker, ReverseWalker, Walker,
 };
-use crate::codecs::{Compress, Encode};
+use crate::codecs::Encode;
 use crate::error::DatabaseError;
 use crate::tables::{DupSort, Table};
-use crate::utils::{decode_one, decode_value, decoder, KeyValue};
+use crate::utils::{
+    compare_keys, compress_value, decode_one, decode_value, decoder, encode_key, KeyValue,
+};
 
 /// Takes key/value pair from the database and decodes it appropriately.
 macro_rules! decode {
@@ -21,17 +28,200 @@ macro_rules! decode {
 }
 
 /// Cursor for navigating the items within a database.
+///
+/// MDBX keeps the cursors of a transaction in sync with the writes made through that transaction,
+/// e.g. with `DbTxMut::put`, so an open cursor sees them without being repositioned.
 #[derive(Debug)]
 pub struct Cursor<K: TransactionKind, T: Table> {
     /// Inner `libmdbx` cursor.
     inner: libmdbx::Cursor<K>,
+    /// The tables mutated by the transaction of the cursor, if they are tracked.
+    mutated: Option<Arc<MutatedTables>>,
     /// Phantom data to enforce encoding/decoding.
     _dbi: PhantomData<T>,
 }
 
 impl<K: TransactionKind, T: Table> Cursor<K, T> {
     pub(crate) fn new(inner: libmdbx::Cursor<K>) -> Self {
-        Self { inner, _dbi: PhantomData }
+        Self { inner, mutated: None, _dbi: PhantomData }
+    }
+
+    /// Marks table `T` as mutated in `mutated` whenever the cursor writes to it.
+    pub(crate) fn with_mutated(mut self, mutated: Option<Arc<MutatedTables>>) -> Self {
+        self.mutated = mutated;
+        self
+    }
+
+    fn mark_mutated(&self) {
+        if let Some(mutated) = &self.mutated {
+            mutated.mark::<T>();
+        }
+    }
+
+    /// Walks over all the entries of the table, starting from the first one, and yields their
+    /// keys and values as they are stored in the database, ie. without decoding them.
+    ///
+    /// This is meant for tools which don't know the types of the table, e.g. for dumping it.
+    pub fn walk_raw(&mut self) -> impl Iterator<Item = Result<RawKeyValue<'_>, DatabaseError>> {
+        let mut started = false;
+        std::iter::from_fn(move || {
+            let entry: Result<Option<RawKeyValue<'_>>, _> = if std::mem::replace(&mut started, true)
+            {
+                libmdbx::Cursor::next(&mut self.inner)
+            } else {
+                libmdbx::Cursor::first(&mut self.inner)
+            };
+            entry.map_err(DatabaseError::Read).transpose()
+        })
+    }
+}
+
+/// An undecoded key-value pair, as stored in the database.
+pub type RawKeyValue<'a> = (Cow<'a, [u8]>, Cow<'a, [u8]>);
+
+/// Iterator over the entries of a table whose keys are within a range, in ascending key order.
+///
+/// Created with [`Tx::walk_range`](super::tx::Tx::walk_range).
+#[derive(Debug)]
+pub struct RangeWalker<K: TransactionKind, T: Table> {
+    cursor: Cursor<K, T>,
+    /// The encoded start bound, until the walk has started.
+    start: Option<Bound<Vec<u8>>>,
+    /// The encoded end bound.
+    end: Bound<Vec<u8>>,
+    /// Whether the end of the range, or an error, has been reached.
+    done: bool,
+}
+
+impl<K: TransactionKind, T: Table> RangeWalker<K, T> {
+    pub(crate) fn new(cursor: Cursor<K, T>, start: Bound<Vec<u8>>, end: Bound<Vec<u8>>) -> Self {
+        Self { cursor, start: Some(start), end, done: false }
+    }
+
+    /// Positions the cursor at the first entry after the start bound, returns `false` if there is
+    /// none.
+    fn seek_start(&mut self, start: Bound<Vec<u8>>) -> Result<bool, DatabaseError> {
+        let inner = &mut self.cursor.inner;
+        let entry: Option<RawKeyValue<'_>> = match &start {
+            Bound::Included(key) | Bound::Excluded(key) => inner.set_range(key),
+            Bound::Unbounded => inner.first(),
+        }
+        .map_err(DatabaseError::Read)?;
+
+        let Some((key, _)) = entry else { return Ok(false) };
+        if matches!(&start, Bound::Excluded(start) if key.as_ref() == start.as_slice()) {
+            let next: Option<RawKeyValue<'_>> = inner.next().map_err(DatabaseError::Read)?;
+            return Ok(next.is_some());
+        }
+
+        Ok(true)
+    }
+}
+
+impl<K: TransactionKind, T: Table> Iterator for RangeWalker<K, T> {
+    type Item = Result<KeyValue<T>, DatabaseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let entry: Result<Option<RawKeyValue<'_>>, _> = match self.start.take() {
+            Some(start) => match self.seek_start(start) {
+                Ok(true) => self.cursor.inner.get_current().map_err(DatabaseError::Read),
+                Ok(false) => Ok(None),
+                Err(error) => Err(error),
+            },
+            None => self.cursor.inner.next().map_err(DatabaseError::Read),
+        };
+
+        let (key, value) = match entry {
+            Ok(Some(entry)) => entry,
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(error) => {
+                self.done = true;
+                return Some(Err(error));
+            }
+        };
+
+        let past_end = match &self.end {
+            Bound::Included(end) => compare_keys::<T>(&key, end).map(Ordering::is_gt),
+            Bound::Excluded(end) => compare_keys::<T>(&key, end).map(Ordering::is_ge),
+            Bound::Unbounded => Ok(false),
+        };
+
+        match past_end {
+            Ok(false) => {}
+            Ok(true) => {
+                self.done = true;
+                return None;
+            }
+            Err(error) => {
+                self.done = true;
+                return Some(Err(error));
+            }
+        }
+
+        Some(decoder::<T>((key, value)))
+    }
+}
+
+impl<K, T> Cursor<K, T>
+where
+    K: TransactionKind,
+    T: Table,
+    T::Key: From<u64> + Into<u64>,
+{
+    /// Splits the key space of an integer keyed table into at most `n` contiguous, non-overlapping
+    /// ranges of roughly equal width, spanning from the first to the last key of the table.
+    ///
+    /// The split is based on the key bounds only, not on the actual key distribution. Each range
+    /// can then be scanned independently (e.g. from a different thread, each with its own
+    /// transaction) by walking from its start key until its end key is passed.
+    ///
+    /// Returns an empty list if the table is empty or `n` is zero.
+    pub fn split_ranges(&mut self, n: usize) -> Result<Vec<RangeInclusive<T::Key>>, DatabaseError> {
+        let Some((min, _)) = self.first()? else { return Ok(Vec::new()) };
+        let Some((max, _)) = self.last()? else { return Ok(Vec::new()) };
+
+        let (min, max): (u64, u64) = (min.into(), max.into());
+        // Use u128 so that a table spanning the whole u64 key space doesn't overflow.
+        let span = (max - min) as u128 + 1;
+        let count = (n as u128).min(span);
+
+        let mut ranges = Vec::with_capacity(count as usize);
+        let mut start = min as u128;
+
+        for i in 0..count {
+            // Spread the remainder over the first ranges.
+            let width = span / count + u128::from(i < span % count);
+            let end = start + width - 1;
+            ranges.push(T::Key::from(start as u64)..=T::Key::from(end as u64));
+            start = end + 1;
+        }
+
+        Ok(ranges)
+    }
+}
+
+impl<K: TransactionKind, T: DupSort> Cursor<K, T> {
+    /// Returns the number of duplicates of the key the cursor is positioned at, without reading
+    /// them, e.g. to size a buffer before collecting them.
+    ///
+    /// Returns [`DatabaseError::CursorNotPositioned`] if the cursor isn't positioned on an entry.
+    pub fn dup_count(&self) -> Result<usize, DatabaseError> {
+        let mut count = 0;
+        // SAFETY: the cursor pointer is valid for as long as `self.inner` is alive.
+        let code = unsafe { ffi::mdbx_cursor_count(self.inner.cursor(), &mut count) };
+
+        match code {
+            ffi::MDBX_SUCCESS => Ok(count),
+            ffi::MDBX_ENODATA | ffi::MDBX_EINVAL => Err(DatabaseError::CursorNotPositioned),
+            code => Err(DatabaseError::Read(libmdbx::Error::from_err_code(code))),
+        }
     }
 }
 
@@ -68,18 +258,46 @@ where
         decode!(libmdbx::Cursor::set_range(&mut self.inner, key.encode().as_ref()))
     }
 
+    fn seek_exact(&mut self, key: <T as Table>::Key) -> Result<Option<KeyValue<T>>, DatabaseError> {
+        decode!(libmdbx::Cursor::set_key(&mut self.inner, key.encode().as_ref()))
+    }
+
     fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
-        let start = if let Some(start_key) = start_key {
-            self.inner
-                .set_range(start_key.encode().as_ref())
-                .map_err(DatabaseError::Read)?
-                .map(decoder::<T>)
-        } else {
-            self.first().transpose()
-        };
+        let start = instrument::record(Operation::CursorWalk, Some(T::NAME), || {
+            Ok::<_, DatabaseError>(if let Some(start_key) = start_key {
+                self.inner
+                    .set_range(start_key.encode().as_ref())
+                    .map_err(DatabaseError::Read)?
+                    .map(decoder::<T>)
+            } else {
+                self.first().transpose()
+            })
+        })?;
 
         Ok(Walker::new(self, start))
     }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start = instrument::record(Operation::CursorWalk, Some(T::NAME), || {
+            Ok::<_, DatabaseError>(match start_key {
+                None => self.last()?,
+                Some(start_key) => match self.set(start_key.clone())? {
+                    Some(entry) => Some(entry),
+                    // Not found, so start from the item right before the first greater key, if
+                    // any.
+                    None => match self.seek(start_key)? {
+                        Some(_) => self.prev()?,
+                        None => self.last()?,
+                    },
+                },
+            })
+        })?;
+
+        Ok(ReverseWalker::new(self, start.map(Ok)))
+    }
 }
 
 impl<K, T> DbDupSortCursor<T> for Cursor<K, T>
@@ -98,6 +316,10 @@ where
             .transpose()
     }
 
+    fn prev_dup(&mut self) -> Result<Option<KeyValue<T>>, DatabaseError> {
+        decode!(libmdbx::Cursor::prev_dup(&mut self.inner))
+    }
+
     fn next_no_dup(&mut self) -> Result<Option<KeyValue<T>>, DatabaseError> {
         decode!(libmdbx::Cursor::next_nodup(&mut self.inner))
     }
@@ -171,8 +393,9 @@ where
     T: Table,
 {
     fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        let key = Encode::encode(key);
-        let value = Compress::compress(value);
+        let key = encode_key::<T>(key)?;
+        let value = compress_value::<T>(value)?;
+        self.mark_mutated();
 
         libmdbx::Cursor::put(&mut self.inner, key.as_ref(), value.as_ref(), WriteFlags::UPSERT)
             .map_err(|error| DatabaseError::Write {
@@ -183,8 +406,9 @@ where
     }
 
     fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        let key = Encode::encode(key);
-        let value = Compress::compress(value);
+        let key = encode_key::<T>(key)?;
+        let value = compress_value::<T>(value)?;
+        self.mark_mutated();
 
         libmdbx::Cursor::put(
             &mut self.inner,
@@ -200,8 +424,9 @@ where
     }
 
     fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        let key = Encode::encode(key);
-        let value = Compress::compress(value);
+        let key = encode_key::<T>(key)?;
+        let value = compress_value::<T>(value)?;
+        self.mark_mutated();
 
         libmdbx::Cursor::put(&mut self.inner, key.as_ref(), value.as_ref(), WriteFlags::APPEND)
             .map_err(|error| DatabaseError::Write {
@@ -212,6 +437,7 @@ where
     }
 
     fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        self.mark_mutated();
         libmdbx::Cursor::del(&mut self.inner, WriteFlags::CURRENT).map_err(DatabaseError::Delete)
     }
 }
@@ -221,13 +447,15 @@ where
     T: DupSort,
 {
     fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        self.mark_mutated();
         libmdbx::Cursor::del(&mut self.inner, WriteFlags::NO_DUP_DATA)
             .map_err(DatabaseError::Delete)
     }
 
     fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        let key = Encode::encode(key);
-        let value = Compress::compress(value);
+        let key = encode_key::<T>(key)?;
+        let value = compress_value::<T>(value)?;
+        self.mark_mutated();
 
         libmdbx::Cursor::put(&mut self.inner, key.as_ref(), value.as_ref(), WriteFlags::APPEND_DUP)
             .map_err(|error| DatabaseError::Write {
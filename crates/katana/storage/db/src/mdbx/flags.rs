@@ -0,0 +1,91 @@
+use libmdbx::{EnvironmentFlags, Mode, SyncMode};
+
+/// Durability mode of a read-write database environment.
+///
+/// Mirrors libmdbx's [`SyncMode`]. Weaker modes trade durability for write throughput, see the
+/// upstream `MDBX_env_flags_t` documentation for the exact guarantees provided by each mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DbSyncMode {
+    /// Default robust and durable sync mode. Every commit is flushed to disk.
+    #[default]
+    Durable,
+    /// Don't sync the meta-page after commit. A system crash may undo the last committed
+    /// transaction, but the database integrity is preserved.
+    NoMetaSync,
+    /// Don't sync anything but keep the previous steady commits. A system crash may undo the
+    /// latest transactions, but the database integrity is preserved.
+    SafeNoSync,
+    /// Don't sync anything and wipe the previous steady commits. A system crash may corrupt the
+    /// database. Should only be used for throwaway databases.
+    UtterlyNoSync,
+}
+
+impl From<SyncMode> for DbSyncMode {
+    fn from(mode: SyncMode) -> Self {
+        match mode {
+            SyncMode::Durable => Self::Durable,
+            SyncMode::NoMetaSync => Self::NoMetaSync,
+            SyncMode::SafeNoSync => Self::SafeNoSync,
+            SyncMode::UtterlyNoSync => Self::UtterlyNoSync,
+        }
+    }
+}
+
+impl From<DbSyncMode> for SyncMode {
+    fn from(mode: DbSyncMode) -> Self {
+        match mode {
+            DbSyncMode::Durable => Self::Durable,
+            DbSyncMode::NoMetaSync => Self::NoMetaSync,
+            DbSyncMode::SafeNoSync => Self::SafeNoSync,
+            DbSyncMode::UtterlyNoSync => Self::UtterlyNoSync,
+        }
+    }
+}
+
+/// A snapshot of the flags a database environment was opened with.
+///
+/// Mainly useful for diagnostics, see [`DbEnv::open_flags`](super::DbEnv::open_flags).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvOpenFlags {
+    /// Whether the environment was opened in read-only mode.
+    pub read_only: bool,
+    /// The durability mode of the environment. `None` if the environment is read-only.
+    pub sync_mode: Option<DbSyncMode>,
+    /// Whether the environment is a single file instead of a directory (`MDBX_NOSUBDIR`).
+    pub no_sub_dir: bool,
+    /// Whether the environment was opened in exclusive mode (`MDBX_EXCLUSIVE`).
+    pub exclusive: bool,
+    /// Whether the environment was opened using the existing settings of the database
+    /// (`MDBX_ACCEDE`).
+    pub accede: bool,
+    /// Whether OS readahead is disabled (`MDBX_NORDAHEAD`).
+    pub no_rdahead: bool,
+    /// Whether malloc'd memory is not initialized before being written to disk
+    /// (`MDBX_NOMEMINIT`).
+    pub no_meminit: bool,
+    /// Whether freed pages are coalesced when reclaimed (`MDBX_COALESCE`).
+    pub coalesce: bool,
+    /// Whether freed pages are reclaimed in LIFO order (`MDBX_LIFORECLAIM`).
+    pub liforeclaim: bool,
+}
+
+impl From<&EnvironmentFlags> for EnvOpenFlags {
+    fn from(flags: &EnvironmentFlags) -> Self {
+        let (read_only, sync_mode) = match flags.mode {
+            Mode::ReadOnly => (true, None),
+            Mode::ReadWrite { sync_mode } => (false, Some(sync_mode.into())),
+        };
+
+        Self {
+            read_only,
+            sync_mode,
+            no_sub_dir: flags.no_sub_dir,
+            exclusive: flags.exclusive,
+            accede: flags.accede,
+            no_rdahead: flags.no_rdahead,
+            no_meminit: flags.no_meminit,
+            coalesce: flags.coalesce,
+            liforeclaim: flags.liforeclaim,
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Instrumentation of the database operations.
+//!
+//! When the `metrics` feature is enabled, every instrumented operation increments the
+//! `db.operations` counter and records its latency, in seconds, in the `db.operation_duration`
+//! histogram. Both are labeled with the `operation` and, if any, the `table` it applies to.
+//! Without the feature, the operations are called directly and nothing is recorded.
+
+/// A database operation whose count and latency are recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    Get,
+    Put,
+    Delete,
+    Commit,
+    CursorWalk,
+}
+
+impl Operation {
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    const fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Get => "get",
+            Operation::Put => "put",
+            Operation::Delete => "delete",
+            Operation::Commit => "commit",
+            Operation::CursorWalk => "cursor_walk",
+        }
+    }
+}
+
+/// Runs the `operation` on `table` implemented by `f`, and records it.
+#[cfg(feature = "metrics")]
+pub(crate) fn record<R>(
+    operation: Operation,
+    table: Option<&'static str>,
+    f: impl FnOnce() -> R,
+) -> R {
+    use metrics::{counter, histogram, Label};
+
+    let start = std::time::Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+
+    let mut labels = vec![Label::new("operation", operation.as_str())];
+    if let Some(table) = table {
+        labels.push(Label::new("table", table));
+    }
+
+    counter!("db.operations", labels.clone()).increment(1);
+    histogram!("db.operation_duration", labels).record(elapsed.as_secs_f64());
+
+    result
+}
+
+/// Runs the `operation` on `table` implemented by `f`. Nothing is recorded without the `metrics`
+/// feature.
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn record<R>(_: Operation, _: Option<&'static str>, f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use katana_primitives::Felt;
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+    use parking_lot::Mutex;
+
+    use crate::abstraction::{Database, DbCursor, DbTx, DbTxMut};
+    use crate::mdbx::test_utils::create_test_db;
+    use crate::tables::BlockHashes;
+
+    /// Records the names and `operation` labels of the registered metrics.
+    #[derive(Default)]
+    struct NameRecorder(Mutex<BTreeSet<(String, String)>>);
+
+    impl NameRecorder {
+        fn register(&self, key: &Key) {
+            let operation = key.labels().find(|label| label.key() == "operation");
+            let operation = operation.map(|label| label.value().to_string()).unwrap_or_default();
+            self.0.lock().insert((key.name().to_string(), operation));
+        }
+    }
+
+    impl Recorder for NameRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            self.register(key);
+            Counter::noop()
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            self.register(key);
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            self.register(key);
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn operations_are_recorded() {
+        let recorder = NameRecorder::default();
+
+        metrics::with_local_recorder(&recorder, || {
+            let env = create_test_db();
+
+            let tx = env.tx_mut().unwrap();
+            tx.put::<BlockHashes>(1, Felt::ONE).unwrap();
+            tx.get::<BlockHashes>(1).unwrap();
+            tx.delete::<BlockHashes>(1, None).unwrap();
+            tx.cursor::<BlockHashes>().unwrap().walk(None).unwrap();
+            tx.commit().unwrap();
+        });
+
+        let recorded = recorder.0.into_inner();
+        for operation in ["get", "put", "delete", "commit", "cursor_walk"] {
+            for name in ["db.operations", "db.operation_duration"] {
+                let metric = (name.to_string(), operation.to_string());
+                assert!(recorded.contains(&metric), "{name} isn't recorded for {operation}");
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::BTreeSet;
+
+use crate::error::CodecError;
+
+/// Outcome of [`DbEnv::verify_integrity`](super::DbEnv::verify_integrity).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Number of entries checked, across all tables.
+    pub entries: usize,
+    /// Problems found, in the order the tables were walked.
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// Returns `true` if no problem was found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the names of the tables with at least one problem.
+    pub fn tables(&self) -> BTreeSet<&'static str> {
+        self.issues.iter().map(|issue| issue.table).collect()
+    }
+}
+
+/// A problem found in a table by [`DbEnv::verify_integrity`](super::DbEnv::verify_integrity).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegrityIssue {
+    /// Name of the table.
+    pub table: &'static str,
+    /// Encoded key of the offending entry.
+    pub key: Vec<u8>,
+    /// What's wrong with the entry.
+    pub kind: IntegrityIssueKind,
+}
+
+/// The kind of an [`IntegrityIssue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssueKind {
+    /// The key or the value can't be decoded with the codecs of the table.
+    Decode(CodecError),
+    /// The key is ordered before the key of the previous entry, ie. the entries between
+    /// `previous` and the key are out of order.
+    Order { previous: Vec<u8> },
+}
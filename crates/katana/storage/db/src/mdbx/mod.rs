@@ -2,30 +2,55 @@
 //!
 //! The code is adapted from `reth` mdbx implementation:  <https://github.com/paradigmxyz/reth/blob/227e1b7ad513977f4f48b18041df02686fca5f94/crates/storage/db/src/implementation/mdbx/mod.rs>
 
+pub mod changefeed;
 pub mod cursor;
+pub mod flags;
+mod instrument;
+pub mod integrity;
+pub mod snapshot;
 pub mod stats;
 pub mod tx;
+mod watchdog;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ffi::CString;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use std::{fs, io};
 
 use dojo_metrics::metrics::gauge;
 pub use libmdbx;
-use libmdbx::{DatabaseFlags, EnvironmentFlags, Geometry, Mode, PageSize, SyncMode, RO, RW};
+use libmdbx::ffi;
+use libmdbx::{
+    DatabaseFlags, EnvironmentFlags, Geometry, Mode, PageSize, SyncMode, WriteFlags, RO, RW,
+};
 use metrics::{describe_gauge, Label};
+use parking_lot::Mutex;
 use tracing::error;
 
-use self::stats::{Stats, TableStat};
+use self::changefeed::{Changefeed, CommitReceiver, DEFAULT_CHANGEFEED_CAPACITY};
+use self::flags::{DbSyncMode, EnvOpenFlags};
+use self::integrity::{IntegrityIssue, IntegrityIssueKind, IntegrityReport};
+use self::snapshot::Snapshot;
+use self::stats::{EnvInfo, Stats, TableStat};
 use self::tx::Tx;
-use crate::abstraction::Database;
+use self::watchdog::WriteWatchdog;
+use crate::abstraction::{Database, DbTx};
 use crate::error::DatabaseError;
-use crate::tables::{TableType, Tables, NUM_TABLES};
+use crate::tables::{SchemaVersion, Table, TableType, Tables, SCHEMA_VERSION_KEY};
 use crate::utils;
 
 const GIGABYTE: usize = 1024 * 1024 * 1024;
 const TERABYTE: usize = GIGABYTE * 1024;
 
+/// Name of the MDBX data file in the environment directory.
+const MDBX_DATA_FILE_NAME: &str = "mdbx.dat";
+
 /// MDBX allows up to 32767 readers (`MDBX_READERS_LIMIT`), but we limit it to slightly below that
 const DEFAULT_MAX_READERS: u64 = 32_000;
 
@@ -38,6 +63,92 @@ pub enum DbEnvKind {
     RW,
 }
 
+/// Options for opening a MDBX environment with [`DbEnv::open_with_config`].
+#[derive(Debug, Clone, Default)]
+pub struct DbEnvConfig {
+    /// Number of additional named tables (DBIs) that can be opened on top of the ones defined in
+    /// [`Tables`], e.g. for temporary tables created at runtime.
+    pub extra_tables: usize,
+    /// Lower bound of the database size, in bytes. MDBX allocates it upfront, which avoids
+    /// growing the database in small steps early on.
+    pub initial_size: usize,
+    /// Upper bound of the database size, in bytes. Defaults to 1 terabyte. Must be a multiple of
+    /// the page size and of the growth step.
+    pub max_size: Option<usize>,
+    /// Increment by which the database grows, in bytes. Defaults to the largest power of two
+    /// the max size is a multiple of, up to 4 gigabytes. Must be a multiple of the page size.
+    pub growth_step: Option<usize>,
+    /// Size of the database pages, in bytes. Must be a power of two between 256 and 65536.
+    /// Defaults to the OS page size, clamped to 4096..65536. Larger pages favor sequential
+    /// reads of large values, smaller ones random accesses. The page size is fixed when the
+    /// database is created, so opening an existing database with a different page size fails.
+    pub page_size: Option<usize>,
+    /// Amount of unused space at the end of the database, in bytes, above which the database
+    /// shrinks. By default the database never shrinks.
+    pub shrink_threshold: Option<usize>,
+    /// Durability mode of a read-write environment, ignored for read-only ones. Defaults to
+    /// [`DbSyncMode::Durable`], which flushes every commit to disk. Weaker modes make commits
+    /// faster, at the cost of possibly losing the latest commits, or for
+    /// [`DbSyncMode::UtterlyNoSync`] corrupting the database, on a system crash. They should
+    /// only be used for throwaway databases, e.g. devnets and tests.
+    pub sync_mode: DbSyncMode,
+    /// Number of times the upper bound of the database size can be increased by the growth step
+    /// when a transaction run with [`DbEnv::update_growing`] fills the database up. Disabled by
+    /// default.
+    pub max_auto_grows: usize,
+}
+
+impl DbEnvConfig {
+    /// Builds the MDBX geometry from the configured sizes, falling back to the defaults for the
+    /// unset ones.
+    fn geometry(&self) -> Result<Geometry<Range<usize>>, DatabaseError> {
+        let page_size = match self.page_size {
+            Some(size) => validate_page_size(size)?,
+            None => utils::default_page_size(),
+        };
+        let max_size = self.max_size.unwrap_or(TERABYTE);
+
+        if max_size % page_size != 0 {
+            return Err(DatabaseError::InvalidGeometry(format!(
+                "max size of {max_size} bytes is not a multiple of the page size ({page_size} \
+                 bytes)"
+            )));
+        }
+
+        let growth_step = self.growth_step.unwrap_or_else(|| default_growth_step(max_size));
+
+        if growth_step == 0 || growth_step % page_size != 0 {
+            return Err(DatabaseError::InvalidGeometry(format!(
+                "growth step of {growth_step} bytes is not a multiple of the page size \
+                 ({page_size} bytes)"
+            )));
+        }
+
+        if max_size % growth_step != 0 {
+            return Err(DatabaseError::InvalidGeometry(format!(
+                "max size of {max_size} bytes is not a multiple of the growth step \
+                 ({growth_step} bytes)"
+            )));
+        }
+
+        Ok(Geometry {
+            size: Some(self.initial_size..max_size),
+            growth_step: Some(growth_step as isize),
+            shrink_threshold: self.shrink_threshold.map(|threshold| threshold as isize),
+            page_size: Some(PageSize::Set(page_size)),
+        })
+    }
+}
+
+/// Returns the largest power of two, up to 4 gigabytes, that `max_size` is a multiple of.
+fn default_growth_step(max_size: usize) -> usize {
+    let mut step = 4 * GIGABYTE;
+    while max_size % step != 0 {
+        step /= 2;
+    }
+    step
+}
+
 /// Wrapper for `libmdbx-sys` environment.
 #[derive(Debug, Clone)]
 pub struct DbEnv {
@@ -53,6 +164,26 @@ struct DbEnvInner {
     /// A flag inidicating whether the database is ephemeral or not. If `true`, the database will
     /// be deleted when the environment is dropped.
     ephemeral: bool,
+    /// The flags the environment was opened with.
+    flags: EnvOpenFlags,
+    /// Whether the environment is in maintenance mode, in which case no new transactions can be
+    /// created.
+    maintenance: AtomicBool,
+    /// Number of live [`ReadGuard`]s. Read-write transactions can't be created while non-zero.
+    read_guards: AtomicUsize,
+    /// Number of read-write transactions created from this environment, either live or waiting
+    /// for the one in progress.
+    writers: Arc<AtomicUsize>,
+    /// Feed of the committed read-write transactions.
+    changefeed: Arc<Changefeed>,
+    /// Watchdog of the long running read-write transactions, if a write timeout is set.
+    watchdog: Mutex<Option<Arc<WriteWatchdog>>>,
+    /// Increment by which the upper bound of the database size is automatically grown.
+    growth_step: usize,
+    /// Maximum number of automatic grows of the upper bound of the database size.
+    max_auto_grows: usize,
+    /// Number of automatic grows done so far.
+    auto_grows: AtomicUsize,
 }
 
 impl DbEnv {
@@ -60,36 +191,67 @@ impl DbEnv {
     ///
     /// It does not create the tables, for that call [`DbEnv::create_tables`].
     pub fn open(path: impl AsRef<Path>, kind: DbEnvKind) -> Result<DbEnv, DatabaseError> {
+        Self::open_with_config(path, kind, DbEnvConfig::default())
+    }
+
+    /// Opens the database at the specified path with the given `EnvKind` and [`DbEnvConfig`].
+    ///
+    /// It does not create the tables, for that call [`DbEnv::create_tables`].
+    pub fn open_with_config(
+        path: impl AsRef<Path>,
+        kind: DbEnvKind,
+        config: DbEnvConfig,
+    ) -> Result<DbEnv, DatabaseError> {
         let mode = match kind {
             DbEnvKind::RO => Mode::ReadOnly,
-            DbEnvKind::RW => Mode::ReadWrite { sync_mode: SyncMode::Durable },
+            DbEnvKind::RW => Mode::ReadWrite { sync_mode: config.sync_mode.into() },
+        };
+
+        let flags = EnvironmentFlags {
+            mode,
+            // We disable readahead because it improves performance for linear scans, but
+            // worsens it for random access (which is our access pattern outside of sync)
+            no_rdahead: true,
+            coalesce: true,
+            ..Default::default()
         };
+        let open_flags = EnvOpenFlags::from(&flags);
+
+        let geometry = config.geometry()?;
+        let growth_step = geometry.growth_step.unwrap_or_default() as usize;
 
         let mut builder = libmdbx::Environment::builder();
         builder
-            .set_max_dbs(Tables::ALL.len())
-            .set_geometry(Geometry {
-                // Maximum database size of 1 terabytes
-                size: Some(0..(TERABYTE)),
-                // We grow the database in increments of 4 gigabytes
-                growth_step: Some(4 * GIGABYTE as isize),
-                // The database never shrinks
-                shrink_threshold: None,
-                page_size: Some(PageSize::Set(utils::default_page_size())),
-            })
-            .set_flags(EnvironmentFlags {
-                mode,
-                // We disable readahead because it improves performance for linear scans, but
-                // worsens it for random access (which is our access pattern outside of sync)
-                no_rdahead: true,
-                coalesce: true,
-                ..Default::default()
-            })
+            .set_max_dbs(Tables::ALL.len() + config.extra_tables)
+            .set_geometry(geometry)
+            .set_flags(flags)
             .set_max_readers(DEFAULT_MAX_READERS);
 
         let env = builder.open(path.as_ref()).map_err(DatabaseError::OpenEnv)?;
+
+        // MDBX silently keeps the page size of an existing database.
+        if let Some(requested) = config.page_size {
+            let actual = env.stat().map_err(DatabaseError::Stat)?.page_size() as usize;
+            if actual != requested {
+                return Err(DatabaseError::PageSizeMismatch { requested, actual });
+            }
+        }
+
         let dir = path.as_ref().to_path_buf();
-        let inner = DbEnvInner { env, dir, ephemeral: false };
+        let inner = DbEnvInner {
+            env,
+            dir,
+            ephemeral: false,
+            flags: open_flags,
+            maintenance: AtomicBool::new(false),
+            read_guards: AtomicUsize::new(0),
+            writers: Default::default(),
+            changefeed: Default::default(),
+            watchdog: Mutex::new(None),
+            growth_step,
+            max_auto_grows: config.max_auto_grows,
+            auto_grows: AtomicUsize::new(0),
+        };
 
         Ok(Self { inner: Arc::new(inner) }.with_metrics())
     }
@@ -101,6 +263,15 @@ impl DbEnv {
             tempfile::Builder::new().keep(true).tempdir().expect("failed to create a temp dir");
         let path = dir.path();
 
+        let flags = EnvironmentFlags {
+            // we dont care about durability  here
+            mode: Mode::ReadWrite { sync_mode: SyncMode::UtterlyNoSync },
+            no_rdahead: true,
+            coalesce: true,
+            ..Default::default()
+        };
+        let open_flags = EnvOpenFlags::from(&flags);
+
         let mut builder = libmdbx::Environment::builder();
         builder
             .set_max_dbs(Tables::ALL.len())
@@ -110,18 +281,25 @@ impl DbEnv {
                 shrink_threshold: None,
                 page_size: Some(PageSize::Set(utils::default_page_size())),
             })
-            .set_flags(EnvironmentFlags {
-                // we dont care about durability  here
-                mode: Mode::ReadWrite { sync_mode: SyncMode::UtterlyNoSync },
-                no_rdahead: true,
-                coalesce: true,
-                ..Default::default()
-            })
+            .set_flags(flags)
             .set_max_readers(DEFAULT_MAX_READERS);
 
         let env = builder.open(path).map_err(DatabaseError::OpenEnv)?;
         let dir = path.to_path_buf();
-        let inner = DbEnvInner { env, dir, ephemeral: true };
+        let inner = DbEnvInner {
+            env,
+            dir,
+            ephemeral: true,
+            flags: open_flags,
+            maintenance: AtomicBool::new(false),
+            read_guards: AtomicUsize::new(0),
+            writers: Default::default(),
+            changefeed: Default::default(),
+            watchdog: Mutex::new(None),
+            growth_step: GIGABYTE / 2,
+            max_auto_grows: 0,
+            auto_grows: AtomicUsize::new(0),
+        };
 
         Ok(Self { inner: Arc::new(inner) }.with_metrics())
     }
@@ -131,11 +309,7 @@ impl DbEnv {
         let tx = self.inner.env.begin_rw_txn().map_err(DatabaseError::CreateRWTx)?;
 
         for table in Tables::ALL {
-            let flags = match table.table_type() {
-                TableType::Table => DatabaseFlags::default(),
-                TableType::DupSort => DatabaseFlags::DUP_SORT,
-            };
-
+            let (flags, _) = table_flags(table);
             tx.create_db(Some(table.name()), flags).map_err(DatabaseError::CreateTable)?;
         }
 
@@ -144,11 +318,463 @@ impl DbEnv {
         Ok(())
     }
 
-    /// Returns the path to the database environment directory.
+    /// Returns the path to the database environment directory, as it was given when opening the
+    /// environment.
     pub fn path(&self) -> &Path {
         &self.inner.dir
     }
 
+    /// Returns the canonical, absolute form of [`DbEnv::path`].
+    pub fn canonical_path(&self) -> io::Result<PathBuf> {
+        self.inner.dir.canonicalize()
+    }
+
+    /// Returns `true` if both handles refer to the same on-disk environment.
+    ///
+    /// Handles are compared by their canonical paths, so that the same directory opened through
+    /// different (e.g. relative and absolute) paths is considered the same environment.
+    pub fn is_same_env(&self, other: &DbEnv) -> bool {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return true;
+        }
+
+        match (self.canonical_path(), other.canonical_path()) {
+            (Ok(this), Ok(other)) => this == other,
+            _ => false,
+        }
+    }
+
+    /// Returns the flags the database environment was opened with.
+    pub fn open_flags(&self) -> EnvOpenFlags {
+        self.inner.flags
+    }
+
+    /// Refreshes the memory map of the environment to the current on-disk geometry, and returns
+    /// the resulting map size.
+    ///
+    /// This is mostly useful for long-lived read-only environments while another process is
+    /// writing to the database: when the writer grows the database, MDBX only remaps the reader
+    /// the next time it starts a read transaction, which is what this method does eagerly so
+    /// that the new data is readable without having to reopen the environment.
+    pub fn refresh_geometry(&self) -> Result<usize, DatabaseError> {
+        let tx = self.inner.env.begin_ro_txn().map_err(DatabaseError::CreateROTx)?;
+        drop(tx);
+
+        let info = self.inner.env.info().map_err(DatabaseError::Stat)?;
+        Ok(info.map_size())
+    }
+
+    /// Returns the size bounds of the database, in bytes, as currently configured in the
+    /// environment geometry.
+    pub fn size_bounds(&self) -> Result<Range<usize>, DatabaseError> {
+        let info = self.inner.env.info().map_err(DatabaseError::Stat)?;
+        Ok(info.geometry().size.unwrap_or_default())
+    }
+
+    /// Returns the statistics of the given table, e.g. its number of entries and pages.
+    pub fn table_stat(&self, table: Tables) -> Result<TableStat, DatabaseError> {
+        self.view(|tx| tx.table_stat(table))?
+    }
+
+    /// Returns the statistics of all the tables, keyed by table name. The stats are all read from
+    /// the same transaction.
+    pub fn all_table_stats(&self) -> Result<HashMap<&'static str, TableStat>, DatabaseError> {
+        self.view(|tx| tx.all_stats())?
+    }
+
+    /// Returns environment-wide information, e.g. the map size and the number of reader slots in
+    /// use, as opposed to the per-table [`DbEnv::all_table_stats`].
+    pub fn env_info(&self) -> Result<EnvInfo, DatabaseError> {
+        let info = self.inner.env.info().map_err(DatabaseError::Stat)?;
+        Ok(EnvInfo::new(info))
+    }
+
+    /// Returns the version of the database schema, `0` if it has never been set.
+    pub fn schema_version(&self) -> Result<u32, DatabaseError> {
+        Ok(self.view(|tx| tx.get::<SchemaVersion>(SCHEMA_VERSION_KEY))??.unwrap_or_default())
+    }
+
+    /// Returns the id of the last committed read-write transaction.
+    ///
+    /// MDBX ids are monotonically increasing, so this can be used by external systems to detect
+    /// whether the database has changed since they last read it. Read-only transactions don't
+    /// change the id.
+    pub fn last_txn_id(&self) -> Result<u64, DatabaseError> {
+        let info = self.inner.env.info().map_err(DatabaseError::Stat)?;
+        Ok(info.last_txnid() as u64)
+    }
+
+    /// Enables or disables the maintenance mode.
+    ///
+    /// While in maintenance mode, creating a new transaction fails with
+    /// [`DatabaseError::Maintenance`]. Transactions created before the mode was enabled are not
+    /// affected and can still be used and committed.
+    pub fn set_maintenance(&self, enabled: bool) {
+        self.inner.maintenance.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the environment is in maintenance mode.
+    pub fn is_maintenance(&self) -> bool {
+        self.inner.maintenance.load(Ordering::SeqCst)
+    }
+
+    /// Subscribes to the commits of read-write transactions.
+    ///
+    /// A [`CommitEvent`](changefeed::CommitEvent) is emitted for every committed transaction that
+    /// mutated at least one table. Only transactions created after the subscription are tracked.
+    /// Committing never blocks on the subscribers, if a subscriber lags behind by more than
+    /// [`DEFAULT_CHANGEFEED_CAPACITY`] events, its oldest events are dropped.
+    pub fn subscribe(&self) -> CommitReceiver {
+        self.inner.changefeed.subscribe(DEFAULT_CHANGEFEED_CAPACITY)
+    }
+
+    /// Takes a [`Snapshot`] of the database at its latest committed state.
+    ///
+    /// Like a read-only transaction from [`Database::tx`], the snapshot keeps reading the same
+    /// state regardless of the writes committed afterwards. But it can release its transaction
+    /// between queries, so that a long-lived snapshot doesn't keep the pages it reads from being
+    /// reused. The tradeoff is that a released snapshot can't be reopened once the database has
+    /// been written to.
+    pub fn snapshot(&self) -> Result<Snapshot, DatabaseError> {
+        Snapshot::new(self.clone())
+    }
+
+    /// Copies the given `tables` into a new environment created at `dest`, and returns it.
+    ///
+    /// Only the requested tables are created in the destination. Their entries are copied as is,
+    /// in key order, from a single read transaction of this environment.
+    pub fn export_tables(
+        &self,
+        dest: impl AsRef<Path>,
+        tables: &[Tables],
+    ) -> Result<DbEnv, DatabaseError> {
+        let dest = DbEnv::open(dest, DbEnvKind::RW)?;
+
+        let src_tx = self.inner.env.begin_ro_txn().map_err(DatabaseError::CreateROTx)?;
+        let dest_tx = dest.inner.env.begin_rw_txn().map_err(DatabaseError::CreateRWTx)?;
+
+        for table in tables {
+            let (db_flags, write_flags) = table_flags(*table);
+
+            let src_db = src_tx.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?;
+            let dest_db = dest_tx
+                .create_db(Some(table.name()), db_flags)
+                .map_err(DatabaseError::CreateTable)?;
+
+            let mut src_cursor = src_tx.cursor(&src_db).map_err(DatabaseError::CreateCursor)?;
+            let mut dest_cursor = dest_tx.cursor(&dest_db).map_err(DatabaseError::CreateCursor)?;
+
+            let mut entry: Option<(Cow<'_, [u8]>, Cow<'_, [u8]>)> =
+                src_cursor.first().map_err(DatabaseError::Read)?;
+
+            while let Some((key, value)) = entry {
+                dest_cursor.put(&key, &value, write_flags).map_err(|error| {
+                    DatabaseError::Write {
+                        error,
+                        table: table.name(),
+                        key: Box::from(key.as_ref()),
+                    }
+                })?;
+                entry = src_cursor.next().map_err(DatabaseError::Read)?;
+            }
+        }
+
+        dest_tx.commit().map_err(DatabaseError::Commit)?;
+        Ok(dest)
+    }
+
+    /// Dumps every table into the `dir` directory, which is created if it doesn't exist, and
+    /// returns the total number of entries dumped.
+    ///
+    /// Each table is written to its own `<table>.ndjson` file with [`Tx::dump_table`]. All the
+    /// tables are dumped from a single read transaction, so the files are consistent with each
+    /// other.
+    pub fn dump_all(&self, dir: impl AsRef<Path>) -> Result<usize, DatabaseError> {
+        let dir = dir.as_ref();
+        let tx = self.tx()?;
+        let mut count = 0;
+
+        for table in Tables::ALL {
+            let dump_error =
+                |e: io::Error| DatabaseError::Dump { table: table.name(), reason: e.to_string() };
+
+            fs::create_dir_all(dir).map_err(dump_error)?;
+            let file = fs::File::create(dir.join(format!("{table}.ndjson"))).map_err(dump_error)?;
+            let mut out = io::BufWriter::new(file);
+            count += table.dump(&tx, &mut out)?;
+            io::Write::flush(&mut out).map_err(dump_error)?;
+        }
+
+        Ok(count)
+    }
+
+    /// Copies the database into a new environment created in the `dest` directory, which must not
+    /// exist yet. The environment can keep being read and written to during the copy.
+    ///
+    /// The copy is made from a single read transaction, so it is a consistent snapshot of the
+    /// database at the time of the call. If `compact` is `true`, the free pages are omitted from
+    /// the copy, which makes it smaller but slower to produce.
+    pub fn copy(&self, dest: impl AsRef<Path>, compact: bool) -> Result<(), DatabaseError> {
+        let dest = dest.as_ref();
+        let error = |reason: String| DatabaseError::Copy { path: dest.to_path_buf(), reason };
+
+        if dest.exists() {
+            return Err(error("destination already exists".to_string()));
+        }
+
+        fs::create_dir_all(dest).map_err(|e| error(e.to_string()))?;
+        let data_file = dest.join(MDBX_DATA_FILE_NAME);
+        let data_file = CString::new(data_file.to_string_lossy().as_bytes())
+            .map_err(|e| error(e.to_string()))?;
+
+        let flags = if compact { ffi::MDBX_CP_COMPACT } else { ffi::MDBX_CP_DEFAULTS };
+        // SAFETY: the environment is kept alive by `self` for the whole call, and MDBX takes its
+        // own read transaction to copy from.
+        let code =
+            unsafe { ffi::mdbx_env_copy(self.inner.env.env_ptr(), data_file.as_ptr(), flags) };
+
+        if code == ffi::MDBX_SUCCESS {
+            Ok(())
+        } else {
+            Err(error(libmdbx::Error::from_err_code(code).to_string()))
+        }
+    }
+
+    /// Runs `f` in a read-write transaction and commits it, growing the database if it's full.
+    ///
+    /// When the transaction fails with `MDBX_MAP_FULL`, either while writing or on commit, the
+    /// upper bound of the database size is increased by the growth step and the transaction is
+    /// retried once. As MDBX aborts the transactions that fill the database up, the retry runs
+    /// `f` again in a new transaction. The number of grows over the lifetime of the environment
+    /// is capped by [`DbEnvConfig::max_auto_grows`], past which the error is returned as is.
+    pub fn update_growing<T>(
+        &self,
+        f: impl Fn(&Tx<RW>) -> Result<T, DatabaseError>,
+    ) -> Result<T, DatabaseError> {
+        let mut retried = false;
+
+        loop {
+            let tx = self.tx_mut()?;
+            let result = f(&tx).and_then(|value| tx.commit().map(|_| value));
+
+            match result {
+                Err(error) if !retried && is_map_full(&error) && self.grow()? => retried = true,
+                result => return result,
+            }
+        }
+    }
+
+    /// Increases the upper bound of the database size by the growth step, unless the maximum
+    /// number of automatic grows has been reached. Returns whether the database has grown.
+    fn grow(&self) -> Result<bool, DatabaseError> {
+        if self.inner.auto_grows.load(Ordering::SeqCst) >= self.inner.max_auto_grows {
+            return Ok(false);
+        }
+
+        let upper = self.size_bounds()?.end + self.inner.growth_step;
+        // SAFETY: the environment is kept alive by `self` for the whole call. Negative values
+        // keep the current settings.
+        let code = unsafe {
+            ffi::mdbx_env_set_geometry(self.inner.env.env_ptr(), -1, -1, upper as isize, -1, -1, -1)
+        };
+
+        if code != ffi::MDBX_SUCCESS {
+            return Err(DatabaseError::Grow(libmdbx::Error::from_err_code(code)));
+        }
+
+        self.inner.auto_grows.fetch_add(1, Ordering::SeqCst);
+        Ok(true)
+    }
+
+    /// Flushes the committed transactions to disk.
+    ///
+    /// This is meant for environments opened with a non-durable [`DbSyncMode`], to persist the
+    /// latest commits at checkpoints. With `force`, the call blocks until the data is flushed
+    /// with an fsync, otherwise the flush is only done if MDBX's sync thresholds are reached.
+    /// Under [`DbSyncMode::Durable`] every commit is already flushed, so there is nothing to do.
+    pub fn sync(&self, force: bool) -> Result<(), DatabaseError> {
+        self.inner.env.sync(force).map_err(DatabaseError::Sync)?;
+        Ok(())
+    }
+
+    /// Checks every entry of every table, from a single read transaction, and reports the
+    /// problems found.
+    ///
+    /// Each key and value is decoded with the codecs of its table, and each key is compared with
+    /// the previous one to check that the entries are in order. Unlike reading the tables through
+    /// the typed cursors, the walk doesn't stop at the first problem, so that the report lists all
+    /// the offending entries. This is meant to validate a database before trusting it, e.g. after
+    /// a crash.
+    pub fn verify_integrity(&self) -> Result<IntegrityReport, DatabaseError> {
+        let tx = self.inner.env.begin_ro_txn().map_err(DatabaseError::CreateROTx)?;
+        let mut report = IntegrityReport::default();
+
+        for table in Tables::ALL {
+            let db = tx.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?;
+            let mut cursor = tx.cursor(&db).map_err(DatabaseError::CreateCursor)?;
+
+            let mut previous: Option<Vec<u8>> = None;
+            let mut entry: Option<(Cow<'_, [u8]>, Cow<'_, [u8]>)> =
+                cursor.first().map_err(DatabaseError::Read)?;
+
+            while let Some((key, value)) = entry {
+                report.entries += 1;
+
+                if let Err(error) = table.decode_raw(&key, &value) {
+                    report.issues.push(IntegrityIssue {
+                        table: table.name(),
+                        key: key.to_vec(),
+                        kind: IntegrityIssueKind::Decode(error),
+                    });
+                }
+
+                if let Some(previous) = previous.take() {
+                    // Keys of an invalid length are already reported as failing to decode.
+                    let order = utils::compare_raw_keys(table.integer_key(), &previous, &key);
+                    if order.is_ok_and(|order| order.is_gt()) {
+                        report.issues.push(IntegrityIssue {
+                            table: table.name(),
+                            key: key.to_vec(),
+                            kind: IntegrityIssueKind::Order { previous },
+                        });
+                    }
+                }
+
+                previous = Some(key.into_owned());
+                entry = cursor.next().map_err(DatabaseError::Read)?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Clears the reader slots left behind by processes that exited without ending their read
+    /// transactions, and returns the number of slots cleared.
+    ///
+    /// Stale readers prevent MDBX from reusing the pages they pin, which makes the database grow.
+    /// This is cheap enough to be called periodically by the node.
+    pub fn check_readers(&self) -> Result<usize, DatabaseError> {
+        let mut dead = 0;
+        // SAFETY: the environment is kept alive by `self` for the whole call.
+        let code = unsafe { ffi::mdbx_reader_check(self.inner.env.env_ptr(), &mut dead) };
+
+        match code {
+            ffi::MDBX_SUCCESS | ffi::MDBX_RESULT_TRUE => Ok(dead as usize),
+            code => Err(DatabaseError::CheckReaders(libmdbx::Error::from_err_code(code))),
+        }
+    }
+
+    /// Prevents the creation of read-write transactions for as long as the returned guard is
+    /// alive, e.g. to make sure that a code path serving queries never writes.
+    ///
+    /// While a guard is held, [`Database::tx_mut`] fails with [`DatabaseError::ReadGuarded`].
+    /// Read-write transactions created before the guard are not affected.
+    pub fn read_guard(&self) -> ReadGuard {
+        self.inner.read_guards.fetch_add(1, Ordering::SeqCst);
+        ReadGuard { inner: self.inner.clone() }
+    }
+
+    fn ensure_not_maintenance(&self) -> Result<(), DatabaseError> {
+        if self.is_maintenance() {
+            Err(DatabaseError::Maintenance)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn ensure_writable(&self) -> Result<(), DatabaseError> {
+        self.ensure_not_maintenance()?;
+        if self.inner.read_guards.load(Ordering::SeqCst) > 0 {
+            return Err(DatabaseError::ReadGuarded);
+        }
+        Ok(())
+    }
+
+    /// Creates a read-write transaction like [`Database::tx_mut`], but returns `None` instead of
+    /// blocking if another read-write transaction of this environment is in progress, so that
+    /// the caller can back off and retry later.
+    ///
+    /// Only the transactions created from this environment, or its clones, are accounted for. A
+    /// read-write transaction of another process still blocks this call until it's done.
+    pub fn try_tx_mut(&self) -> Result<Option<Tx<RW>>, DatabaseError> {
+        self.ensure_writable()?;
+
+        let writers = &self.inner.writers;
+        if writers.compare_exchange(0, 1, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return Ok(None);
+        }
+
+        // Released on error, or along with the transaction.
+        let guard = WriterGuard(writers.clone());
+        let inner = self.inner.env.begin_rw_txn().map_err(DatabaseError::CreateRWTx)?;
+        self.init_tx_mut(inner, guard).map(Some)
+    }
+
+    /// Accounts for a read-write transaction of this environment until the returned guard is
+    /// dropped.
+    fn writer_guard(&self) -> WriterGuard {
+        self.inner.writers.fetch_add(1, Ordering::SeqCst);
+        WriterGuard(self.inner.writers.clone())
+    }
+
+    fn init_tx_mut(
+        &self,
+        inner: libmdbx::Transaction<RW>,
+        guard: WriterGuard,
+    ) -> Result<Tx<RW>, DatabaseError> {
+        let mut tx = Tx::new(inner).with_writer(guard);
+
+        if let Some(watchdog) = self.inner.watchdog.lock().as_ref() {
+            let txn_id = tx.inner.id().map_err(DatabaseError::Read)?;
+            tx = tx.with_watchdog(watchdog.watch(txn_id));
+        }
+
+        // Only track the mutated tables if someone is listening.
+        if self.inner.changefeed.has_subscribers() {
+            Ok(tx.with_changefeed(self.inner.changefeed.clone()))
+        } else {
+            Ok(tx)
+        }
+    }
+
+    /// Compacts the table `T` by rewriting all of its entries, in key order, into the emptied
+    /// table.
+    ///
+    /// Deleting entries leaves partially filled pages behind which are only merged once they fall
+    /// below MDBX's merge threshold. Rewriting the entries sequentially packs them densely again,
+    /// and the pages released by the table are returned to the freelist to be reused by later
+    /// writes. Unlike compacting copies of the whole environment, only `T` is touched.
+    ///
+    /// The table is rebuilt within a single write transaction, see [`Tx::rebuild_table`], which
+    /// is subject to the same checks as any other write transaction.
+    pub fn compact_table<T: Table>(&self) -> Result<(), DatabaseError> {
+        let table = Tables::from_str(T::NAME).map_err(|_| DatabaseError::UnknownTable(T::NAME))?;
+
+        let tx = self.tx_mut()?;
+        tx.rebuild_table(table, |key| Ok(key.to_vec()))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Logs a warning for every read-write transaction that stays open for longer than
+    /// `timeout`. Disabled by default.
+    pub fn with_write_timeout(self, timeout: Duration) -> Self {
+        *self.inner.watchdog.lock() = Some(WriteWatchdog::spawn(timeout, false));
+        self
+    }
+
+    /// Same as [`DbEnv::with_write_timeout`], but the timed out transactions are also aborted
+    /// when committed, failing with [`DatabaseError::WriteTimeout`].
+    pub fn with_write_timeout_abort(self, timeout: Duration) -> Self {
+        *self.inner.watchdog.lock() = Some(WriteWatchdog::spawn(timeout, true));
+        self
+    }
+
+    /// Returns the number of read-write transactions that have exceeded the write timeout.
+    pub fn timed_out_writes(&self) -> u64 {
+        self.inner.watchdog.lock().as_ref().map_or(0, |w| w.timed_out())
+    }
+
     fn with_metrics(self) -> Self {
         describe_gauge!("db.table_size", metrics::Unit::Bytes, "Total size of the table");
         describe_gauge!("db.table_pages", metrics::Unit::Count, "Number of pages in the table");
@@ -158,29 +784,58 @@ impl DbEnv {
     }
 }
 
+/// Guard returned by [`DbEnv::read_guard`]. Writes are allowed again once all the guards of the
+/// environment are dropped.
+#[derive(Debug)]
+#[must_use = "writes are only prevented while the guard is alive"]
+pub struct ReadGuard {
+    inner: Arc<DbEnvInner>,
+}
+
+impl Drop for ReadGuard {
+    fn drop(&mut self) {
+        self.inner.read_guards.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Accounts for a live read-write transaction in the `writers` count of its environment, until
+/// it's dropped along with the transaction.
+#[derive(Debug)]
+struct WriterGuard(Arc<AtomicUsize>);
+
+impl Drop for WriterGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 impl Database for DbEnv {
     type Tx = tx::Tx<RO>;
     type TxMut = tx::Tx<RW>;
     type Stats = stats::Stats;
 
     fn tx(&self) -> Result<Self::Tx, DatabaseError> {
+        self.ensure_not_maintenance()?;
         Ok(Tx::new(self.inner.env.begin_ro_txn().map_err(DatabaseError::CreateROTx)?))
     }
 
+    /// Creates a read-write transaction.
+    ///
+    /// MDBX allows a single read-write transaction at a time per environment, across threads and
+    /// processes. If another one is in progress, this call blocks until it is committed or
+    /// aborted, with no timeout. Use [`DbEnv::try_tx_mut`] to back off instead of waiting.
     fn tx_mut(&self) -> Result<Self::TxMut, DatabaseError> {
-        Ok(Tx::new(self.inner.env.begin_rw_txn().map_err(DatabaseError::CreateRWTx)?))
+        self.ensure_writable()?;
+        // Accounted for before beginning the transaction, which may block, so that
+        // `try_tx_mut` doesn't start waiting behind it in the meantime.
+        let guard = self.writer_guard();
+        let inner = self.inner.env.begin_rw_txn().map_err(DatabaseError::CreateRWTx)?;
+        self.init_tx_mut(inner, guard)
     }
 
     fn stats(&self) -> Result<Self::Stats, DatabaseError> {
         self.view(|tx| {
-            let mut table_stats = HashMap::with_capacity(NUM_TABLES);
-
-            for table in Tables::ALL.iter() {
-                let dbi = tx.inner.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?;
-                let stat = tx.inner.db_stat(&dbi).map_err(DatabaseError::GetStats)?;
-                table_stats.insert(table.name(), TableStat::new(stat));
-            }
-
+            let table_stats = tx.all_stats()?;
             let info = self.inner.env.info().map_err(DatabaseError::Stat)?;
             let freelist = self.inner.env.freelist().map_err(DatabaseError::Stat)?;
             Ok(Stats { table_stats, info, freelist })
@@ -238,7 +893,9 @@ impl dojo_metrics::Report for DbEnv {
 pub mod test_utils {
 
     use super::DbEnv;
+    use crate::codecs::Encode;
     use crate::init_ephemeral_db;
+    use crate::tables::Table;
 
     const ERROR_DB_CREATION: &str = "Not able to create the mdbx file.";
 
@@ -246,6 +903,70 @@ pub mod test_utils {
     pub fn create_test_db() -> DbEnv {
         init_ephemeral_db().expect(ERROR_DB_CREATION)
     }
+
+    /// Asserts that the encoding of the keys of table `T` preserves their natural ordering, ie.
+    /// that the keys are iterated by the database in the same order as they compare.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two keys are ordered differently than their encoded bytes.
+    pub fn assert_key_order_preserved<T: Table>(keys: &[T::Key])
+    where
+        T::Key: Ord,
+    {
+        let mut keys = keys.to_vec();
+        keys.sort();
+
+        let encoded =
+            keys.iter().map(|key| key.clone().encode().as_ref().to_vec()).collect::<Vec<_>>();
+
+        for (i, pair) in encoded.windows(2).enumerate() {
+            assert!(
+                crate::utils::compare_keys::<T>(&pair[0], &pair[1]).unwrap().is_le(),
+                "encoding of {} keys doesn't preserve ordering: {:?} < {:?} but {:?} > {:?}",
+                T::NAME,
+                keys[i],
+                keys[i + 1],
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+}
+
+/// Checks that `size` is a valid MDBX page size, ie. a power of two within `256..=65536` bytes,
+/// and returns it.
+fn validate_page_size(size: usize) -> Result<usize, DatabaseError> {
+    if size.is_power_of_two()
+        && (utils::LIBMDBX_MIN_PAGE_SIZE..=utils::LIBMDBX_MAX_PAGE_SIZE).contains(&size)
+    {
+        Ok(size)
+    } else {
+        Err(DatabaseError::InvalidPageSize(size))
+    }
+}
+
+/// Returns `true` if the error is caused by the database being full.
+fn is_map_full(error: &DatabaseError) -> bool {
+    matches!(
+        error,
+        DatabaseError::Write { error: libmdbx::Error::MapFull, .. }
+            | DatabaseError::Commit(libmdbx::Error::MapFull)
+    )
+}
+
+/// Returns the flags `table` is created with, and the flags to append entries to it in key order.
+fn table_flags(table: Tables) -> (DatabaseFlags, WriteFlags) {
+    let (mut db_flags, write_flags) = match table.table_type() {
+        TableType::Table | TableType::IntegerKey => (DatabaseFlags::default(), WriteFlags::APPEND),
+        TableType::DupSort => (DatabaseFlags::DUP_SORT, WriteFlags::APPEND_DUP),
+    };
+
+    if table.integer_key() {
+        db_flags |= DatabaseFlags::INTEGER_KEY;
+    }
+
+    (db_flags, write_flags)
 }
 
 impl Drop for DbEnv {
@@ -266,17 +987,24 @@ impl Drop for DbEnv {
 #[cfg(test)]
 mod tests {
 
+    use std::ops::Bound;
+
     use katana_primitives::block::Header;
     use katana_primitives::contract::{ContractAddress, GenericContractInfo};
     use katana_primitives::{address, Felt};
     use starknet::macros::felt;
 
     use super::*;
-    use crate::abstraction::{DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut, Walker};
-    use crate::codecs::Encode;
-    use crate::mdbx::test_utils::create_test_db;
+    use crate::abstraction::{
+        DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut, ReverseWalker, Walker,
+    };
+    use crate::codecs::{Compress, Decode, Encode};
+    use crate::error::CodecError;
+    use crate::mdbx::test_utils::{assert_key_order_preserved, create_test_db};
     use crate::models::storage::StorageEntry;
-    use crate::tables::{BlockHashes, ContractInfo, ContractStorage, Headers, Table};
+    use crate::tables::{
+        BlockHashes, CompiledClassHashes, ContractInfo, ContractStorage, Headers, Table,
+    };
 
     const ERROR_PUT: &str = "Not able to insert value into table.";
     const ERROR_DELETE: &str = "Failed to delete value from table.";
@@ -284,6 +1012,7 @@ mod tests {
     const ERROR_COMMIT: &str = "Not able to commit transaction.";
     const ERROR_RETURN_VALUE: &str = "Mismatching result.";
     const ERROR_UPSERT: &str = "Not able to upsert the value to the table.";
+    const ERROR_APPEND: &str = "Not able to append the value to the table.";
     const ERROR_INIT_TX: &str = "Failed to create a MDBX transaction.";
     const ERROR_INIT_CURSOR: &str = "Failed to create cursor.";
     const ERROR_GET_AT_CURSOR_POS: &str = "Failed to get value at cursor position.";
@@ -326,56 +1055,919 @@ mod tests {
     }
 
     #[test]
-    fn db_manual_put_get() {
+    fn db_compact_table() {
         let env = create_test_db();
 
-        let value = Header::default();
-        let key = 1u64;
+        // Fill the pages densely by appending sequential keys.
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        (0..3000u64).try_for_each(|key| cursor.append(key, Felt::from(key))).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
 
-        // PUT
+        // Delete churn: remove two out of every three keys, leaving sparse pages behind.
         let tx = env.tx_mut().expect(ERROR_INIT_TX);
-        tx.put::<Headers>(key, value.clone()).expect(ERROR_PUT);
+        for key in (0..3000u64).filter(|key| key % 3 != 0) {
+            tx.delete::<BlockHashes>(key, None).expect(ERROR_DELETE);
+        }
         tx.commit().expect(ERROR_COMMIT);
 
-        // GET
         let tx = env.tx().expect(ERROR_INIT_TX);
-        let result = tx.get::<Headers>(key).expect(ERROR_GET);
-        let total_entries = tx.entries::<Headers>().expect(ERROR_GET);
+        let before = tx.stat::<BlockHashes>().expect("failed to get table stat");
         tx.commit().expect(ERROR_COMMIT);
 
-        assert!(total_entries == 1);
-        assert!(result.expect(ERROR_RETURN_VALUE) == value);
+        env.compact_table::<BlockHashes>().expect("failed to compact table");
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let after = tx.stat::<BlockHashes>().expect("failed to get table stat");
+
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let rows = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        let expected = (0..3000u64).filter(|key| key % 3 == 0).map(|key| (key, Felt::from(key)));
+
+        assert_eq!(rows, expected.collect::<Vec<_>>());
+        assert_eq!(after.entries(), before.entries());
+        assert!(
+            after.leaf_pages() < before.leaf_pages(),
+            "compaction should reduce the number of leaf pages ({} >= {})",
+            after.leaf_pages(),
+            before.leaf_pages()
+        );
+
+        // Compacting is a write like any other.
+        let guard = env.read_guard();
+        assert_eq!(env.compact_table::<BlockHashes>().err(), Some(DatabaseError::ReadGuarded));
+        drop(guard);
+
+        env.set_maintenance(true);
+        assert_eq!(env.compact_table::<BlockHashes>().err(), Some(DatabaseError::Maintenance));
     }
 
     #[test]
-    fn db_delete() {
+    fn db_open_flags() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).expect("failed to open db");
+        let flags = env.open_flags();
+        assert!(!flags.read_only);
+        assert_eq!(flags.sync_mode, Some(DbSyncMode::Durable));
+        assert!(flags.no_rdahead);
+        assert!(flags.coalesce);
+        assert!(!flags.no_sub_dir);
+        assert!(!flags.exclusive);
+        env.create_tables().unwrap();
+        // MDBX doesn't allow opening the same environment twice within a process.
+        drop(env);
+
+        let env = DbEnv::open(dir.path(), DbEnvKind::RO).expect("failed to open db");
+        let flags = env.open_flags();
+        assert!(flags.read_only);
+        assert_eq!(flags.sync_mode, None);
+
         let env = create_test_db();
+        assert_eq!(env.open_flags().sync_mode, Some(DbSyncMode::UtterlyNoSync));
+    }
 
-        let value = Header::default();
-        let key = 1u64;
+    #[test]
+    fn db_open_with_extra_tables() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).expect("failed to open db");
+        env.create_tables().unwrap();
+        let tx = env.inner.env.begin_rw_txn().unwrap();
+        let result = tx.create_db(Some("ExtraTable"), DatabaseFlags::default());
+        assert!(matches!(result, Err(libmdbx::Error::DbsFull)));
+        drop(tx);
+        // MDBX doesn't allow opening the same environment twice within a process.
+        drop(env);
+
+        let config = DbEnvConfig { extra_tables: 1, ..Default::default() };
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config).unwrap();
+        env.create_tables().unwrap();
+        let tx = env.inner.env.begin_rw_txn().unwrap();
+        tx.create_db(Some("ExtraTable"), DatabaseFlags::default()).expect("table created");
+        tx.commit().unwrap();
+    }
+
+    #[test]
+    fn db_open_with_sync_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DbEnvConfig { sync_mode: DbSyncMode::UtterlyNoSync, ..Default::default() };
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config).unwrap();
+        env.create_tables().unwrap();
+        assert_eq!(env.open_flags().sync_mode, Some(DbSyncMode::UtterlyNoSync));
+
+        let header = Header { number: 1, parent_hash: felt!("0x1337"), ..Default::default() };
 
-        // PUT
         let tx = env.tx_mut().expect(ERROR_INIT_TX);
-        tx.put::<Headers>(key, value).expect(ERROR_PUT);
+        tx.put::<Headers>(1, header.clone()).expect(ERROR_PUT);
         tx.commit().expect(ERROR_COMMIT);
 
-        let entries = env.tx().expect(ERROR_INIT_TX).entries::<Headers>().expect(ERROR_GET);
-        assert!(entries == 1);
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(1).expect(ERROR_GET), Some(header));
+    }
+
+    #[test]
+    fn db_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = || DbEnvConfig { sync_mode: DbSyncMode::SafeNoSync, ..Default::default() };
+        let header = Header { number: 1, parent_hash: felt!("0x1337"), ..Default::default() };
+
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config()).unwrap();
+        env.create_tables().unwrap();
 
-        // DELETE
         let tx = env.tx_mut().expect(ERROR_INIT_TX);
-        tx.delete::<Headers>(key, None).expect(ERROR_DELETE);
+        tx.put::<Headers>(1, header.clone()).expect(ERROR_PUT);
         tx.commit().expect(ERROR_COMMIT);
+        env.sync(true).unwrap();
+        // MDBX doesn't allow opening the same environment twice within a process.
+        drop(env);
 
-        let entries = env.tx().expect(ERROR_INIT_TX).entries::<Headers>().expect(ERROR_GET);
-        assert!(entries == 0);
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config()).unwrap();
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(1).expect(ERROR_GET), Some(header));
     }
 
     #[test]
-    fn db_manual_cursor_walk() {
+    fn db_verify_integrity() {
         let env = create_test_db();
 
-        let key1 = 1u64;
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..5u64 {
+            tx.put::<Headers>(key, Header { number: key, ..Default::default() }).expect(ERROR_PUT);
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let report = env.verify_integrity().unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.entries, 10);
+
+        // Write a value that isn't a valid header, bypassing the codec.
+        let tx = env.inner.env.begin_rw_txn().unwrap();
+        let db = tx.open_db(Some(Headers::NAME)).unwrap();
+        tx.put(db.dbi(), 3u64.encode(), [0xff; 4], WriteFlags::UPSERT).unwrap();
+        tx.commit().unwrap();
+
+        let report = env.verify_integrity().unwrap();
+        assert_eq!(report.entries, 10);
+        assert_eq!(report.tables(), [Headers::NAME].into());
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].key, 3u64.encode().to_vec());
+        assert!(matches!(report.issues[0].kind, IntegrityIssueKind::Decode(_)));
+    }
+
+    #[test]
+    fn db_open_with_initial_size() {
+        const INITIAL_SIZE: usize = 256 * 1024 * 1024;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = DbEnvConfig { initial_size: INITIAL_SIZE, ..Default::default() };
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config).unwrap();
+
+        let bounds = env.size_bounds().unwrap();
+        assert_eq!(bounds.start, INITIAL_SIZE);
+        assert_eq!(bounds.end, TERABYTE);
+    }
+
+    #[test]
+    fn db_open_with_max_size() {
+        const MAX_SIZE: usize = 64 * 1024 * 1024;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = DbEnvConfig {
+            max_size: Some(MAX_SIZE),
+            growth_step: Some(4 * 1024 * 1024),
+            ..Default::default()
+        };
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config).unwrap();
+        env.create_tables().unwrap();
+        assert_eq!(env.size_bounds().unwrap().end, MAX_SIZE);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        let header = Header { parent_hash: felt!("0x1337"), ..Default::default() };
+        let error = (0..u64::MAX)
+            .find_map(|key| tx.put::<Headers>(key, header.clone()).err())
+            .expect("db is capped");
+        assert!(matches!(error, DatabaseError::Write { error: libmdbx::Error::MapFull, .. }));
+    }
+
+    #[test]
+    fn db_update_growing() {
+        const MAX_SIZE: usize = 4 * 1024 * 1024;
+        const GROWTH_STEP: usize = 2 * 1024 * 1024;
+        const BATCH: u64 = 1_000;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = DbEnvConfig {
+            max_size: Some(MAX_SIZE),
+            growth_step: Some(GROWTH_STEP),
+            max_auto_grows: 1,
+            ..Default::default()
+        };
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config).unwrap();
+        env.create_tables().unwrap();
+
+        let header = Header { parent_hash: felt!("0x1337"), ..Default::default() };
+        let mut written = 0;
+        let mut grown_at = None;
+
+        // Write until the database is full even after having grown.
+        let error = loop {
+            let res = env.update_growing(|tx| {
+                (written..written + BATCH)
+                    .try_for_each(|key| tx.put::<Headers>(key, header.clone()))
+            });
+
+            match res {
+                Ok(()) => written += BATCH,
+                Err(error) => break error,
+            }
+
+            if grown_at.is_none() && env.size_bounds().unwrap().end > MAX_SIZE {
+                grown_at = Some(written);
+            }
+        };
+
+        assert!(matches!(error, DatabaseError::Write { error: libmdbx::Error::MapFull, .. }));
+        assert_eq!(env.size_bounds().unwrap().end, MAX_SIZE + GROWTH_STEP);
+
+        // The batch that filled the database up was retried and committed, and more batches were
+        // written after the grow.
+        let grown_at = grown_at.expect("db should have grown");
+        assert!(written > grown_at);
+        assert_eq!(env.tx().unwrap().entries::<Headers>().unwrap(), written as usize);
+    }
+
+    #[test]
+    fn db_open_with_invalid_geometry() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = DbEnvConfig { growth_step: Some(1000), ..Default::default() };
+        let result = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config);
+        assert!(matches!(result, Err(DatabaseError::InvalidGeometry(_))));
+
+        let config = DbEnvConfig {
+            max_size: Some(10 * GIGABYTE),
+            growth_step: Some(4 * GIGABYTE),
+            ..Default::default()
+        };
+        let result = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config);
+        assert!(matches!(result, Err(DatabaseError::InvalidGeometry(_))));
+    }
+
+    #[test]
+    fn db_open_with_max_size_only() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = DbEnvConfig { max_size: Some(6 * GIGABYTE), ..Default::default() };
+        let geometry = config.geometry().unwrap();
+        assert_eq!(geometry.size, Some(0..6 * GIGABYTE));
+        assert_eq!(geometry.growth_step, Some(2 * GIGABYTE as isize));
+        DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config).unwrap();
+
+        let config = DbEnvConfig { max_size: Some(GIGABYTE / 2), ..Default::default() };
+        assert_eq!(config.geometry().unwrap().growth_step, Some(GIGABYTE as isize / 2));
+
+        let config = DbEnvConfig { max_size: Some(GIGABYTE + 1), ..Default::default() };
+        assert!(matches!(config.geometry(), Err(DatabaseError::InvalidGeometry(_))));
+    }
+
+    #[test]
+    fn db_open_with_page_size() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = DbEnvConfig { page_size: Some(16 * 1024), ..Default::default() };
+        let env = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config).unwrap();
+        assert_eq!(env.inner.env.stat().unwrap().page_size(), 16 * 1024);
+        drop(env);
+
+        // The page size of an existing database can't be changed.
+        let config = DbEnvConfig { page_size: Some(8 * 1024), ..Default::default() };
+        let result = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config);
+        assert!(matches!(
+            result,
+            Err(DatabaseError::PageSizeMismatch { requested: 8192, actual: 16384 })
+        ));
+    }
+
+    #[test]
+    fn db_open_with_invalid_page_size() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for page_size in [5000, 128, 128 * 1024] {
+            let config = DbEnvConfig { page_size: Some(page_size), ..Default::default() };
+            let result = DbEnv::open_with_config(dir.path(), DbEnvKind::RW, config);
+            assert!(
+                matches!(result, Err(DatabaseError::InvalidPageSize(size)) if size == page_size)
+            );
+        }
+    }
+
+    #[test]
+    fn db_maintenance_mode() {
+        let env = create_test_db();
+        let key = 1u64;
+        let value = Header::default();
+
+        // Transaction created before maintenance is enabled.
+        let in_flight = env.tx_mut().expect(ERROR_INIT_TX);
+
+        env.set_maintenance(true);
+        assert!(env.is_maintenance());
+        assert_eq!(env.tx().err(), Some(DatabaseError::Maintenance));
+        assert_eq!(env.tx_mut().err(), Some(DatabaseError::Maintenance));
+
+        // In-flight transactions are unaffected.
+        in_flight.put::<Headers>(key, value.clone()).expect(ERROR_PUT);
+        in_flight.commit().expect(ERROR_COMMIT);
+
+        env.set_maintenance(false);
+        assert!(!env.is_maintenance());
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(key).expect(ERROR_GET), Some(value));
+        env.tx_mut().expect(ERROR_INIT_TX);
+    }
+
+    #[test]
+    fn db_last_txn_id() {
+        let env = create_test_db();
+        let initial = env.last_txn_id().unwrap();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(1, Header::default()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let committed = env.last_txn_id().unwrap();
+        assert!(committed > initial);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        tx.get::<Headers>(1).expect(ERROR_GET);
+        tx.commit().expect(ERROR_COMMIT);
+        assert_eq!(env.last_txn_id().unwrap(), committed);
+    }
+
+    #[test]
+    fn db_env_info() {
+        let env = create_test_db();
+
+        let info = env.env_info().unwrap();
+        assert_eq!(info.last_transaction_id() as u64, env.last_txn_id().unwrap());
+        assert!(info.max_readers() >= DEFAULT_MAX_READERS as usize);
+
+        // Each thread holds a read transaction until the main thread has read the info.
+        let opened = Arc::new(std::sync::Barrier::new(3));
+        let checked = Arc::new(std::sync::Barrier::new(3));
+        let readers = (0..2)
+            .map(|_| {
+                let (env, opened, checked) = (env.clone(), opened.clone(), checked.clone());
+                std::thread::spawn(move || {
+                    let tx = env.tx().expect(ERROR_INIT_TX);
+                    opened.wait();
+                    checked.wait();
+                    drop(tx);
+                })
+            })
+            .collect::<Vec<_>>();
+
+        opened.wait();
+        assert!(env.env_info().unwrap().readers_in_use() >= 2);
+        checked.wait();
+
+        readers.into_iter().for_each(|reader| reader.join().unwrap());
+    }
+
+    /// Path of the database opened by the [`db_leak_reader`] helper.
+    const LEAK_READER_DB_PATH: &str = "KATANA_DB_LEAK_READER_PATH";
+
+    #[test]
+    #[ignore = "helper of `db_check_readers`, run in a child process"]
+    fn db_leak_reader() {
+        let Ok(path) = std::env::var(LEAK_READER_DB_PATH) else { return };
+
+        let env = DbEnv::open(path, DbEnvKind::RO).expect("failed to open db");
+        std::mem::forget(env.tx().expect(ERROR_INIT_TX));
+        // Exit without running any destructor, like a crashed process would.
+        std::process::exit(0);
+    }
+
+    #[test]
+    fn db_check_readers() {
+        // Removed on drop, after the environment.
+        let dir = tempfile::tempdir().unwrap();
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).expect("failed to open db");
+        env.create_tables().unwrap();
+        assert_eq!(env.check_readers().unwrap(), 0);
+
+        // Reader slots are only stale once their process is gone, so the reader is leaked by a
+        // child process.
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["mdbx::tests::db_leak_reader", "--exact", "--ignored", "--nocapture"])
+            .env(LEAK_READER_DB_PATH, dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        assert_eq!(env.check_readers().unwrap(), 1);
+        assert_eq!(env.check_readers().unwrap(), 0);
+    }
+
+    #[test]
+    fn db_clear_table() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..100u64 {
+            tx.put::<BlockHashes>(key, felt!("0x1337")).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.clear::<BlockHashes>().unwrap();
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.entries::<BlockHashes>().unwrap(), 0);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // Clearing an empty table is fine, and the table can still be written to.
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.clear::<BlockHashes>().unwrap();
+        tx.put::<BlockHashes>(1, felt!("0x1")).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.entries::<BlockHashes>().unwrap(), 1);
+        assert_eq!(tx.get::<BlockHashes>(1).expect(ERROR_GET), Some(felt!("0x1")));
+    }
+
+    #[test]
+    fn db_table_stat() {
+        const N: u64 = 100;
+
+        let env = create_test_db();
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..N {
+            tx.put::<Headers>(key, Header::default()).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let stat = env.table_stat(Tables::Headers).unwrap();
+        assert_eq!(stat.entries(), N as usize);
+        assert!(stat.depth() >= 1);
+        assert!(stat.leaf_pages() >= 1);
+
+        let stats = env.all_table_stats().unwrap();
+        assert_eq!(stats.len(), Tables::ALL.len());
+        assert_eq!(stats[Tables::Headers.name()].entries(), N as usize);
+        assert_eq!(stats[Tables::BlockHashes.name()].entries(), 0);
+    }
+
+    #[test]
+    fn db_copy() {
+        let env = create_test_db();
+        let headers = (0..10u64)
+            .map(|number| Header { number, parent_hash: felt!("0x1337"), ..Default::default() })
+            .collect::<Vec<_>>();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for header in &headers {
+            tx.put::<Headers>(header.number, header.clone()).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let dir = tempfile::tempdir().unwrap();
+        for compact in [false, true] {
+            let dest = dir.path().join(format!("copy-{compact}"));
+            env.copy(&dest, compact).unwrap();
+
+            let copy = DbEnv::open(&dest, DbEnvKind::RO).expect("failed to open copy");
+            let tx = copy.tx().expect(ERROR_INIT_TX);
+            assert_eq!(tx.entries::<Headers>().unwrap(), headers.len());
+            for header in &headers {
+                assert_eq!(
+                    tx.get::<Headers>(header.number).expect(ERROR_GET).as_ref(),
+                    Some(header)
+                );
+            }
+        }
+
+        let result = env.copy(dir.path(), false);
+        assert!(matches!(result, Err(DatabaseError::Copy { .. })));
+    }
+
+    #[test]
+    fn db_try_tx_mut() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        std::thread::scope(|s| {
+            let result = s.spawn(|| env.try_tx_mut().map(|tx| tx.is_some())).join().unwrap();
+            assert_eq!(result, Ok(false));
+        });
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.try_tx_mut().unwrap().expect("no transaction is in progress");
+        assert!(env.try_tx_mut().unwrap().is_none());
+        drop(tx);
+        assert!(env.try_tx_mut().unwrap().is_some());
+    }
+
+    #[test]
+    fn db_try_tx_mut_with_waiting_writer() {
+        let env = create_test_db();
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+
+        std::thread::scope(|s| {
+            let waiting =
+                s.spawn(|| env.tx_mut().expect(ERROR_INIT_TX).commit().expect(ERROR_COMMIT));
+
+            // The writer blocked behind `tx` is accounted for before it begins its transaction,
+            // so `try_tx_mut` backs off instead of blocking behind it as well.
+            let start = std::time::Instant::now();
+            while env.inner.writers.load(Ordering::SeqCst) < 2 {
+                assert!(start.elapsed() < Duration::from_secs(5), "waiting writer not accounted");
+                std::thread::yield_now();
+            }
+            assert!(env.try_tx_mut().unwrap().is_none());
+
+            tx.commit().expect(ERROR_COMMIT);
+            waiting.join().unwrap();
+        });
+
+        assert!(env.try_tx_mut().unwrap().is_some());
+    }
+
+    #[test]
+    fn db_read_guard() {
+        let env = create_test_db();
+
+        let guard = env.read_guard();
+        assert_eq!(env.tx_mut().err(), Some(DatabaseError::ReadGuarded));
+        // Reads are still allowed.
+        env.tx().expect(ERROR_INIT_TX);
+
+        drop(guard);
+        env.tx_mut().expect(ERROR_INIT_TX);
+    }
+
+    #[test]
+    fn db_changefeed() {
+        let env = create_test_db();
+        let receiver = env.subscribe();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(1, Header::default()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<BlockHashes>(1, felt!("0x1")).expect(ERROR_PUT);
+        tx.put::<Headers>(2, Header::default()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // Transactions without writes aren't reported, even if they opened write cursors.
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_mut::<ContractInfo>().expect(ERROR_INIT_CURSOR);
+        assert_eq!(cursor.first().expect(ERROR_GET_AT_CURSOR_POS), None);
+        drop(cursor);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // Writes done through a cursor are reported.
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_mut::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        cursor.upsert(2, felt!("0x2")).expect(ERROR_UPSERT);
+        drop(cursor);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let first = receiver.try_recv().expect("first commit event");
+        assert_eq!(first.tables, vec![Headers::NAME]);
+
+        let second = receiver.try_recv().expect("second commit event");
+        assert_eq!(second.tables, vec![Headers::NAME, BlockHashes::NAME]);
+        assert!(second.txn_id > first.txn_id);
+
+        let third = receiver.try_recv().expect("third commit event");
+        assert_eq!(third.tables, vec![BlockHashes::NAME]);
+
+        assert_eq!(receiver.try_recv(), None);
+        assert_eq!(receiver.lagged(), 0);
+    }
+
+    #[test]
+    fn db_env_identity() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_dir = dir.path().join("db");
+        std::fs::create_dir(&db_dir).unwrap();
+
+        // Open the environment through a non-canonical path.
+        let path = db_dir.join("..").join("db");
+        let env = DbEnv::open(&path, DbEnvKind::RW).expect("failed to open db");
+        assert_eq!(env.path(), path.as_path());
+        assert_eq!(env.canonical_path().unwrap(), db_dir.canonicalize().unwrap());
+
+        let handle = env.clone();
+        assert!(env.is_same_env(&handle));
+        assert_eq!(env.canonical_path().unwrap(), handle.canonical_path().unwrap());
+
+        let other = create_test_db();
+        assert!(!env.is_same_env(&other));
+        assert_ne!(env.canonical_path().unwrap(), other.canonical_path().unwrap());
+    }
+
+    #[test]
+    fn db_tx_all_stats() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..10u64 {
+            tx.put::<Headers>(key, Header::default()).expect(ERROR_PUT);
+        }
+        for key in 0..5u64 {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let stats = tx.all_stats().expect("failed to get stats");
+        assert_eq!(stats.len(), Tables::ALL.len());
+
+        let headers = tx.entries::<Headers>().unwrap();
+        let block_hashes = tx.entries::<BlockHashes>().unwrap();
+        assert_eq!(stats[Headers::NAME].entries(), headers);
+        assert_eq!(stats[BlockHashes::NAME].entries(), block_hashes);
+
+        let total = stats.values().map(|stat| stat.entries()).sum::<usize>();
+        assert_eq!(total, headers + block_hashes);
+        assert_eq!(total, 15);
+    }
+
+    #[test]
+    fn key_order_preserved() {
+        assert_key_order_preserved::<Headers>(&[0, 1, 255, 256, 65_535, 1 << 32, 7, u64::MAX]);
+        assert_key_order_preserved::<CompiledClassHashes>(&[
+            felt!("0x0"),
+            felt!("0x1"),
+            felt!("0xff"),
+            felt!("0x100"),
+            felt!("0x800000000000011000000000000000000000000000000000000000000000000"),
+            felt!("0x1234567890abcdef"),
+        ]);
+    }
+
+    #[test]
+    fn db_integer_key_walk() {
+        let env = create_test_db();
+        assert!(Tables::BlockHashes.integer_key());
+
+        let boundary = u32::MAX as u64;
+        let keys = [boundary + 1, 0, 1 << 40, boundary, 255, u64::MAX, 256, boundary - 1, 1];
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in keys {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let mut expected = keys.to_vec();
+        expected.sort();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let walked = cursor.walk(None).unwrap().map(|entry| entry.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(walked, expected);
+
+        let walked = tx
+            .walk_range::<BlockHashes>(255..=boundary + 1)
+            .unwrap()
+            .map(|entry| entry.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(walked, vec![255, 256, boundary - 1, boundary, boundary + 1]);
+    }
+
+    #[test]
+    fn db_migrate_integer_keys() {
+        use crate::tables::ClassDeclarations;
+        use crate::version::{create_db_version_file, get_db_version, CURRENT_DB_VERSION};
+
+        let dir = tempfile::tempdir().unwrap();
+        let keys = [1u64 << 40, 256, 0, 255, 1];
+
+        // Create a version 3 database: the tables have no `MDBX_INTEGERKEY` flag, and the integer
+        // keys are encoded in big-endian.
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).unwrap();
+        let tx = env.inner.env.begin_rw_txn().unwrap();
+        for table in Tables::ALL {
+            let flags = match table.table_type() {
+                TableType::DupSort => DatabaseFlags::DUP_SORT,
+                _ => DatabaseFlags::default(),
+            };
+            tx.create_db(Some(table.name()), flags).unwrap();
+        }
+
+        let db = tx.open_db(Some(BlockHashes::NAME)).unwrap();
+        for key in keys {
+            let value = Felt::from(key).compress();
+            tx.put(db.dbi(), key.to_be_bytes(), value, WriteFlags::UPSERT).unwrap();
+        }
+
+        let db = tx.open_db(Some(ClassDeclarations::NAME)).unwrap();
+        for (key, class_hash) in [(256u64, 2u64), (1, 3), (256, 1)] {
+            let value = Felt::from(class_hash).compress();
+            tx.put(db.dbi(), key.to_be_bytes(), value, WriteFlags::UPSERT).unwrap();
+        }
+
+        tx.commit().unwrap();
+        drop(env);
+        create_db_version_file(dir.path(), 3).unwrap();
+
+        let env = crate::init_db(dir.path()).unwrap();
+        assert_eq!(env.schema_version().unwrap(), CURRENT_DB_VERSION);
+        assert_eq!(get_db_version(dir.path()).unwrap(), CURRENT_DB_VERSION);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let stored = tx.get::<SchemaVersion>(SCHEMA_VERSION_KEY).expect(ERROR_GET);
+        assert_eq!(stored, Some(CURRENT_DB_VERSION));
+
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let entries = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        let mut expected = keys.map(|key| (key, Felt::from(key))).to_vec();
+        expected.sort();
+        assert_eq!(entries, expected);
+
+        let mut cursor = tx.cursor_dup::<ClassDeclarations>().expect(ERROR_INIT_CURSOR);
+        let entries = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        let expected = [(1, 3u64), (256, 1), (256, 2)].map(|(key, hash)| (key, Felt::from(hash)));
+        assert_eq!(entries, expected);
+
+        // Creating the tables would fail if they had other flags than those of version 4.
+        env.create_tables().unwrap();
+        assert!(env.verify_integrity().unwrap().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't preserve ordering")]
+    fn key_order_not_preserved() {
+        // Little endian encoding doesn't preserve the natural ordering.
+        struct LeKeys;
+        impl Table for LeKeys {
+            const NAME: &'static str = "LeKeys";
+            type Key = LeKey;
+            type Value = u64;
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct LeKey(u64);
+
+        impl Encode for LeKey {
+            type Encoded = [u8; 8];
+            fn encode(self) -> Self::Encoded {
+                self.0.to_le_bytes()
+            }
+        }
+
+        impl crate::codecs::Decode for LeKey {
+            fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, crate::error::CodecError> {
+                let bytes = bytes.as_ref().try_into().map_err(|_| {
+                    crate::error::CodecError::Decode("invalid key length".to_string())
+                })?;
+                Ok(Self(u64::from_le_bytes(bytes)))
+            }
+        }
+
+        assert_key_order_preserved::<LeKeys>(&[LeKey(1), LeKey(256)]);
+    }
+
+    /// Environment variable used to pass the database directory to the writer process of
+    /// [`db_refresh_geometry`].
+    const GROW_DB_DIR: &str = "KATANA_DB_TEST_GROW_DIR";
+    const GROW_DB_ENTRIES: u64 = 1_000;
+    /// Upper bound of the database size set by the writer process, one growth step above the
+    /// default one.
+    const GROW_DB_MAX_SIZE: usize = TERABYTE + 4 * GIGABYTE;
+
+    #[test]
+    fn db_refresh_geometry() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).expect("failed to open db");
+        env.create_tables().unwrap();
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(0, Header::default()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+        drop(env);
+
+        let reader = DbEnv::open(dir.path(), DbEnvKind::RO).expect("failed to open db");
+        assert_eq!(reader.refresh_geometry().unwrap(), TERABYTE);
+
+        // MDBX doesn't allow opening the same environment twice within a process, so the writes
+        // are done from a separate process running `db_refresh_geometry_writer`.
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "mdbx::tests::db_refresh_geometry_writer", "--ignored"])
+            .env(GROW_DB_DIR, dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success(), "writer process failed");
+
+        // The reader is remapped to the size set by the writer, without being reopened.
+        assert_eq!(reader.refresh_geometry().unwrap(), GROW_DB_MAX_SIZE);
+
+        let tx = reader.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.entries::<Headers>().unwrap(), GROW_DB_ENTRIES as usize);
+        assert!(tx.get::<Headers>(GROW_DB_ENTRIES - 1).expect(ERROR_GET).is_some());
+    }
+
+    #[test]
+    #[ignore = "run by `db_refresh_geometry` in a separate process"]
+    fn db_refresh_geometry_writer() {
+        let dir = std::env::var(GROW_DB_DIR)
+            .unwrap_or_else(|_| panic!("{GROW_DB_DIR} must be set, run `db_refresh_geometry`"));
+
+        // Grows the upper bound of the database size of the existing database.
+        let config = DbEnvConfig { max_size: Some(GROW_DB_MAX_SIZE), ..Default::default() };
+        let env = DbEnv::open_with_config(dir, DbEnvKind::RW, config).expect("failed to open db");
+        assert_eq!(env.size_bounds().unwrap().end, GROW_DB_MAX_SIZE);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        let header = Header { parent_hash: felt!("0x1337"), ..Default::default() };
+        for key in 1..GROW_DB_ENTRIES {
+            tx.put::<Headers>(key, header.clone()).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+    }
+
+    #[test]
+    fn db_tx_prefetch() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..100u64 {
+            tx.put::<Headers>(key, Header { number: key, ..Default::default() }).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.prefetch::<Headers>(..).unwrap(), 100);
+        assert_eq!(tx.prefetch::<Headers>(10..20).unwrap(), 10);
+        assert_eq!(tx.prefetch::<Headers>(10..=20).unwrap(), 11);
+        assert_eq!(tx.prefetch::<Headers>(90..).unwrap(), 10);
+        assert_eq!(tx.prefetch::<BlockHashes>(..).unwrap(), 0);
+
+        for key in 0..100u64 {
+            let header = tx.get::<Headers>(key).expect(ERROR_GET).expect(ERROR_RETURN_VALUE);
+            assert_eq!(header.number, key);
+        }
+    }
+
+    #[test]
+    fn db_manual_put_get() {
+        let env = create_test_db();
+
+        let value = Header::default();
+        let key = 1u64;
+
+        // PUT
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(key, value.clone()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // GET
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let result = tx.get::<Headers>(key).expect(ERROR_GET);
+        let total_entries = tx.entries::<Headers>().expect(ERROR_GET);
+        tx.commit().expect(ERROR_COMMIT);
+
+        assert!(total_entries == 1);
+        assert!(result.expect(ERROR_RETURN_VALUE) == value);
+    }
+
+    #[test]
+    fn db_delete() {
+        let env = create_test_db();
+
+        let value = Header::default();
+        let key = 1u64;
+
+        // PUT
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(key, value).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let entries = env.tx().expect(ERROR_INIT_TX).entries::<Headers>().expect(ERROR_GET);
+        assert!(entries == 1);
+
+        // DELETE
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.delete::<Headers>(key, None).expect(ERROR_DELETE);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let entries = env.tx().expect(ERROR_INIT_TX).entries::<Headers>().expect(ERROR_GET);
+        assert!(entries == 0);
+    }
+
+    #[test]
+    fn db_manual_cursor_walk() {
+        let env = create_test_db();
+
+        let key1 = 1u64;
         let key2 = 2u64;
         let key3 = 3u64;
         let header1 = Header::default();
@@ -437,6 +2029,213 @@ mod tests {
         assert_eq!(dup_cursor.next_dup_val(), Ok(Some(entry2)));
     }
 
+    #[test]
+    fn db_cursor_sees_tx_writes() {
+        let env = create_test_db();
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(1, Header::default()).expect(ERROR_PUT);
+        tx.put::<Headers>(2, Header::default()).expect(ERROR_PUT);
+
+        let mut cursor = tx.cursor::<Headers>().expect(ERROR_INIT_CURSOR);
+        assert_eq!(cursor.first().unwrap().map(|(key, _)| key), Some(1));
+
+        // Written through the transaction, not the cursor, which MDBX keeps in sync.
+        tx.put::<Headers>(3, Header::default()).expect(ERROR_PUT);
+
+        assert_eq!(cursor.current().unwrap().map(|(key, _)| key), Some(1));
+        assert_eq!(cursor.next().unwrap().map(|(key, _)| key), Some(2));
+        assert_eq!(cursor.next().unwrap().map(|(key, _)| key), Some(3));
+        assert_eq!(cursor.next().unwrap(), None);
+    }
+
+    #[test]
+    fn db_cursor_walk_back() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..5u64 {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+
+        fn keys<C: DbCursor<BlockHashes>>(walker: ReverseWalker<'_, BlockHashes, C>) -> Vec<u64> {
+            walker.map(|entry| entry.unwrap().0).collect()
+        }
+
+        assert_eq!(keys(cursor.walk_back(None).unwrap()), vec![4, 3, 2, 1, 0]);
+        assert_eq!(keys(cursor.walk_back(Some(2)).unwrap()), vec![2, 1, 0]);
+        // Starts from the last key below a missing start key.
+        assert_eq!(keys(cursor.walk_back(Some(10)).unwrap()), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn db_cursor_seek() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in [0u64, 5, 10] {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+
+        // Nearest key greater than or equal to the target.
+        assert_eq!(cursor.seek(0).unwrap(), Some((0, Felt::from(0u64))));
+        assert_eq!(cursor.seek(3).unwrap(), Some((5, Felt::from(5u64))));
+        assert_eq!(cursor.seek(6).unwrap(), Some((10, Felt::from(10u64))));
+        assert_eq!(cursor.seek(11).unwrap(), None);
+
+        // Exact match only.
+        assert_eq!(cursor.seek_exact(5).unwrap(), Some((5, Felt::from(5u64))));
+        assert_eq!(cursor.seek_exact(3).unwrap(), None);
+        assert_eq!(cursor.seek_exact(11).unwrap(), None);
+
+        // The cursor is positioned at the found key.
+        assert_eq!(cursor.seek_exact(5).unwrap(), Some((5, Felt::from(5u64))));
+        assert_eq!(cursor.next().unwrap(), Some((10, Felt::from(10u64))));
+    }
+
+    #[test]
+    fn db_walk_range() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..10u64 {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        fn keys(walker: cursor::RangeWalker<RO, BlockHashes>) -> Vec<u64> {
+            walker.map(|entry| entry.unwrap().0).collect()
+        }
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(keys(tx.walk_range::<BlockHashes>(2..5).unwrap()), vec![2, 3, 4]);
+        assert_eq!(keys(tx.walk_range::<BlockHashes>(2..=5).unwrap()), vec![2, 3, 4, 5]);
+        assert_eq!(keys(tx.walk_range::<BlockHashes>(..3).unwrap()), vec![0, 1, 2]);
+        assert_eq!(keys(tx.walk_range::<BlockHashes>(8..).unwrap()), vec![8, 9]);
+        assert_eq!(keys(tx.walk_range::<BlockHashes>(20..).unwrap()), vec![]);
+
+        let range = (Bound::Excluded(2), Bound::Included(4));
+        assert_eq!(keys(tx.walk_range::<BlockHashes>(range).unwrap()), vec![3, 4]);
+    }
+
+    #[test]
+    fn db_get_many() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in (0..10u64).step_by(2) {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let values = tx.get_many::<BlockHashes>(&[8, 3, 0, 42, 4, 8]).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Some(Felt::from(8u64)),
+                None,
+                Some(Felt::ZERO),
+                None,
+                Some(Felt::from(4u64)),
+                Some(Felt::from(8u64))
+            ]
+        );
+        assert_eq!(tx.get_many::<BlockHashes>(&[]).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn db_put_many() {
+        const ROWS: u64 = 10_000;
+
+        fn entries() -> impl Iterator<Item = (u64, Felt)> {
+            (0..ROWS).map(|key| (key, Felt::from(key * 2)))
+        }
+
+        fn contents(env: &DbEnv) -> Vec<(u64, Felt)> {
+            let tx = env.tx().expect(ERROR_INIT_TX);
+            let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+            cursor.walk(None).unwrap().map(|entry| entry.unwrap()).collect()
+        }
+
+        let naive = create_test_db();
+        let batched = create_test_db();
+        let appended = create_test_db();
+
+        let tx = naive.tx_mut().expect(ERROR_INIT_TX);
+        for (key, value) in entries() {
+            tx.put::<BlockHashes>(key, value).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = batched.tx_mut().expect(ERROR_INIT_TX);
+        assert_eq!(tx.put_many::<BlockHashes>(entries()).expect(ERROR_PUT), ROWS as usize);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = appended.tx_mut().expect(ERROR_INIT_TX);
+        assert_eq!(tx.append_many::<BlockHashes>(entries()).expect(ERROR_APPEND), ROWS as usize);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let expected = contents(&naive);
+        assert_eq!(expected.len(), ROWS as usize);
+        assert_eq!(contents(&batched), expected);
+        assert_eq!(contents(&appended), expected);
+
+        // Appending keys lower than the last key of the table fails.
+        let tx = appended.tx_mut().expect(ERROR_INIT_TX);
+        let res = tx.append_many::<BlockHashes>([(0, Felt::ZERO)]);
+        assert!(matches!(res, Err(DatabaseError::Write { .. })));
+    }
+
+    #[test]
+    fn db_append() {
+        const ROWS: u64 = 500;
+
+        fn raw_contents(env: &DbEnv) -> Vec<(Vec<u8>, Vec<u8>)> {
+            let tx = env.tx().expect(ERROR_INIT_TX);
+            let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+            cursor
+                .walk_raw()
+                .map(|entry| entry.map(|(key, value)| (key.into_owned(), value.into_owned())))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        }
+
+        let put = create_test_db();
+        let appended = create_test_db();
+
+        let tx = put.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..ROWS {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = appended.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..ROWS {
+            tx.append::<BlockHashes>(key, Felt::from(key)).expect(ERROR_APPEND);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let expected = raw_contents(&put);
+        assert_eq!(expected.len(), ROWS as usize);
+        assert_eq!(raw_contents(&appended), expected);
+
+        // Keys that aren't greater than the last key are rejected.
+        let tx = appended.tx_mut().expect(ERROR_INIT_TX);
+        for key in [ROWS - 1, 0] {
+            let res = tx.append::<BlockHashes>(key, Felt::ZERO);
+            assert!(matches!(res, Err(DatabaseError::KeyMismatch { table: "BlockHashes", .. })));
+        }
+        tx.append::<BlockHashes>(ROWS, Felt::ZERO).expect(ERROR_APPEND);
+    }
+
     #[test]
     fn db_cursor_walk() {
         let env = create_test_db();
@@ -462,6 +2261,37 @@ mod tests {
         assert_eq!(first.1, value, "First next should be put value");
     }
 
+    #[test]
+    fn db_cursor_split_ranges() {
+        let env = create_test_db();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        assert!(cursor.split_ranges(4).unwrap().is_empty());
+        drop(cursor);
+        drop(tx);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..100u64 {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+
+        let ranges = cursor.split_ranges(4).unwrap();
+        assert_eq!(ranges, vec![0..=24, 25..=49, 50..=74, 75..=99]);
+
+        // Ranges must cover every key exactly once.
+        let keys = ranges.into_iter().flatten().collect::<Vec<_>>();
+        assert_eq!(keys, (0..100).collect::<Vec<_>>());
+
+        // Can't split into more ranges than there are keys.
+        assert_eq!(cursor.split_ranges(200).unwrap().len(), 100);
+        assert!(cursor.split_ranges(0).unwrap().is_empty());
+    }
+
     #[test]
     fn db_walker() {
         let db = create_test_db();
@@ -519,6 +2349,527 @@ mod tests {
         tx.commit().expect(ERROR_COMMIT);
     }
 
+    #[test]
+    fn db_cursor_walk_raw() {
+        let env = create_test_db();
+
+        let entries = (1..=3u64).map(|key| (key, Felt::from(key * 10))).collect::<Vec<_>>();
+        env.update(|tx| {
+            for (key, value) in &entries {
+                tx.put::<BlockHashes>(*key, *value).expect(ERROR_PUT);
+            }
+        })
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let raw = cursor
+            .walk_raw()
+            .map(|entry| entry.map(|(key, value)| (key.into_owned(), value.into_owned())))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let expected = entries
+            .into_iter()
+            .map(|(key, value)| (key.encode().to_vec(), value.compress()))
+            .collect::<Vec<_>>();
+        assert_eq!(raw, expected);
+    }
+
+    #[test]
+    fn db_export_tables() {
+        let env = create_test_db();
+
+        env.update(|tx| {
+            for key in 0..3u64 {
+                tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+                tx.put::<Headers>(key, Header::default()).expect(ERROR_PUT);
+            }
+        })
+        .unwrap();
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let dest = env.export_tables(dir.path(), &[Tables::BlockHashes]).unwrap();
+
+        let tx = dest.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let entries = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, (0..3u64).map(|key| (key, Felt::from(key))).collect::<Vec<_>>());
+
+        // The other tables aren't created.
+        assert_eq!(
+            tx.get::<Headers>(0),
+            Err(DatabaseError::TableNotInitialized { table: Headers::NAME })
+        );
+    }
+
+    #[test]
+    fn db_dump_table() {
+        let env = create_test_db();
+
+        env.update(|tx| {
+            for key in 0..3u64 {
+                tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+            }
+        })
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut out = Vec::new();
+        assert_eq!(tx.dump_table::<BlockHashes, _>(&mut out).unwrap(), 3);
+
+        let lines = String::from_utf8(out).unwrap();
+        let lines = lines.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+
+        for (key, line) in (0..3u64).zip(lines) {
+            let line: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(line["key"], hex::encode(key.encode()));
+            assert_eq!(line["value"], serde_json::to_value(Felt::from(key)).unwrap());
+        }
+
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(env.dump_all(dir.path()).unwrap(), 3);
+
+        for table in Tables::ALL {
+            let dump = fs::read_to_string(dir.path().join(format!("{table}.ndjson"))).unwrap();
+            let expected = if table == Tables::BlockHashes { 3 } else { 0 };
+            assert_eq!(dump.lines().count(), expected, "{table}");
+        }
+    }
+
+    #[test]
+    fn db_load_table() {
+        let env = create_test_db();
+
+        let headers = (0..10u64)
+            .map(|number| (number, Header { number, timestamp: number * 2, ..Default::default() }))
+            .collect::<Vec<_>>();
+
+        env.update(|tx| {
+            for (number, header) in headers.clone() {
+                tx.put::<Headers>(number, header).expect(ERROR_PUT);
+            }
+        })
+        .unwrap();
+
+        let mut dump = Vec::new();
+        env.view(|tx| tx.dump_table::<Headers, _>(&mut dump)).unwrap().unwrap();
+
+        env.update(|tx| tx.clear::<Headers>()).unwrap().unwrap();
+        assert_eq!(env.tx().unwrap().entries::<Headers>().unwrap(), 0);
+
+        let loaded = env.update(|tx| tx.load_table::<Headers, _>(dump.as_slice())).unwrap();
+        assert_eq!(loaded.unwrap(), 10);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<Headers>().expect(ERROR_INIT_CURSOR);
+        let entries = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, headers);
+    }
+
+    #[test]
+    fn db_load_table_malformed_line() {
+        let env = create_test_db();
+        let key = hex::encode(1u64.encode());
+
+        let input = format!("{{\"key\":\"{key}\",\"value\":\"0x1\"}}\n\nnot json\n");
+        let result = env.update(|tx| tx.load_table::<BlockHashes, _>(input.as_bytes())).unwrap();
+        assert!(matches!(result, Err(DatabaseError::Load { table: "BlockHashes", line: 3, .. })));
+
+        let input = format!("{{\"key\":\"{}\",\"value\":\"0x1\"}}\n", &key[2..]);
+        let result = env.update(|tx| tx.load_table::<BlockHashes, _>(input.as_bytes())).unwrap();
+        assert!(matches!(result, Err(DatabaseError::Load { table: "BlockHashes", line: 1, .. })));
+    }
+
+    #[test]
+    fn db_get_or_insert_with() {
+        let env = create_test_db();
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+
+        let value = tx.get_or_insert_with::<BlockHashes>(1, || felt!("0x1")).unwrap();
+        assert_eq!(value, felt!("0x1"));
+        assert_eq!(tx.get::<BlockHashes>(1).expect(ERROR_GET), Some(felt!("0x1")));
+
+        let value =
+            tx.get_or_insert_with::<BlockHashes>(1, || panic!("the key already exists")).unwrap();
+        assert_eq!(value, felt!("0x1"));
+
+        tx.commit().expect(ERROR_COMMIT);
+        assert_eq!(env.tx().unwrap().get::<BlockHashes>(1).expect(ERROR_GET), Some(felt!("0x1")));
+    }
+
+    #[test]
+    fn db_snapshot() {
+        let env = create_test_db();
+        env.update(|tx| tx.put::<BlockHashes>(1, felt!("0x1"))).unwrap().unwrap();
+
+        let snapshot = env.snapshot().unwrap();
+        env.update(|tx| tx.put::<BlockHashes>(1, felt!("0x2"))).unwrap().unwrap();
+
+        // The snapshot still reads the state it was taken at.
+        let value = snapshot.view(|tx| tx.get::<BlockHashes>(1)).unwrap().unwrap();
+        assert_eq!(value, Some(felt!("0x1")));
+        assert_eq!(env.tx().unwrap().get::<BlockHashes>(1).unwrap(), Some(felt!("0x2")));
+
+        // Once released, it can't be reopened at a state that is gone.
+        snapshot.release();
+        assert!(!snapshot.is_open());
+        assert_eq!(
+            snapshot.view(|_| ()),
+            Err(DatabaseError::SnapshotExpired {
+                txn_id: snapshot.txn_id(),
+                latest: snapshot.txn_id() + 1
+            })
+        );
+
+        // But it is reopened lazily if nothing was written in the meantime.
+        let snapshot = env.snapshot().unwrap();
+        snapshot.release();
+        let value = snapshot.view(|tx| tx.get::<BlockHashes>(1)).unwrap().unwrap();
+        assert_eq!(value, Some(felt!("0x2")));
+        assert!(snapshot.is_open());
+    }
+
+    #[test]
+    fn db_table_not_initialized() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).expect("failed to open db");
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(
+            tx.get::<BlockHashes>(0),
+            Err(DatabaseError::TableNotInitialized { table: BlockHashes::NAME })
+        );
+        assert_eq!(
+            tx.table_stat(Tables::Headers).map(|_| ()),
+            Err(DatabaseError::TableNotInitialized { table: Headers::NAME })
+        );
+    }
+
+    #[test]
+    fn db_write_timeout() {
+        let env = create_test_db().with_write_timeout(Duration::from_millis(20));
+
+        // Transactions finishing in time aren't reported.
+        env.update(|tx| tx.put::<BlockHashes>(0, felt!("1")).expect(ERROR_PUT)).unwrap();
+        assert_eq!(env.timed_out_writes(), 0);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<BlockHashes>(1, felt!("1")).expect(ERROR_PUT);
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(env.timed_out_writes(), 1);
+
+        // The transaction is only reported, not aborted.
+        tx.commit().expect(ERROR_COMMIT);
+        assert_eq!(env.tx().unwrap().get::<BlockHashes>(1).unwrap(), Some(felt!("1")));
+    }
+
+    #[test]
+    fn db_tx_abort() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(1, Header::default()).expect(ERROR_PUT);
+        tx.abort().unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<Headers>(1).expect(ERROR_GET), None);
+    }
+
+    #[test]
+    fn db_prune() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..100u64 {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        assert_eq!(tx.prune::<BlockHashes>(50).unwrap(), 50);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let keys = cursor.walk(None).unwrap().map(|entry| entry.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys, (50..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn db_write_timeout_abort() {
+        let env = create_test_db().with_write_timeout_abort(Duration::from_millis(20));
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<BlockHashes>(1, felt!("1")).expect(ERROR_PUT);
+        std::thread::sleep(Duration::from_millis(200));
+
+        assert_eq!(env.timed_out_writes(), 1);
+        assert_eq!(tx.commit(), Err(DatabaseError::WriteTimeout));
+        assert_eq!(env.tx().unwrap().get::<BlockHashes>(1).unwrap(), None);
+
+        // The next transactions aren't affected.
+        env.update(|tx| tx.put::<BlockHashes>(1, felt!("1")).expect(ERROR_PUT)).unwrap();
+        assert_eq!(env.tx().unwrap().get::<BlockHashes>(1).unwrap(), Some(felt!("1")));
+    }
+
+    #[test]
+    fn db_dup_sort_walk_yields_all_duplicates() {
+        let env = create_test_db();
+        let key1 = address!("0x1");
+        let key2 = address!("0x2");
+
+        let entry = |key: u64| StorageEntry { key: Felt::from(key), value: felt!("1") };
+
+        env.update(|tx| {
+            tx.put::<ContractStorage>(key2, entry(1)).expect(ERROR_PUT);
+            tx.put::<ContractStorage>(key1, entry(3)).expect(ERROR_PUT);
+            tx.put::<ContractStorage>(key1, entry(1)).expect(ERROR_PUT);
+            tx.put::<ContractStorage>(key2, entry(0)).expect(ERROR_PUT);
+            tx.put::<ContractStorage>(key1, entry(2)).expect(ERROR_PUT);
+        })
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<ContractStorage>().expect(ERROR_INIT_CURSOR);
+        let items = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                (key1, entry(1)),
+                (key1, entry(2)),
+                (key1, entry(3)),
+                (key2, entry(0)),
+                (key2, entry(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn db_cursor_delete_current_while_walking() {
+        let env = create_test_db();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..10u64 {
+            tx.put::<BlockHashes>(key, Felt::from(key)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_mut::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let mut walker = cursor.walk(None).unwrap();
+        while let Some((key, _)) = walker.next().transpose().unwrap() {
+            if key % 2 == 0 {
+                walker.delete_current().unwrap();
+            }
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+        let keys = cursor.walk(None).unwrap().map(|entry| entry.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys, vec![1, 3, 5, 7, 9]);
+    }
+
+    #[test]
+    fn db_cursor_delete_current_duplicates() {
+        let env = create_test_db();
+        let key1 = address!("0x1");
+        let key2 = address!("0x2");
+
+        let entry = |key: u64| StorageEntry { key: Felt::from(key), value: felt!("1") };
+
+        env.update(|tx| {
+            for key in [key1, key2] {
+                for subkey in [1, 2, 3] {
+                    tx.put::<ContractStorage>(key, entry(subkey)).expect(ERROR_PUT);
+                }
+            }
+        })
+        .unwrap();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup_mut::<ContractStorage>().expect(ERROR_INIT_CURSOR);
+
+        // Only the current duplicate is deleted.
+        assert_eq!(cursor.seek_by_key_subkey(key1, Felt::from(2u64)).unwrap(), Some(entry(2)));
+        cursor.delete_current().unwrap();
+        // All the duplicates of the key are deleted.
+        cursor.set(key2).unwrap();
+        cursor.delete_current_duplicates().unwrap();
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<ContractStorage>().expect(ERROR_INIT_CURSOR);
+        let items = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(items, vec![(key1, entry(1)), (key1, entry(3))]);
+    }
+
+    #[test]
+    fn db_dup_sort_navigation() {
+        let env = create_test_db();
+        let key1 = address!("0x1");
+        let key2 = address!("0x2");
+
+        let entry = |key: u64| StorageEntry { key: Felt::from(key), value: Felt::from(key * 10) };
+
+        env.update(|tx| {
+            for key in [key1, key2] {
+                for subkey in [1, 3, 5] {
+                    tx.put::<ContractStorage>(key, entry(subkey)).expect(ERROR_PUT);
+                }
+            }
+        })
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup::<ContractStorage>().expect(ERROR_INIT_CURSOR);
+
+        // Duplicates of the same key.
+        assert_eq!(cursor.first().unwrap(), Some((key1, entry(1))));
+        assert_eq!(cursor.next_dup().unwrap(), Some((key1, entry(3))));
+        assert_eq!(cursor.next_dup().unwrap(), Some((key1, entry(5))));
+        assert_eq!(cursor.next_dup().unwrap(), None);
+        assert_eq!(cursor.prev_dup().unwrap(), Some((key1, entry(3))));
+        assert_eq!(cursor.prev_dup().unwrap(), Some((key1, entry(1))));
+        assert_eq!(cursor.prev_dup().unwrap(), None);
+
+        // Skips the remaining duplicates.
+        assert_eq!(cursor.first().unwrap(), Some((key1, entry(1))));
+        assert_eq!(cursor.next_no_dup().unwrap(), Some((key2, entry(1))));
+        assert_eq!(cursor.next_no_dup().unwrap(), None);
+
+        // First duplicate whose subkey is greater than or equal to the given one.
+        assert_eq!(cursor.seek_by_key_subkey(key2, Felt::from(3u64)).unwrap(), Some(entry(3)));
+        assert_eq!(cursor.seek_by_key_subkey(key2, Felt::from(4u64)).unwrap(), Some(entry(5)));
+        assert_eq!(cursor.seek_by_key_subkey(key2, Felt::from(6u64)).unwrap(), None);
+
+        // Only the duplicates of the given key are walked.
+        let walker = cursor.walk_dup(Some(key1), None).unwrap().expect("key exists");
+        let items = walker.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(items, vec![(key1, entry(1)), (key1, entry(3)), (key1, entry(5))]);
+    }
+
+    #[test]
+    fn db_get_ref() {
+        let env = create_test_db();
+        let header = Header { number: 1, timestamp: 1_700_000_000, ..Default::default() };
+        env.update(|tx| tx.put::<Headers>(1, header.clone())).unwrap().unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let len = tx.get_ref::<Headers, _>(1, |bytes| bytes.len()).unwrap();
+        assert_eq!(len, Some(header.compress().len()));
+        assert_eq!(tx.get_ref::<Headers, _>(2, |bytes| bytes.len()).unwrap(), None);
+    }
+
+    #[test]
+    fn db_walker_take() {
+        let env = create_test_db();
+        env.update(|tx| {
+            tx.append_many::<BlockHashes>((0..1000u64).map(|key| (key, Felt::from(key))))
+        })
+        .unwrap()
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+
+        let walker = cursor.walk(None).unwrap();
+        let entries = walker.take(3).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, (0..3u64).map(|key| (key, Felt::from(key))).collect::<Vec<_>>());
+
+        // `Take` stops without calling `next` once `n` entries are yielded, so the cursor isn't
+        // advanced past the last entry taken.
+        assert_eq!(cursor.current().unwrap(), Some((2, Felt::from(2u64))));
+
+        assert_eq!(cursor.walk(Some(998)).unwrap().take(5).count(), 2);
+        assert_eq!(cursor.walk(None).unwrap().take(0).count(), 0);
+    }
+
+    #[test]
+    fn db_cursor_last_prev() {
+        let env = create_test_db();
+        env.update(|tx| (0..5u64).try_for_each(|key| tx.put::<BlockHashes>(key, Felt::from(key))))
+            .unwrap()
+            .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor::<BlockHashes>().expect(ERROR_INIT_CURSOR);
+
+        let mut keys = Vec::new();
+        let mut entry = cursor.last().expect(ERROR_GET_AT_CURSOR_POS);
+        while let Some((key, value)) = entry {
+            assert_eq!(value, Felt::from(key));
+            keys.push(key);
+            entry = cursor.prev().expect(ERROR_GET_AT_CURSOR_POS);
+        }
+
+        assert_eq!(keys, vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn db_entries() {
+        let env = create_test_db();
+        env.update(|tx| {
+            tx.append_many::<BlockHashes>((0..1000u64).map(|key| (key, Felt::from(key))))
+        })
+        .unwrap()
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.entries::<BlockHashes>().unwrap(), 1000);
+        // The count is read from the table's statistics rather than by walking it.
+        assert_eq!(tx.stat::<BlockHashes>().unwrap().entries(), 1000);
+    }
+
+    #[test]
+    fn db_entries_dup_sort() {
+        let env = create_test_db();
+
+        env.update(|tx| {
+            for subkey in 1..=3u64 {
+                let entry = StorageEntry { key: Felt::from(subkey), value: Felt::ONE };
+                tx.put::<ContractStorage>(address!("0x1"), entry).expect(ERROR_PUT);
+            }
+            let entry = StorageEntry { key: Felt::ONE, value: Felt::ONE };
+            tx.put::<ContractStorage>(address!("0x2"), entry).expect(ERROR_PUT);
+        })
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.entries::<ContractStorage>().unwrap(), 4);
+        assert_eq!(tx.entries_including_dups::<ContractStorage>().unwrap(), 4);
+        assert_eq!(tx.distinct_keys::<ContractStorage>().unwrap(), 2);
+    }
+
+    #[test]
+    fn db_dup_count() {
+        let env = create_test_db();
+        let key1 = address!("0x1");
+        let key2 = address!("0x2");
+
+        env.update(|tx| {
+            for subkey in 1..=3u64 {
+                let entry = StorageEntry { key: Felt::from(subkey), value: Felt::ONE };
+                tx.put::<ContractStorage>(key1, entry).expect(ERROR_PUT);
+            }
+            let entry = StorageEntry { key: Felt::ONE, value: Felt::ONE };
+            tx.put::<ContractStorage>(key2, entry).expect(ERROR_PUT);
+        })
+        .unwrap();
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_dup::<ContractStorage>().expect(ERROR_INIT_CURSOR);
+        assert_eq!(cursor.dup_count(), Err(DatabaseError::CursorNotPositioned));
+
+        cursor.seek(key1).unwrap();
+        assert_eq!(cursor.dup_count().unwrap(), 3);
+        cursor.seek(key2).unwrap();
+        assert_eq!(cursor.dup_count().unwrap(), 1);
+    }
+
     #[test]
     fn db_dup_sort() {
         let env = create_test_db();
@@ -562,4 +2913,42 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn db_put_rejects_oversized_key_encoding() {
+        #[derive(Debug, Clone)]
+        struct OversizedKey;
+
+        impl Encode for OversizedKey {
+            type Encoded = Vec<u8>;
+            const ENCODED_LEN: Option<usize> = Some(8);
+            fn encode(self) -> Self::Encoded {
+                vec![0; 9]
+            }
+        }
+
+        impl Decode for OversizedKey {
+            fn decode<B: AsRef<[u8]>>(_: B) -> Result<Self, CodecError> {
+                Ok(OversizedKey)
+            }
+        }
+
+        #[derive(Debug)]
+        struct Oversized;
+
+        impl Table for Oversized {
+            const NAME: &'static str = "Oversized";
+            type Key = OversizedKey;
+            type Value = Felt;
+        }
+
+        let env = create_test_db();
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+
+        let result = tx.put::<Oversized>(OversizedKey, felt!("1"));
+        assert_eq!(
+            result,
+            Err(DatabaseError::Encode { table: "Oversized", expected: 8, actual: 9 })
+        );
+    }
 }
@@ -0,0 +1,73 @@
+//! Reusable read-only snapshots of the database.
+
+use libmdbx::RO;
+use parking_lot::Mutex;
+
+use super::tx::Tx;
+use super::DbEnv;
+use crate::abstraction::Database;
+use crate::error::DatabaseError;
+
+/// A consistent view of the database at a given transaction id, see [`DbEnv::snapshot`].
+///
+/// Unlike a plain read-only [`Tx`], a snapshot can release its underlying transaction while it
+/// isn't being queried, e.g. before an await point, so that the pages it reads don't stay pinned
+/// for the whole duration of a long analytical read. The transaction is reopened lazily by the
+/// next query.
+///
+/// MDBX can only open a read transaction at the latest committed state, so a snapshot can only
+/// be reopened if no read-write transaction has been committed since it was taken. Otherwise the
+/// query fails with [`DatabaseError::SnapshotExpired`] rather than reading data from a different
+/// state, and a new snapshot must be taken. As long as the snapshot isn't released, it keeps
+/// reading the state it was taken at, regardless of the writes committed in the meantime.
+#[derive(Debug)]
+pub struct Snapshot {
+    env: DbEnv,
+    /// The id of the transaction the snapshot was taken at.
+    txn_id: u64,
+    /// The transaction used for the queries, unless it has been released.
+    tx: Mutex<Option<Tx<RO>>>,
+}
+
+impl Snapshot {
+    pub(super) fn new(env: DbEnv) -> Result<Self, DatabaseError> {
+        let tx = env.tx()?;
+        let txn_id = tx.inner.id().map_err(DatabaseError::Read)?;
+        Ok(Self { env, txn_id, tx: Mutex::new(Some(tx)) })
+    }
+
+    /// Returns the id of the transaction the snapshot was taken at.
+    pub fn txn_id(&self) -> u64 {
+        self.txn_id
+    }
+
+    /// Runs `f` with a read-only transaction at the state the snapshot was taken at.
+    ///
+    /// Reopens the transaction if it has been released, which fails with
+    /// [`DatabaseError::SnapshotExpired`] if the database has been written to since.
+    pub fn view<T>(&self, f: impl FnOnce(&Tx<RO>) -> T) -> Result<T, DatabaseError> {
+        let mut tx = self.tx.lock();
+
+        if tx.is_none() {
+            let new = self.env.tx()?;
+            let latest = new.inner.id().map_err(DatabaseError::Read)?;
+            if latest != self.txn_id {
+                return Err(DatabaseError::SnapshotExpired { txn_id: self.txn_id, latest });
+            }
+            *tx = Some(new);
+        }
+
+        Ok(f(tx.as_ref().expect("transaction is open")))
+    }
+
+    /// Releases the underlying transaction, which unpins the pages it reads. It is reopened by
+    /// the next call to [`Snapshot::view`].
+    pub fn release(&self) {
+        self.tx.lock().take();
+    }
+
+    /// Returns `true` if the underlying transaction is open.
+    pub fn is_open(&self) -> bool {
+        self.tx.lock().is_some()
+    }
+}
@@ -57,6 +57,48 @@ impl TableStat {
     }
 }
 
+/// Environment-wide information, independent of the tables.
+///
+/// A wrapper over MDBX's environment [Info](libmdbx::Info).
+pub struct EnvInfo(Info);
+
+impl EnvInfo {
+    /// Creates a new EnvInfo instance
+    pub(super) fn new(info: Info) -> Self {
+        Self(info)
+    }
+
+    /// Size of the mapped memory region, in bytes.
+    #[inline]
+    pub fn map_size(&self) -> usize {
+        self.0.map_size()
+    }
+
+    /// Last used page number.
+    #[inline]
+    pub fn last_page_number(&self) -> usize {
+        self.0.last_pgno()
+    }
+
+    /// Id of the last committed transaction.
+    #[inline]
+    pub fn last_transaction_id(&self) -> usize {
+        self.0.last_txnid()
+    }
+
+    /// Number of reader slots currently in use.
+    #[inline]
+    pub fn readers_in_use(&self) -> usize {
+        self.0.num_readers()
+    }
+
+    /// Maximum number of reader slots of the environment.
+    #[inline]
+    pub fn max_readers(&self) -> usize {
+        self.0.max_readers()
+    }
+}
+
 /// Statistics for the entire MDBX environment.
 pub struct Stats {
     /// Statistics for individual tables in the environment
@@ -145,6 +187,18 @@ impl std::fmt::Debug for TableStat {
     }
 }
 
+impl std::fmt::Debug for EnvInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvInfo")
+            .field("map_size", &self.map_size())
+            .field("last_page_number", &self.last_page_number())
+            .field("last_transaction_id", &self.last_transaction_id())
+            .field("readers_in_use", &self.readers_in_use())
+            .field("max_readers", &self.max_readers())
+            .finish()
+    }
+}
+
 impl std::fmt::Debug for Stats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Stats")
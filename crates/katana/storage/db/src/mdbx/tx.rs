@@ -1,18 +1,37 @@
 //! Transaction wrapper for libmdbx-sys.
 
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::ops::{Bound, RangeBounds};
 use std::str::FromStr;
+use std::sync::Arc;
 
+use katana_primitives::block::BlockNumber;
 use libmdbx::ffi::DBI;
 use libmdbx::{TransactionKind, WriteFlags, RW};
 use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::info;
 
-use super::cursor::Cursor;
+use super::changefeed::{Changefeed, CommitEvent, MutatedTables};
+use super::cursor::{Cursor, RangeWalker};
+use super::instrument::{self, Operation};
 use super::stats::TableStat;
-use crate::abstraction::{DbTx, DbTxMut};
-use crate::codecs::{Compress, Encode};
+use super::watchdog::WriteGuard;
+use super::WriterGuard;
+use crate::abstraction::{DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
+use crate::codecs::{Compress, Decode, Encode};
 use crate::error::DatabaseError;
 use crate::tables::{DupSort, Table, Tables, NUM_TABLES};
-use crate::utils::decode_one;
+use crate::utils::{compare_keys, compare_raw_keys, compress_value, decode_one, encode_key};
+
+const LOG_TARGET: &str = "db::tx";
+
+/// Number of entries between two progress logs of [`Tx::rebuild_table`].
+const REBUILD_PROGRESS_INTERVAL: usize = 1_000_000;
 
 /// Alias for read-only transaction.
 pub type TxRO = Tx<libmdbx::RO>;
@@ -28,12 +47,52 @@ pub struct Tx<K: TransactionKind> {
     pub(super) inner: libmdbx::Transaction<K>,
     /// Database table handle cache.
     db_handles: RwLock<[Option<DBI>; NUM_TABLES]>,
+    /// The changefeed notified when the transaction is committed.
+    changefeed: Option<Arc<Changefeed>>,
+    /// Tables mutated by the transaction and its cursors. Only tracked if there is a changefeed.
+    mutated: Option<Arc<MutatedTables>>,
+    /// Set if the transaction is watched by the write timeout watchdog.
+    watchdog: Option<WriteGuard>,
+    /// Set for read-write transactions, to account for them in their environment.
+    writer: Option<WriterGuard>,
 }
 
 impl<K: TransactionKind> Tx<K> {
     /// Creates new `Tx` object with a `RO` or `RW` transaction.
     pub fn new(inner: libmdbx::Transaction<K>) -> Self {
-        Self { inner, db_handles: Default::default() }
+        Self {
+            inner,
+            db_handles: Default::default(),
+            changefeed: None,
+            mutated: None,
+            watchdog: None,
+            writer: None,
+        }
+    }
+
+    /// Publishes a [`CommitEvent`] to the `changefeed` once the transaction is committed.
+    pub(super) fn with_changefeed(mut self, changefeed: Arc<Changefeed>) -> Self {
+        self.changefeed = Some(changefeed);
+        self.mutated = Some(Default::default());
+        self
+    }
+
+    /// Accounts for the transaction as a live writer of its environment until it's dropped.
+    pub(super) fn with_writer(mut self, guard: WriterGuard) -> Self {
+        self.writer = Some(guard);
+        self
+    }
+
+    /// Keeps the transaction watched by the write timeout watchdog until it's dropped.
+    pub(super) fn with_watchdog(mut self, guard: WriteGuard) -> Self {
+        self.watchdog = Some(guard);
+        self
+    }
+
+    fn mark_mutated<T: Table>(&self) {
+        if let Some(mutated) = &self.mutated {
+            mutated.mark::<T>();
+        }
     }
 
     pub fn get_dbi<T: Table>(&self) -> Result<DBI, DatabaseError> {
@@ -43,18 +102,240 @@ impl<K: TransactionKind> Tx<K> {
         let dbi_handle = handles.get_mut(table as usize).expect("should exist");
         if dbi_handle.is_none() {
             *dbi_handle =
-                Some(self.inner.open_db(Some(T::NAME)).map_err(DatabaseError::OpenDb)?.dbi());
+                Some(self.inner.open_db(Some(T::NAME)).map_err(open_db_error(T::NAME))?.dbi());
         }
 
         Ok(dbi_handle.expect("is some; qed"))
     }
 
+    /// Calls `f` with the raw, compressed value of `key` in table `T`, if any, and returns its
+    /// result.
+    ///
+    /// Unlike [`DbTx::get`], the value isn't decompressed, which avoids allocating a large value,
+    /// e.g. a receipt or a trace, when only its size or a part of it is needed. The bytes are
+    /// those of the memory map, so they are only valid for the duration of the call and can't be
+    /// kept around after `f` returns; copy them if needed. Within a read-write transaction, a
+    /// value modified by the transaction is copied before being handed to `f`, as its page can
+    /// be moved by later writes.
+    pub fn get_ref<T: Table, R>(
+        &self,
+        key: T::Key,
+        f: impl FnOnce(&[u8]) -> R,
+    ) -> Result<Option<R>, DatabaseError> {
+        let key = Encode::encode(key);
+        let value: Option<Cow<'_, [u8]>> =
+            self.inner.get(self.get_dbi::<T>()?, key.as_ref()).map_err(DatabaseError::Read)?;
+        Ok(value.map(|value| f(&value)))
+    }
+
     /// Retrieves statistics for a specific table.
     pub fn stat<T: Table>(&self) -> Result<TableStat, DatabaseError> {
         let dbi = self.get_dbi::<T>()?;
         let stat = self.inner.db_stat_with_dbi(dbi).map_err(DatabaseError::Stat)?;
         Ok(TableStat::new(stat))
     }
+
+    /// Warms up the OS page cache with the pages of table `T` by sequentially reading the entries
+    /// whose keys are within `range`, and returns the number of entries read.
+    ///
+    /// This can be used before a latency sensitive batch of reads. The values aren't decoded, but
+    /// every page they span is touched so that large values stored in overflow pages are loaded
+    /// as well.
+    pub fn prefetch<T: Table>(
+        &self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<usize, DatabaseError> {
+        let encode = |key: &T::Key| -> Vec<u8> { key.clone().encode().into() };
+        let start = range.start_bound().map(encode);
+        let end = range.end_bound().map(encode);
+
+        let dbi = self.get_dbi::<T>()?;
+        // The page size of the environment, which isn't necessarily the default one.
+        let page_size = self.inner.db_stat_with_dbi(dbi).map_err(DatabaseError::Stat)?.page_size();
+        let mut cursor = self.inner.cursor_with_dbi(dbi).map_err(DatabaseError::CreateCursor)?;
+
+        let mut entry: Option<(Cow<'_, [u8]>, Cow<'_, [u8]>)> = match &start {
+            Bound::Included(key) | Bound::Excluded(key) => cursor.set_range(key),
+            Bound::Unbounded => cursor.first(),
+        }
+        .map_err(DatabaseError::Read)?;
+
+        let mut count = 0;
+
+        while let Some((key, value)) = entry {
+            let past_end = match &end {
+                Bound::Included(end) => compare_keys::<T>(&key, end)?.is_gt(),
+                Bound::Excluded(end) => compare_keys::<T>(&key, end)?.is_ge(),
+                Bound::Unbounded => false,
+            };
+
+            if past_end {
+                break;
+            }
+
+            if !matches!(&start, Bound::Excluded(start) if key.as_ref() == start.as_slice()) {
+                // Read a byte from every page spanned by the value.
+                std::hint::black_box(
+                    value.iter().step_by(page_size as usize).fold(0u8, |acc, b| acc ^ b),
+                );
+                count += 1;
+            }
+
+            entry = cursor.next().map_err(DatabaseError::Read)?;
+        }
+
+        Ok(count)
+    }
+
+    /// Walks over the entries of table `T` whose keys are within `range`, in ascending key order.
+    ///
+    /// The cursor is positioned at the start bound directly, and the walk stops at the first key
+    /// past the end bound, so only the entries within the range are read.
+    pub fn walk_range<T: Table>(
+        &self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<K, T>, DatabaseError> {
+        let encode = |key: &T::Key| -> Vec<u8> { key.clone().encode().into() };
+        let start = range.start_bound().map(encode);
+        let end = range.end_bound().map(encode);
+
+        let cursor = DbTx::cursor::<T>(self)?;
+        Ok(RangeWalker::new(cursor, start, end))
+    }
+
+    /// Retrieves the values of the given `keys` of table `T`, with `None` for the keys which don't
+    /// exist. The values are returned in the order of `keys`.
+    ///
+    /// The keys are looked up in the order they are sorted in the table, with a single cursor,
+    /// which keeps the lookups of nearby keys on the pages already loaded.
+    pub fn get_many<T: Table>(
+        &self,
+        keys: &[T::Key],
+    ) -> Result<Vec<Option<T::Value>>, DatabaseError> {
+        let mut keys = keys.iter().map(|key| key.clone().encode()).enumerate().collect::<Vec<_>>();
+        let mut sorted = Ok(());
+        keys.sort_by(|(_, a), (_, b)| {
+            compare_keys::<T>(a.as_ref(), b.as_ref()).unwrap_or_else(|error| {
+                sorted = Err(error);
+                Ordering::Equal
+            })
+        });
+        sorted?;
+
+        let mut cursor = self
+            .inner
+            .cursor_with_dbi(self.get_dbi::<T>()?)
+            .map_err(DatabaseError::CreateCursor)?;
+
+        let mut values = (0..keys.len()).map(|_| None).collect::<Vec<_>>();
+        for (index, key) in keys {
+            let value: Option<Cow<'_, [u8]>> =
+                cursor.set(key.as_ref()).map_err(DatabaseError::Read)?;
+            values[index] = value.map(decode_one::<T>).transpose()?;
+        }
+
+        Ok(values)
+    }
+
+    /// Returns the number of key/value pairs of the dup-sort table `T`, ie. every duplicate of a
+    /// key counts as an entry. See [`Tx::distinct_keys`] for the number of keys.
+    ///
+    /// This is the same count as [`DbTx::entries`], which is defined for every table, as MDBX's
+    /// statistics include the duplicates. So it also takes constant time.
+    pub fn entries_including_dups<T: DupSort>(&self) -> Result<usize, DatabaseError> {
+        DbTx::entries::<T>(self)
+    }
+
+    /// Returns the number of distinct keys of the dup-sort table `T`, whereas
+    /// [`Tx::entries_including_dups`] also counts their duplicates.
+    ///
+    /// MDBX doesn't keep track of the number of distinct keys, so the table is walked, skipping
+    /// the duplicates of each key, which takes time linear in the number of keys.
+    pub fn distinct_keys<T: DupSort>(&self) -> Result<usize, DatabaseError> {
+        let mut cursor = DbTx::cursor_dup::<T>(self)?;
+        let mut entry = cursor.first()?;
+        let mut count = 0;
+
+        while entry.is_some() {
+            count += 1;
+            entry = cursor.next_no_dup()?;
+        }
+
+        Ok(count)
+    }
+
+    /// Retrieves the statistics of the given table.
+    pub fn table_stat(&self, table: Tables) -> Result<TableStat, DatabaseError> {
+        let dbi = self.inner.open_db(Some(table.name())).map_err(open_db_error(table.name()))?;
+        let stat = self.inner.db_stat(&dbi).map_err(DatabaseError::GetStats)?;
+        Ok(TableStat::new(stat))
+    }
+
+    /// Retrieves the statistics of all the tables, as seen by this transaction.
+    ///
+    /// Unlike collecting the stats of each table from separate transactions, the returned stats
+    /// all reflect the same point in time.
+    pub fn all_stats(&self) -> Result<HashMap<&'static str, TableStat>, DatabaseError> {
+        let mut stats = HashMap::with_capacity(NUM_TABLES);
+
+        for table in Tables::ALL {
+            stats.insert(table.name(), self.table_stat(table)?);
+        }
+
+        Ok(stats)
+    }
+
+    /// Writes all the entries of table `T` to `out` as newline-delimited JSON, and returns the
+    /// number of entries written.
+    ///
+    /// Each line is an object with the hex-encoded key of the entry, as stored in the table, and
+    /// its JSON-serialized value, e.g. `{"key":"0100000000000000","value":"0x1"}`. The entries
+    /// are written in key order. This is meant for inspecting the content of a table, e.g. to
+    /// attach it to a bug report.
+    pub fn dump_table<T: Table, W: Write>(&self, out: &mut W) -> Result<usize, DatabaseError>
+    where
+        T::Value: Serialize,
+    {
+        let dump_error = |reason: String| DatabaseError::Dump { table: T::NAME, reason };
+
+        let mut cursor = DbTx::cursor::<T>(self)?;
+        let mut entry = cursor.first()?;
+        let mut count = 0;
+
+        while let Some((key, value)) = entry {
+            let line = DumpEntry { key: hex::encode(key.encode()), value };
+            serde_json::to_writer(&mut *out, &line).map_err(|e| dump_error(e.to_string()))?;
+            out.write_all(b"\n").map_err(|e| dump_error(e.to_string()))?;
+            count += 1;
+            entry = cursor.next()?;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Maps the error of opening the database of `table`, which doesn't exist if the tables haven't
+/// been created, to [`DatabaseError::TableNotInitialized`].
+fn open_db_error(table: &'static str) -> impl FnOnce(libmdbx::Error) -> DatabaseError {
+    move |error| match error {
+        libmdbx::Error::NotFound => DatabaseError::TableNotInitialized { table },
+        error => DatabaseError::OpenDb(error),
+    }
+}
+
+/// Logs the progress of [`Tx::rebuild_table`] every [`REBUILD_PROGRESS_INTERVAL`] entries.
+fn log_rebuild_progress(table: Tables, stage: &str, entries: usize) {
+    if entries % REBUILD_PROGRESS_INTERVAL == 0 {
+        info!(target: LOG_TARGET, table = table.name(), entries, "{stage} table entries for rebuild.");
+    }
+}
+
+/// A line of a table dump, see [`Tx::dump_table`].
+#[derive(Debug, Serialize, Deserialize)]
+struct DumpEntry<V> {
+    /// The hex-encoded key.
+    key: String,
+    value: V,
 }
 
 impl<K: TransactionKind> DbTx for Tx<K> {
@@ -64,26 +345,30 @@ impl<K: TransactionKind> DbTx for Tx<K> {
     fn cursor<T: Table>(&self) -> Result<Cursor<K, T>, DatabaseError> {
         self.inner
             .cursor_with_dbi(self.get_dbi::<T>()?)
-            .map(Cursor::new)
+            .map(|cursor| Cursor::new(cursor).with_mutated(self.mutated.clone()))
             .map_err(DatabaseError::CreateCursor)
     }
 
     fn cursor_dup<T: DupSort>(&self) -> Result<Cursor<K, T>, DatabaseError> {
         self.inner
             .cursor_with_dbi(self.get_dbi::<T>()?)
-            .map(Cursor::new)
+            .map(|cursor| Cursor::new(cursor).with_mutated(self.mutated.clone()))
             .map_err(DatabaseError::CreateCursor)
     }
 
     fn get<T: Table>(&self, key: T::Key) -> Result<Option<<T as Table>::Value>, DatabaseError> {
-        let key = Encode::encode(key);
-        self.inner
-            .get(self.get_dbi::<T>()?, key.as_ref())
-            .map_err(DatabaseError::Read)?
-            .map(decode_one::<T>)
-            .transpose()
+        instrument::record(Operation::Get, Some(T::NAME), || {
+            let key = Encode::encode(key);
+            self.inner
+                .get(self.get_dbi::<T>()?, key.as_ref())
+                .map_err(DatabaseError::Read)?
+                .map(decode_one::<T>)
+                .transpose()
+        })
     }
 
+    /// Reads the number of entries from the table's statistics, without walking it, so it takes
+    /// constant time regardless of the size of the table.
     fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
         self.inner
             .db_stat_with_dbi(self.get_dbi::<T>()?)
@@ -92,11 +377,296 @@ impl<K: TransactionKind> DbTx for Tx<K> {
     }
 
     fn commit(self) -> Result<bool, DatabaseError> {
-        self.inner.commit().map_err(DatabaseError::Commit)
+        // Dropping the transaction aborts it.
+        if self.watchdog.as_ref().is_some_and(|w| w.must_abort()) {
+            return Err(DatabaseError::WriteTimeout);
+        }
+
+        let Some(changefeed) = self.changefeed else {
+            return instrument::record(Operation::Commit, None, || self.inner.commit())
+                .map_err(DatabaseError::Commit);
+        };
+
+        let tables = self.mutated.as_ref().map(|mutated| mutated.names()).unwrap_or_default();
+
+        let txn_id = self.inner.id().map_err(DatabaseError::Read)?;
+        let result = instrument::record(Operation::Commit, None, || self.inner.commit())
+            .map_err(DatabaseError::Commit)?;
+
+        if !tables.is_empty() {
+            changefeed.publish(CommitEvent { txn_id, tables });
+        }
+
+        Ok(result)
     }
 
-    fn abort(self) {
-        drop(self.inner)
+    fn abort(self) -> Result<(), DatabaseError> {
+        // Dropping the inner transaction calls `mdbx_txn_abort`.
+        drop(self.inner);
+        Ok(())
+    }
+}
+
+impl Tx<RW> {
+    /// Inserts all the `entries` into table `T`, replacing the values of existing keys, and
+    /// returns the number of entries inserted.
+    ///
+    /// A single cursor is used for the whole batch, which avoids the overhead of calling
+    /// [`DbTxMut::put`] for every entry.
+    pub fn put_many<T: Table>(
+        &self,
+        entries: impl IntoIterator<Item = (T::Key, T::Value)>,
+    ) -> Result<usize, DatabaseError> {
+        let mut cursor = DbTxMut::cursor_mut::<T>(self)?;
+        let mut count = 0;
+
+        for (key, value) in entries {
+            cursor.upsert(key, value)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Creates `table` if it doesn't exist, with the flags of its definition, and returns its
+    /// handle.
+    pub(crate) fn create_table(&self, table: Tables) -> Result<libmdbx::Database, DatabaseError> {
+        let (db_flags, _) = super::table_flags(table);
+        let db = self
+            .inner
+            .create_db(Some(table.name()), db_flags)
+            .map_err(DatabaseError::CreateTable)?;
+        self.db_handles.write()[table as usize] = Some(db.dbi());
+        Ok(db)
+    }
+
+    /// Recreates `table` with the flags of its current definition, and writes its entries back
+    /// with their keys converted by `rekey`. Returns the number of entries written back.
+    ///
+    /// This is meant for migrations changing the encoding of the keys of a table, as MDBX
+    /// doesn't allow changing the flags of an existing table, e.g. `MDBX_INTEGERKEY`. The table
+    /// is created if it doesn't exist. No cursor of the table must be open.
+    ///
+    /// As the table has to be dropped before being recreated, all of its entries are loaded in
+    /// memory in between, so rebuilding a table needs about as much memory as the size of its
+    /// entries. The progress is logged every [`REBUILD_PROGRESS_INTERVAL`] entries.
+    pub(crate) fn rebuild_table(
+        &self,
+        table: Tables,
+        rekey: impl Fn(&[u8]) -> Result<Vec<u8>, DatabaseError>,
+    ) -> Result<usize, DatabaseError> {
+        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+
+        match self.inner.open_db(Some(table.name())) {
+            Ok(db) => {
+                let stat = self.inner.db_stat_with_dbi(db.dbi()).map_err(DatabaseError::Stat)?;
+                info!(
+                    target: LOG_TARGET,
+                    table = table.name(),
+                    entries = stat.entries(),
+                    "Rebuilding table."
+                );
+
+                entries.reserve(stat.entries());
+                {
+                    let mut cursor = self.inner.cursor(&db).map_err(DatabaseError::CreateCursor)?;
+                    let mut entry = cursor.first().map_err(DatabaseError::Read)?;
+                    while let Some((key, value)) = entry {
+                        entries.push((rekey(&key)?, value));
+                        log_rebuild_progress(table, "Read", entries.len());
+                        entry = cursor.next().map_err(DatabaseError::Read)?;
+                    }
+                }
+
+                // SAFETY: the cursor above is dropped, and the handle of the table cached by
+                // this transaction is replaced below, so the dropped handle isn't used again.
+                unsafe { self.inner.drop_db(db) }.map_err(DatabaseError::Clear)?;
+            }
+            Err(libmdbx::Error::NotFound) => {}
+            Err(error) => return Err(DatabaseError::OpenDb(error)),
+        }
+
+        let db = self.create_table(table)?;
+        let (_, write_flags) = super::table_flags(table);
+
+        // The sort is stable, so the duplicates of a key stay in order.
+        let mut sorted = Ok(());
+        entries.sort_by(|(a, _), (b, _)| {
+            compare_raw_keys(table.integer_key(), a, b).unwrap_or_else(|error| {
+                sorted = Err(error);
+                Ordering::Equal
+            })
+        });
+        sorted?;
+
+        let count = entries.len();
+        let mut cursor = self.inner.cursor(&db).map_err(DatabaseError::CreateCursor)?;
+        for (written, (key, value)) in entries.into_iter().enumerate() {
+            cursor.put(&key, &value, write_flags).map_err(|error| DatabaseError::Write {
+                error,
+                table: table.name(),
+                key: key.into_boxed_slice(),
+            })?;
+            log_rebuild_progress(table, "Written", written + 1);
+        }
+
+        Ok(count)
+    }
+
+    /// Returns the value of `key` in table `T`, or inserts the value computed by `f` and returns
+    /// it if the key doesn't exist.
+    ///
+    /// `f` is only called if the key is absent. As the lookup and the insertion happen within
+    /// this transaction, no other writer can insert the key in between.
+    pub fn get_or_insert_with<T: Table>(
+        &self,
+        key: T::Key,
+        f: impl FnOnce() -> T::Value,
+    ) -> Result<T::Value, DatabaseError>
+    where
+        T::Value: Clone,
+    {
+        let key = encode_key::<T>(key)?;
+        let dbi = self.get_dbi::<T>()?;
+
+        if let Some(value) = self.inner.get(dbi, key.as_ref()).map_err(DatabaseError::Read)? {
+            return decode_one::<T>(value);
+        }
+
+        let value = f();
+        let compressed = compress_value::<T>(value.clone())?;
+        self.mark_mutated::<T>();
+        self.inner.put(dbi, key.as_ref(), compressed, WriteFlags::UPSERT).map_err(|error| {
+            DatabaseError::Write { error, table: T::NAME, key: Box::from(key.as_ref()) }
+        })?;
+
+        Ok(value)
+    }
+
+    /// Inserts an entry at the end of table `T` using `MDBX_APPEND`, which skips the search for
+    /// the position of the key.
+    ///
+    /// This is meant for building a table in a single sequential pass, e.g. when importing a
+    /// snapshot. The key must be greater than the last key of the table, otherwise
+    /// [`DatabaseError::KeyMismatch`] is returned.
+    pub fn append<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key = encode_key::<T>(key)?;
+        let value = compress_value::<T>(value)?;
+        self.mark_mutated::<T>();
+        self.inner.put(self.get_dbi::<T>()?, key.as_ref(), value, WriteFlags::APPEND).map_err(
+            |error| {
+                let key = Box::from(key.as_ref());
+                match error {
+                    libmdbx::Error::KeyMismatch => {
+                        DatabaseError::KeyMismatch { table: T::NAME, key }
+                    }
+                    error => DatabaseError::Write { error, table: T::NAME, key },
+                }
+            },
+        )
+    }
+
+    /// Same as [`Tx::put_many`], but the entries are appended at the end of table `T` using
+    /// `MDBX_APPEND`, which is faster when inserting a large number of entries.
+    ///
+    /// The keys must be sorted in ascending order and greater than the last key of the table,
+    /// otherwise the insertion fails with [`DatabaseError::Write`].
+    pub fn append_many<T: Table>(
+        &self,
+        entries: impl IntoIterator<Item = (T::Key, T::Value)>,
+    ) -> Result<usize, DatabaseError> {
+        let mut cursor = DbTxMut::cursor_mut::<T>(self)?;
+        let mut count = 0;
+
+        for (key, value) in entries {
+            cursor.append(key, value)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Deletes all the entries of table `T` whose keys are lower than `up_to`, and returns the
+    /// number of entries deleted.
+    ///
+    /// Only the entries being deleted are visited, as the walk stops at the first key greater
+    /// than or equal to `up_to`.
+    pub fn prune<T: Table<Key = BlockNumber>>(
+        &self,
+        up_to: T::Key,
+    ) -> Result<usize, DatabaseError> {
+        let mut cursor = DbTxMut::cursor_mut::<T>(self)?;
+        let mut entry = cursor.first()?;
+        let mut count = 0;
+
+        while let Some((key, _)) = entry {
+            if key >= up_to {
+                break;
+            }
+
+            cursor.delete_current()?;
+            count += 1;
+            entry = cursor.next()?;
+        }
+
+        Ok(count)
+    }
+
+    /// Inserts the entries of a dump of table `T`, as written by [`Tx::dump_table`], and returns
+    /// the number of entries inserted.
+    ///
+    /// Entries whose keys are greater than the last key of the table are appended using
+    /// `MDBX_APPEND`, so loading a dump into an empty table only takes a single sequential pass.
+    /// The other entries are upserted. Blank lines are skipped. A line that isn't a valid entry,
+    /// or whose key can't be decoded, fails the whole load with [`DatabaseError::Load`].
+    pub fn load_table<T: Table, R: BufRead>(&self, input: R) -> Result<usize, DatabaseError>
+    where
+        T::Value: DeserializeOwned,
+    {
+        let load_error =
+            |line: usize, reason: String| DatabaseError::Load { table: T::NAME, line, reason };
+
+        let mut cursor = DbTxMut::cursor_mut::<T>(self)?;
+        let mut last = cursor.last()?.map(|(key, _)| key.encode().as_ref().to_vec());
+        let mut count = 0;
+
+        for (index, line) in input.lines().enumerate() {
+            let number = index + 1;
+            let line = line.map_err(|e| load_error(number, e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: DumpEntry<T::Value> =
+                serde_json::from_str(&line).map_err(|e| load_error(number, e.to_string()))?;
+            let raw_key = hex::decode(&entry.key).map_err(|e| load_error(number, e.to_string()))?;
+
+            if let Some(len) = <T::Key as Encode>::ENCODED_LEN {
+                if raw_key.len() != len {
+                    let reason = format!("expected a {len} bytes key, got {}", raw_key.len());
+                    return Err(load_error(number, reason));
+                }
+            }
+
+            let key = <T::Key as Decode>::decode(&raw_key)
+                .map_err(|e| load_error(number, e.to_string()))?;
+
+            let past_last = match &last {
+                Some(last) => compare_keys::<T>(last, &raw_key)?.is_lt(),
+                None => true,
+            };
+
+            if past_last {
+                cursor.append(key, entry.value)?;
+                last = Some(raw_key);
+            } else {
+                cursor.upsert(key, entry.value)?;
+            }
+
+            count += 1;
+        }
+
+        Ok(count)
     }
 }
 
@@ -109,17 +679,22 @@ impl DbTxMut for Tx<RW> {
     }
 
     fn cursor_dup_mut<T: DupSort>(&self) -> Result<<Self as DbTxMut>::DupCursor<T>, DatabaseError> {
-        self.inner
-            .cursor_with_dbi(self.get_dbi::<T>()?)
-            .map(Cursor::new)
-            .map_err(DatabaseError::CreateCursor)
+        DbTx::cursor_dup(self)
     }
 
     fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        let key = key.encode();
-        let value = value.compress();
-        self.inner.put(self.get_dbi::<T>()?, key, value, WriteFlags::UPSERT).unwrap();
-        Ok(())
+        instrument::record(Operation::Put, Some(T::NAME), || {
+            let key = encode_key::<T>(key)?;
+            let value = compress_value::<T>(value)?;
+            self.mark_mutated::<T>();
+            self.inner.put(self.get_dbi::<T>()?, key.as_ref(), value, WriteFlags::UPSERT).map_err(
+                |error| DatabaseError::Write {
+                    error,
+                    table: T::NAME,
+                    key: Box::from(key.as_ref()),
+                },
+            )
+        })
     }
 
     fn delete<T: Table>(
@@ -127,12 +702,16 @@ impl DbTxMut for Tx<RW> {
         key: T::Key,
         value: Option<T::Value>,
     ) -> Result<bool, DatabaseError> {
-        let value = value.map(Compress::compress);
-        let value = value.as_ref().map(|v| v.as_ref());
-        self.inner.del(self.get_dbi::<T>()?, key.encode(), value).map_err(DatabaseError::Delete)
+        instrument::record(Operation::Delete, Some(T::NAME), || {
+            let value = value.map(Compress::compress);
+            let value = value.as_ref().map(|v| v.as_ref());
+            self.mark_mutated::<T>();
+            self.inner.del(self.get_dbi::<T>()?, key.encode(), value).map_err(DatabaseError::Delete)
+        })
     }
 
     fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.mark_mutated::<T>();
         self.inner.clear_db(self.get_dbi::<T>()?).map_err(DatabaseError::Clear)
     }
 }
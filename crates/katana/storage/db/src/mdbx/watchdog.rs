@@ -0,0 +1,120 @@
+//! Watchdog of the long running read-write transactions.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tracing::warn;
+
+const LOG_TARGET: &str = "db::watchdog";
+
+/// Reports the read-write transactions which are open for longer than `timeout`.
+///
+/// MDBX only allows a single read-write transaction at a time, so a transaction that is never
+/// committed nor aborted stalls all the writers.
+#[derive(Debug)]
+pub(crate) struct WriteWatchdog {
+    timeout: Duration,
+    /// Whether the timed out transactions must fail to commit.
+    abort: bool,
+    /// The currently open read-write transaction, if any.
+    current: Mutex<Option<ActiveWrite>>,
+    /// Number of transactions that have exceeded the timeout.
+    timed_out: AtomicU64,
+}
+
+#[derive(Debug)]
+struct ActiveWrite {
+    txn_id: u64,
+    started_at: Instant,
+    timed_out: bool,
+}
+
+impl WriteWatchdog {
+    /// Creates a new watchdog, and spawns the thread checking the open transaction. The thread
+    /// exits once the watchdog is dropped.
+    pub(crate) fn spawn(timeout: Duration, abort: bool) -> Arc<Self> {
+        let watchdog = Arc::new(Self {
+            timeout,
+            abort,
+            current: Mutex::new(None),
+            timed_out: AtomicU64::new(0),
+        });
+
+        let weak = Arc::downgrade(&watchdog);
+        let interval = (timeout / 4).clamp(Duration::from_millis(1), Duration::from_secs(1));
+
+        thread::Builder::new()
+            .name("db-write-watchdog".to_string())
+            .spawn(move || run(weak, interval))
+            .expect("failed to spawn db write watchdog thread");
+
+        watchdog
+    }
+
+    /// Starts watching the read-write transaction `txn_id` until the returned guard is dropped.
+    pub(crate) fn watch(self: &Arc<Self>, txn_id: u64) -> WriteGuard {
+        let write = ActiveWrite { txn_id, started_at: Instant::now(), timed_out: false };
+        *self.current.lock() = Some(write);
+        WriteGuard { watchdog: self.clone(), txn_id }
+    }
+
+    /// Returns the number of read-write transactions that have exceeded the timeout.
+    pub(crate) fn timed_out(&self) -> u64 {
+        self.timed_out.load(Ordering::Relaxed)
+    }
+
+    fn check(&self) {
+        let mut current = self.current.lock();
+        let Some(write) = current.as_mut() else { return };
+
+        let elapsed = write.started_at.elapsed();
+        if !write.timed_out && elapsed > self.timeout {
+            write.timed_out = true;
+            self.timed_out.fetch_add(1, Ordering::Relaxed);
+
+            warn!(
+                target: LOG_TARGET,
+                txn_id = write.txn_id,
+                elapsed = ?elapsed,
+                timeout = ?self.timeout,
+                abort = self.abort,
+                "Read-write transaction exceeded the write timeout."
+            );
+        }
+    }
+}
+
+fn run(watchdog: Weak<WriteWatchdog>, interval: Duration) {
+    loop {
+        thread::sleep(interval);
+        let Some(watchdog) = watchdog.upgrade() else { break };
+        watchdog.check();
+    }
+}
+
+/// Keeps a read-write transaction watched by the [`WriteWatchdog`].
+#[derive(Debug)]
+pub(crate) struct WriteGuard {
+    watchdog: Arc<WriteWatchdog>,
+    txn_id: u64,
+}
+
+impl WriteGuard {
+    /// Returns `true` if the transaction has timed out and must not be committed.
+    pub(crate) fn must_abort(&self) -> bool {
+        self.watchdog.abort && self.watchdog.current.lock().as_ref().is_some_and(|w| w.timed_out)
+    }
+}
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        let mut current = self.watchdog.current.lock();
+        // The next transaction may already have started once the watched one is dropped.
+        if current.as_ref().is_some_and(|w| w.txn_id == self.txn_id) {
+            current.take();
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Migrations of the database schema.
+//!
+//! The version of the schema of a database is stored in the [`SchemaVersion`] table. When the
+//! layout of the tables changes, e.g. the encoding of a value, a [`Migration`] converting the
+//! existing data from the previous version is added to [`migrations`].
+
+use libmdbx::RW;
+
+use crate::abstraction::{Database, DbTx, DbTxMut};
+use crate::error::DatabaseError;
+use crate::mdbx::tx::Tx;
+use crate::mdbx::DbEnv;
+use crate::tables::{SchemaVersion, Tables, SCHEMA_VERSION_KEY};
+
+/// A migration of the database from one schema version to the next.
+pub trait Migration {
+    /// The schema version the migration applies to.
+    fn from(&self) -> u32;
+
+    /// The schema version of the database once the migration is applied.
+    fn to(&self) -> u32;
+
+    /// Converts the existing data to the new schema.
+    fn apply(&self, tx: &Tx<RW>) -> Result<(), DatabaseError>;
+}
+
+/// The migrations of the database schema, in order.
+pub fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![Box::new(IntegerKeys)]
+}
+
+/// Migrates the tables keyed by integers to `MDBX_INTEGERKEY`, from version `3` to `4`.
+///
+/// Up to version `3`, integer keys were encoded in big-endian, so they sort in lexicographic
+/// order. Since version `4`, they're encoded in native-endian and the tables are created with
+/// `MDBX_INTEGERKEY`, which MDBX doesn't allow setting on an existing table. So the tables are
+/// recreated, and their entries written back with their keys re-encoded.
+pub struct IntegerKeys;
+
+impl Migration for IntegerKeys {
+    fn from(&self) -> u32 {
+        3
+    }
+
+    fn to(&self) -> u32 {
+        4
+    }
+
+    fn apply(&self, tx: &Tx<RW>) -> Result<(), DatabaseError> {
+        for table in Tables::ALL.into_iter().filter(Tables::integer_key) {
+            tx.rebuild_table(table, |key| {
+                let bytes =
+                    <[u8; 8]>::try_from(key).map_err(|_| DatabaseError::IntegerKey(key.len()))?;
+                Ok(u64::from_be_bytes(bytes).to_ne_bytes().to_vec())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs the migrations of a database whose schema is outdated.
+pub struct MigrationRunner {
+    migrations: Vec<Box<dyn Migration>>,
+    base_version: u32,
+}
+
+impl MigrationRunner {
+    /// Creates a runner for the given migrations. They must be ordered, each one migrating from
+    /// the version the previous one migrated to.
+    pub fn new(migrations: Vec<Box<dyn Migration>>) -> Self {
+        Self { migrations, base_version: 0 }
+    }
+
+    /// Sets the schema version of a database without a schema version, e.g. the version of its
+    /// version file. Defaults to `0`.
+    pub fn with_base_version(mut self, version: u32) -> Self {
+        self.base_version = version;
+        self
+    }
+
+    /// Applies the migrations that haven't been applied yet, and returns the resulting schema
+    /// version.
+    ///
+    /// The migrations are all applied within a single transaction, along with the update of the
+    /// schema version, so a failing migration leaves the database untouched. A database without
+    /// a schema version is considered to be at the base version, see
+    /// [`MigrationRunner::with_base_version`], which is then recorded as its schema version.
+    ///
+    /// The tables don't have to be created beforehand, as a migration may have to recreate a
+    /// table with different flags.
+    pub fn run(&self, env: &DbEnv) -> Result<u32, DatabaseError> {
+        let tx = env.tx_mut()?;
+        let stored = match tx.get::<SchemaVersion>(SCHEMA_VERSION_KEY) {
+            Ok(version) => version,
+            Err(DatabaseError::TableNotInitialized { .. }) => None,
+            Err(error) => return Err(error),
+        };
+        let mut version = stored.unwrap_or(self.base_version);
+
+        for migration in &self.migrations {
+            if migration.to() <= version {
+                continue;
+            }
+
+            if migration.from() != version {
+                return Err(DatabaseError::MissingMigration(version));
+            }
+
+            migration.apply(&tx)?;
+            version = migration.to();
+        }
+
+        // The version is also recorded when it was missing, e.g. for a new database, so that it
+        // doesn't fall back to the base version of a later run.
+        if stored != Some(version) {
+            tx.create_table(Tables::SchemaVersion)?;
+            tx.put::<SchemaVersion>(SCHEMA_VERSION_KEY, version)?;
+            tx.commit()?;
+        }
+
+        Ok(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use katana_primitives::Felt;
+
+    use super::*;
+    use crate::abstraction::{DbCursor, DbCursorMut};
+    use crate::mdbx::DbEnvKind;
+    use crate::tables::BlockHashes;
+
+    /// Doubles the values of the `BlockHashes` table.
+    struct DoubleBlockHashes {
+        applied: Arc<AtomicUsize>,
+    }
+
+    impl Migration for DoubleBlockHashes {
+        fn from(&self) -> u32 {
+            0
+        }
+
+        fn to(&self) -> u32 {
+            1
+        }
+
+        fn apply(&self, tx: &Tx<RW>) -> Result<(), DatabaseError> {
+            self.applied.fetch_add(1, Ordering::SeqCst);
+
+            let mut cursor = tx.cursor_mut::<BlockHashes>()?;
+            let mut entry = cursor.first()?;
+            while let Some((key, value)) = entry {
+                cursor.upsert(key, value + value)?;
+                entry = cursor.next()?;
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn migrations_are_applied_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let applied = Arc::new(AtomicUsize::new(0));
+        let runner =
+            || MigrationRunner::new(vec![Box::new(DoubleBlockHashes { applied: applied.clone() })]);
+
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).unwrap();
+        env.create_tables().unwrap();
+        assert_eq!(env.schema_version().unwrap(), 0);
+
+        env.update(|tx| (0..10u64).try_for_each(|key| tx.put::<BlockHashes>(key, Felt::from(key))))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(runner().run(&env).unwrap(), 1);
+        assert_eq!(env.schema_version().unwrap(), 1);
+        // MDBX doesn't allow opening the same environment twice within a process.
+        drop(env);
+
+        let env = DbEnv::open(dir.path(), DbEnvKind::RW).unwrap();
+        assert_eq!(runner().run(&env).unwrap(), 1);
+        assert_eq!(env.schema_version().unwrap(), 1);
+        assert_eq!(applied.load(Ordering::SeqCst), 1);
+
+        let tx = env.tx().unwrap();
+        for key in 0..10u64 {
+            assert_eq!(tx.get::<BlockHashes>(key).unwrap(), Some(Felt::from(key * 2)));
+        }
+    }
+
+    #[test]
+    fn fresh_database_records_its_schema_version() {
+        let env = DbEnv::open_ephemeral().unwrap();
+
+        let runner = MigrationRunner::new(migrations()).with_base_version(4);
+        assert_eq!(runner.run(&env).unwrap(), 4);
+        assert_eq!(env.schema_version().unwrap(), 4);
+
+        // The recorded version takes precedence over the base version.
+        let runner = MigrationRunner::new(migrations()).with_base_version(3);
+        assert_eq!(runner.run(&env).unwrap(), 4);
+    }
+
+    #[test]
+    fn missing_migration() {
+        let env = DbEnv::open_ephemeral().unwrap();
+        env.create_tables().unwrap();
+        env.update(|tx| tx.put::<SchemaVersion>(SCHEMA_VERSION_KEY, 2)).unwrap().unwrap();
+
+        struct FromThree;
+
+        impl Migration for FromThree {
+            fn from(&self) -> u32 {
+                3
+            }
+
+            fn to(&self) -> u32 {
+                4
+            }
+
+            fn apply(&self, _: &Tx<RW>) -> Result<(), DatabaseError> {
+                Ok(())
+            }
+        }
+
+        let runner = MigrationRunner::new(vec![Box::new(FromThree)]);
+        assert_eq!(runner.run(&env), Err(DatabaseError::MissingMigration(2)));
+        assert_eq!(env.schema_version().unwrap(), 2);
+    }
+}
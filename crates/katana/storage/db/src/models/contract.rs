@@ -11,7 +11,7 @@ pub struct ContractInfoChangeList {
     pub nonce_change_list: BlockList,
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractClassChange {
     pub contract_address: ContractAddress,
@@ -38,7 +38,7 @@ impl Decompress for ContractClassChange {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractNonceChange {
     pub contract_address: ContractAddress,
@@ -1,4 +1,5 @@
 use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
+use serde::{Deserialize, Serialize};
 
 use crate::codecs::{Compress, Decode, Decompress, Encode};
 use crate::error::CodecError;
@@ -6,7 +7,7 @@ use crate::error::CodecError;
 /// Represents a contract storage entry.
 ///
 /// `key` is the subkey for the dupsort table.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct StorageEntry {
     /// The storage key.
@@ -34,7 +35,7 @@ impl Decompress for StorageEntry {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractStorageKey {
     pub contract_address: ContractAddress,
@@ -43,6 +44,7 @@ pub struct ContractStorageKey {
 
 impl Encode for ContractStorageKey {
     type Encoded = [u8; 64];
+    const ENCODED_LEN: Option<usize> = Some(64);
     fn encode(self) -> Self::Encoded {
         let mut buf = [0u8; 64];
         buf[0..32].copy_from_slice(&self.contract_address.encode());
@@ -60,7 +62,7 @@ impl Decode for ContractStorageKey {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractStorageEntry {
     pub key: ContractStorageKey,
@@ -6,6 +6,7 @@ use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{Tx, TxHash, TxNumber};
 
 use crate::codecs::{Compress, Decode, Decompress, Encode};
+use crate::error::CodecError;
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::{ContractClassChange, ContractInfoChangeList, ContractNonceChange};
 use crate::models::list::BlockList;
@@ -42,9 +43,14 @@ pub enum TableType {
     Table,
     /// Duplicate key value table
     DupSort,
+    /// key value table whose keys are unsigned integers, created with `MDBX_INTEGERKEY`
+    IntegerKey,
 }
 
-pub const NUM_TABLES: usize = 23;
+pub const NUM_TABLES: usize = 24;
+
+/// The only key of the [`SchemaVersion`] table.
+pub const SCHEMA_VERSION_KEY: u64 = 0;
 
 /// Macro to declare `libmdbx` tables.
 #[macro_export]
@@ -63,7 +69,7 @@ macro_rules! define_tables_enum {
             pub const ALL: [Tables; NUM_TABLES] = [$(Tables::$table,)*];
 
             /// The name of the given table in database
-            pub const fn name(&self) -> &str {
+            pub const fn name(&self) -> &'static str {
                 match self {
                     $(Tables::$table => {
                         $table::NAME
@@ -79,8 +85,61 @@ macro_rules! define_tables_enum {
                     },)*
                 }
             }
+
+            /// Whether the keys of the given table are unsigned integers, compared with MDBX's
+            /// integer comparator. This is the case of all [`TableType::IntegerKey`] tables, but
+            /// also of the [`TableType::DupSort`] tables with integer keys.
+            pub const fn integer_key(&self) -> bool {
+                match self {
+                    $(Tables::$table => {
+                        <<$table as Table>::Key as Encode>::INTEGER_KEY
+                    },)*
+                }
+            }
+
+            /// Decodes a raw key/value pair of the given table with the codecs of the table, to
+            /// check that it is valid.
+            pub fn decode_raw(&self, key: &[u8], value: &[u8]) -> Result<(), CodecError> {
+                match self {
+                    $(Tables::$table => {
+                        <<$table as Table>::Key as Decode>::decode(key)?;
+                        <<$table as Table>::Value as Decompress>::decompress(value)?;
+                        Ok(())
+                    },)*
+                }
+            }
+
+            /// Dumps the given table with [`Tx::dump_table`](crate::mdbx::tx::Tx::dump_table).
+            pub fn dump<K: libmdbx::TransactionKind, W: std::io::Write>(
+                &self,
+                tx: &crate::mdbx::tx::Tx<K>,
+                out: &mut W,
+            ) -> Result<usize, crate::error::DatabaseError> {
+                match self {
+                    $(Tables::$table => tx.dump_table::<$table, W>(out),)*
+                }
+            }
         }
 
+        // Statically requires the keys and values of every table to implement the codecs, so that
+        // a missing impl fails the build here rather than where the table is first used.
+        const _: () = {
+            const fn assert_codecs<T: Table>()
+            where
+                T::Key: Encode + Decode,
+                T::Value: Compress + Decompress,
+            {
+            }
+
+            $(assert_codecs::<$table>();)*
+
+            // Integer key tables must have keys encoded as native integers.
+            $(assert!(
+                !matches!($type, TableType::IntegerKey)
+                    || <<$table as Table>::Key as Encode>::INTEGER_KEY
+            );)*
+        };
+
         impl std::fmt::Display for Tables {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(f, "{}", self.name())
@@ -145,17 +204,17 @@ macro_rules! dupsort {
 }
 
 define_tables_enum! {[
-    (Headers, TableType::Table),
-    (BlockHashes, TableType::Table),
+    (Headers, TableType::IntegerKey),
+    (BlockHashes, TableType::IntegerKey),
     (BlockNumbers, TableType::Table),
-    (BlockBodyIndices, TableType::Table),
-    (BlockStatusses, TableType::Table),
+    (BlockBodyIndices, TableType::IntegerKey),
+    (BlockStatusses, TableType::IntegerKey),
     (TxNumbers, TableType::Table),
-    (TxBlocks, TableType::Table),
-    (TxHashes, TableType::Table),
-    (TxTraces, TableType::Table),
-    (Transactions, TableType::Table),
-    (Receipts, TableType::Table),
+    (TxBlocks, TableType::IntegerKey),
+    (TxHashes, TableType::IntegerKey),
+    (TxTraces, TableType::IntegerKey),
+    (Transactions, TableType::IntegerKey),
+    (Receipts, TableType::IntegerKey),
     (CompiledClassHashes, TableType::Table),
     (CompiledClasses, TableType::Table),
     (SierraClasses, TableType::Table),
@@ -167,7 +226,8 @@ define_tables_enum! {[
     (NonceChangeHistory, TableType::DupSort),
     (ClassChangeHistory, TableType::DupSort),
     (StorageChangeHistory, TableType::DupSort),
-    (StorageChangeSet, TableType::Table)
+    (StorageChangeSet, TableType::Table),
+    (SchemaVersion, TableType::IntegerKey)
 ]}
 
 tables! {
@@ -223,7 +283,10 @@ tables! {
     /// storage change set
     StorageChangeSet: (ContractStorageKey) => BlockList,
     /// Account storage change set
-    StorageChangeHistory: (BlockNumber, ContractStorageKey) => ContractStorageEntry
+    StorageChangeHistory: (BlockNumber, ContractStorageKey) => ContractStorageEntry,
+
+    /// Stores the version of the database schema, under the [`SCHEMA_VERSION_KEY`] key.
+    SchemaVersion: (u64) => u32
 
 }
 
@@ -258,18 +321,19 @@ mod tests {
         assert_eq!(Tables::ALL[20].name(), ClassChangeHistory::NAME);
         assert_eq!(Tables::ALL[21].name(), StorageChangeHistory::NAME);
         assert_eq!(Tables::ALL[22].name(), StorageChangeSet::NAME);
+        assert_eq!(Tables::ALL[23].name(), SchemaVersion::NAME);
 
-        assert_eq!(Tables::Headers.table_type(), TableType::Table);
-        assert_eq!(Tables::BlockHashes.table_type(), TableType::Table);
+        assert_eq!(Tables::Headers.table_type(), TableType::IntegerKey);
+        assert_eq!(Tables::BlockHashes.table_type(), TableType::IntegerKey);
         assert_eq!(Tables::BlockNumbers.table_type(), TableType::Table);
-        assert_eq!(Tables::BlockBodyIndices.table_type(), TableType::Table);
-        assert_eq!(Tables::BlockStatusses.table_type(), TableType::Table);
+        assert_eq!(Tables::BlockBodyIndices.table_type(), TableType::IntegerKey);
+        assert_eq!(Tables::BlockStatusses.table_type(), TableType::IntegerKey);
         assert_eq!(Tables::TxNumbers.table_type(), TableType::Table);
-        assert_eq!(Tables::TxBlocks.table_type(), TableType::Table);
-        assert_eq!(Tables::TxHashes.table_type(), TableType::Table);
-        assert_eq!(Tables::TxTraces.table_type(), TableType::Table);
-        assert_eq!(Tables::Transactions.table_type(), TableType::Table);
-        assert_eq!(Tables::Receipts.table_type(), TableType::Table);
+        assert_eq!(Tables::TxBlocks.table_type(), TableType::IntegerKey);
+        assert_eq!(Tables::TxHashes.table_type(), TableType::IntegerKey);
+        assert_eq!(Tables::TxTraces.table_type(), TableType::IntegerKey);
+        assert_eq!(Tables::Transactions.table_type(), TableType::IntegerKey);
+        assert_eq!(Tables::Receipts.table_type(), TableType::IntegerKey);
         assert_eq!(Tables::CompiledClassHashes.table_type(), TableType::Table);
         assert_eq!(Tables::CompiledClasses.table_type(), TableType::Table);
         assert_eq!(Tables::SierraClasses.table_type(), TableType::Table);
@@ -282,6 +346,7 @@ mod tests {
         assert_eq!(Tables::ClassChangeHistory.table_type(), TableType::DupSort);
         assert_eq!(Tables::StorageChangeHistory.table_type(), TableType::DupSort);
         assert_eq!(Tables::StorageChangeSet.table_type(), TableType::Table);
+        assert_eq!(Tables::SchemaVersion.table_type(), TableType::IntegerKey);
     }
 
     use katana_primitives::address;
@@ -328,6 +393,51 @@ mod tests {
 		};
 	}
 
+    // Instantiates the write and read codec paths of every table
+    #[test]
+    fn test_tables_codecs() {
+        use super::*;
+
+        fn codec_path<T: Table>() -> &'static str {
+            let _ = crate::utils::encode_key::<T>;
+            let _ = crate::utils::compress_value::<T>;
+            let _ = crate::utils::decoder::<T>;
+            T::NAME
+        }
+
+        let tables = [
+            codec_path::<Headers>(),
+            codec_path::<BlockHashes>(),
+            codec_path::<BlockNumbers>(),
+            codec_path::<BlockBodyIndices>(),
+            codec_path::<BlockStatusses>(),
+            codec_path::<TxNumbers>(),
+            codec_path::<TxBlocks>(),
+            codec_path::<TxHashes>(),
+            codec_path::<TxTraces>(),
+            codec_path::<Transactions>(),
+            codec_path::<Receipts>(),
+            codec_path::<CompiledClassHashes>(),
+            codec_path::<CompiledClasses>(),
+            codec_path::<SierraClasses>(),
+            codec_path::<ContractInfo>(),
+            codec_path::<ContractStorage>(),
+            codec_path::<ClassDeclarationBlock>(),
+            codec_path::<ClassDeclarations>(),
+            codec_path::<ContractInfoChangeSet>(),
+            codec_path::<NonceChangeHistory>(),
+            codec_path::<ClassChangeHistory>(),
+            codec_path::<StorageChangeHistory>(),
+            codec_path::<StorageChangeSet>(),
+            codec_path::<SchemaVersion>(),
+        ];
+
+        assert_eq!(tables.len(), NUM_TABLES);
+        for (table, name) in Tables::ALL.iter().zip(tables) {
+            assert_eq!(table.name(), name);
+        }
+    }
+
     // Test that all key/subkey types can be encoded and decoded
     // through the Encode and Decode traits
     #[test]
@@ -1,14 +1,17 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::path::Path;
 
-use crate::codecs::{Decode, Decompress};
+use crate::codecs::{Compress, Decode, Decompress, Encode};
 use crate::error::DatabaseError;
 use crate::tables::Table;
 
 // May lead to errors if it's reduced further because of the potential size of the data.
 const MIN_PAGE_SIZE: usize = 4096;
+// source: `MDBX_MIN_PAGESIZE` in mdbx.h
+pub(crate) const LIBMDBX_MIN_PAGE_SIZE: usize = 256;
 // source: https://gitflic.ru/project/erthink/libmdbx/blob?file=mdbx.h#line-num-821
-const LIBMDBX_MAX_PAGE_SIZE: usize = 65536;
+pub(crate) const LIBMDBX_MAX_PAGE_SIZE: usize = 65536;
 
 /// Returns the default page size (in bytes) that can be used in this OS.
 pub(crate) fn default_page_size() -> usize {
@@ -31,6 +34,64 @@ pub fn is_database_empty<P: AsRef<Path>>(path: P) -> bool {
     }
 }
 
+/// Encodes a key of table `T`, ensuring it has the length declared by the key type.
+pub(crate) fn encode_key<T: Table>(
+    key: T::Key,
+) -> Result<<T::Key as Encode>::Encoded, DatabaseError> {
+    let encoded = key.encode();
+    check_len::<T>(<T::Key as Encode>::ENCODED_LEN, encoded.as_ref())?;
+    Ok(encoded)
+}
+
+/// Compares two encoded keys of table `T`, in the order they are sorted in the table.
+pub(crate) fn compare_keys<T: Table>(a: &[u8], b: &[u8]) -> Result<Ordering, DatabaseError> {
+    compare_raw_keys(<T::Key as Encode>::INTEGER_KEY, a, b)
+}
+
+/// Compares two encoded keys, with MDBX's integer comparator if `integer_key` is set, or
+/// lexicographically otherwise.
+///
+/// MDBX only accepts integer keys of 4 or 8 bytes, [`DatabaseError::IntegerKey`] is returned for
+/// the other lengths.
+pub(crate) fn compare_raw_keys(
+    integer_key: bool,
+    a: &[u8],
+    b: &[u8],
+) -> Result<Ordering, DatabaseError> {
+    if integer_key {
+        Ok(integer_key_value(a)?.cmp(&integer_key_value(b)?))
+    } else {
+        Ok(a.cmp(b))
+    }
+}
+
+/// Reads an integer key, encoded in native endianness.
+fn integer_key_value(bytes: &[u8]) -> Result<u64, DatabaseError> {
+    match bytes.len() {
+        4 => Ok(u32::from_ne_bytes(bytes.try_into().expect("4 bytes; qed")).into()),
+        8 => Ok(u64::from_ne_bytes(bytes.try_into().expect("8 bytes; qed"))),
+        len => Err(DatabaseError::IntegerKey(len)),
+    }
+}
+
+/// Compresses a value of table `T`, ensuring it has the length declared by the value type.
+pub(crate) fn compress_value<T: Table>(
+    value: T::Value,
+) -> Result<<T::Value as Compress>::Compressed, DatabaseError> {
+    let compressed = value.compress();
+    check_len::<T>(<T::Value as Compress>::COMPRESSED_LEN, compressed.as_ref())?;
+    Ok(compressed)
+}
+
+fn check_len<T: Table>(expected: Option<usize>, bytes: &[u8]) -> Result<(), DatabaseError> {
+    match expected {
+        Some(expected) if expected != bytes.len() => {
+            Err(DatabaseError::Encode { table: T::NAME, expected, actual: bytes.len() })
+        }
+        _ => Ok(()),
+    }
+}
+
 /// A key-value pair for table `T`.
 pub type KeyValue<T> = (<T as Table>::Key, <T as Table>::Value);
 
@@ -5,7 +5,7 @@ use std::mem;
 use std::path::{Path, PathBuf};
 
 /// Current version of the database.
-pub const CURRENT_DB_VERSION: u32 = 3;
+pub const CURRENT_DB_VERSION: u32 = 4;
 
 /// Name of the version file.
 const DB_VERSION_FILE_NAME: &str = "db.version";
@@ -46,6 +46,20 @@ pub(super) fn create_db_version_file(
     file.write_all(&version.to_be_bytes()).map_err(DatabaseVersionError::Io)
 }
 
+/// Replace the version file at the given `path` with one of the specified `version`, e.g. once
+/// the database is migrated. The `path` is resolved as in [`create_db_version_file`].
+pub(super) fn update_db_version_file(
+    path: impl AsRef<Path>,
+    version: u32,
+) -> Result<(), DatabaseVersionError> {
+    let path = path.as_ref();
+    let path = if path.is_dir() { default_version_file_path(path) } else { path.to_path_buf() };
+
+    // The file is read-only, but that doesn't prevent removing it.
+    fs::remove_file(&path)?;
+    create_db_version_file(path, version)
+}
+
 /// Check the version of the database at the given `path`.
 ///
 /// Returning `Ok` if the version matches with [`CURRENT_DB_VERSION`], otherwise `Err` is returned.
@@ -81,6 +95,6 @@ mod tests {
     #[test]
     fn test_current_version() {
         use super::CURRENT_DB_VERSION;
-        assert_eq!(CURRENT_DB_VERSION, 3, "Invalid current database version")
+        assert_eq!(CURRENT_DB_VERSION, 4, "Invalid current database version")
     }
 }
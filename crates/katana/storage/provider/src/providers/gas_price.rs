@@ -0,0 +1,121 @@
+use katana_primitives::block::BlockHashOrNumber;
+use katana_primitives::env::BlockEnv;
+
+use crate::traits::block::BlockNumberProvider;
+use crate::traits::env::{BlockEnvProvider, GasPriceProvider};
+use crate::ProviderResult;
+
+/// A provider decorator which prices the pending block using a [`GasPriceProvider`].
+///
+/// The environment of the stored blocks is returned as is, only the environment of the pending
+/// block takes its gas prices from the L1 price source instead of the latest stored block.
+#[derive(Debug)]
+pub struct L1GasPricedProvider<P, G> {
+    provider: P,
+    gas_prices: G,
+}
+
+impl<P, G> L1GasPricedProvider<P, G> {
+    /// Creates a new [`L1GasPricedProvider`] wrapping `provider`.
+    pub fn new(provider: P, gas_prices: G) -> Self {
+        Self { provider, gas_prices }
+    }
+
+    /// Returns the wrapped provider.
+    pub fn inner(&self) -> &P {
+        &self.provider
+    }
+}
+
+impl<P, G> L1GasPricedProvider<P, G>
+where
+    P: BlockNumberProvider + BlockEnvProvider,
+    G: GasPriceProvider,
+{
+    /// Returns the environment of the pending block, built on top of the latest block.
+    ///
+    /// The block number is the one following the latest block, and the gas prices are the ones
+    /// returned by the L1 price source. The other values are inherited from the latest block.
+    pub fn pending_block_env(&self) -> ProviderResult<Option<BlockEnv>> {
+        let latest = self.provider.latest_number()?;
+        let Some(env) = self.provider.block_env_at(BlockHashOrNumber::Num(latest))? else {
+            return Ok(None);
+        };
+
+        Ok(Some(BlockEnv {
+            number: env.number + 1,
+            l1_gas_prices: self.gas_prices.l1_gas_prices()?,
+            l1_data_gas_prices: self.gas_prices.l1_data_gas_prices()?,
+            ..env
+        }))
+    }
+}
+
+impl<P, G> BlockEnvProvider for L1GasPricedProvider<P, G>
+where
+    P: BlockEnvProvider,
+    G: Send + Sync,
+{
+    fn block_env_at(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<BlockEnv>> {
+        self.provider.block_env_at(block_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::block::{
+        Block, FinalityStatus, GasPrices, Header, SealedBlockWithStatus,
+    };
+
+    use super::*;
+    use crate::providers::db::DbProvider;
+    use crate::traits::block::BlockWriter;
+
+    struct MockL1GasPrices;
+
+    impl GasPriceProvider for MockL1GasPrices {
+        fn l1_gas_prices(&self) -> ProviderResult<GasPrices> {
+            Ok(GasPrices { eth: 111, strk: 222 })
+        }
+
+        fn l1_data_gas_prices(&self) -> ProviderResult<GasPrices> {
+            Ok(GasPrices { eth: 333, strk: 444 })
+        }
+    }
+
+    #[test]
+    fn pending_env_uses_l1_gas_prices() {
+        let provider = DbProvider::new_ephemeral();
+
+        let header = Header {
+            number: 0,
+            timestamp: 100,
+            l1_gas_prices: GasPrices { eth: 1, strk: 2 },
+            l1_data_gas_prices: GasPrices { eth: 3, strk: 4 },
+            ..Default::default()
+        };
+        let block = Block { header, body: Vec::new() }.seal();
+        let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
+        provider
+            .insert_block_with_states_and_receipts(
+                block,
+                Default::default(),
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap();
+
+        let provider = L1GasPricedProvider::new(provider, MockL1GasPrices);
+
+        // Stored blocks are untouched.
+        let stored = provider.block_env_at(BlockHashOrNumber::Num(0)).unwrap().unwrap();
+        assert_eq!(stored.l1_gas_prices, GasPrices { eth: 1, strk: 2 });
+        assert_eq!(stored.l1_data_gas_prices, GasPrices { eth: 3, strk: 4 });
+
+        let pending = provider.pending_block_env().unwrap().unwrap();
+        assert_eq!(pending.number, 1);
+        assert_eq!(pending.timestamp, 100);
+        assert_eq!(pending.l1_gas_prices, GasPrices { eth: 111, strk: 222 });
+        assert_eq!(pending.l1_data_gas_prices, GasPrices { eth: 333, strk: 444 });
+    }
+}
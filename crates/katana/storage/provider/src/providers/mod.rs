@@ -1,5 +1,6 @@
 pub mod db;
 #[cfg(feature = "fork")]
 pub mod fork;
+pub mod gas_price;
 #[cfg(feature = "in-memory")]
 pub mod in_memory;
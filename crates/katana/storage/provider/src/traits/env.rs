@@ -1,4 +1,4 @@
-use katana_primitives::block::BlockHashOrNumber;
+use katana_primitives::block::{BlockHashOrNumber, GasPrices};
 use katana_primitives::env::BlockEnv;
 
 use crate::ProviderResult;
@@ -10,3 +10,13 @@ pub trait BlockEnvProvider: Send + Sync {
     /// Returns the block environment values at the given block id.
     fn block_env_at(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<BlockEnv>>;
 }
+
+/// A source of the current L1 gas prices, e.g. derived from the base fees observed on L1.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait GasPriceProvider: Send + Sync {
+    /// Returns the current L1 gas prices.
+    fn l1_gas_prices(&self) -> ProviderResult<GasPrices>;
+
+    /// Returns the current L1 data gas prices.
+    fn l1_data_gas_prices(&self) -> ProviderResult<GasPrices>;
+}
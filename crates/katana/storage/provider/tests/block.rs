@@ -1,3 +1,6 @@
+use std::sync::Arc;
+use std::thread;
+
 use anyhow::Result;
 use katana_primitives::block::{
     Block, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus,
@@ -272,6 +275,70 @@ where
     Ok(())
 }
 
+/// The [`BlockEnvProvider`] trait is `Send + Sync` and is commonly shared behind an `Arc`. Each call
+/// on the database-backed provider opens its own read-only transaction, so concurrent callers must
+/// not interfere with each other nor exhaust the MDBX reader slots.
+#[rstest::rstest]
+fn concurrent_block_env_reads_with_db_provider(
+    #[from(db_provider)] provider: BlockchainProvider<DbProvider>,
+) -> Result<()> {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 100;
+
+    let blocks = utils::generate_dummy_blocks_empty(10);
+    for block in &blocks {
+        provider.insert_block_with_states_and_receipts(
+            block.clone(),
+            Default::default(),
+            vec![],
+            vec![],
+        )?;
+    }
+
+    let expected = blocks
+        .iter()
+        .map(|block| BlockEnv {
+            number: block.block.header.number,
+            timestamp: block.block.header.timestamp,
+            l1_gas_prices: block.block.header.l1_gas_prices.clone(),
+            l1_data_gas_prices: block.block.header.l1_data_gas_prices.clone(),
+            sequencer_address: block.block.header.sequencer_address,
+        })
+        .collect::<Vec<_>>();
+
+    let provider: Arc<dyn BlockEnvProvider> = Arc::new(provider);
+    let expected = Arc::new(expected);
+
+    let handles = (0..THREADS)
+        .map(|_| {
+            let provider = Arc::clone(&provider);
+            let expected = Arc::clone(&expected);
+
+            thread::spawn(move || -> Result<()> {
+                for i in 0..ITERATIONS {
+                    let env = &expected[i % expected.len()];
+                    let actual = provider.block_env_at(BlockHashOrNumber::Num(env.number))?;
+                    assert_eq!(actual.as_ref(), Some(env));
+                }
+                Ok(())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        handle.join().expect("reader thread panicked")?;
+    }
+
+    // Make sure the provider is still usable after all readers have finished.
+    let latest = expected.last().expect("blocks were inserted");
+    assert_eq!(
+        provider.block_env_at(BlockHashOrNumber::Num(latest.number))?.as_ref(),
+        Some(latest)
+    );
+
+    Ok(())
+}
+
 #[apply(test_read_state_update)]
 fn test_read_state_update_with_fork_provider(
     #[with(fork_provider_with_spawned_fork_network::default())] provider: BlockchainProvider<